@@ -0,0 +1,43 @@
+use crate::files::BINARY_STORE;
+use leptos::prelude::*;
+use log::error;
+
+#[component]
+pub fn Checksec() -> impl IntoView {
+    #[server]
+    // Fetch the hardening report of the current binary
+    pub async fn fetch_checksec() -> Result<Vec<(String, String)>, ServerFnError> {
+        match BINARY_STORE.write() {
+            Ok(mut binary_lock) => Ok(binary_lock.checksec()?),
+            Err(e) => {
+                error!("Failed to get binary lock: {:#?}", e);
+                Err(ServerFnError::from(e))
+            }
+        }
+    }
+
+    let checksec = Resource::new(|| (), |_| async move { fetch_checksec().await });
+    checksec.refetch();
+
+    view! {
+        <Suspense fallback=|| view!{ <p>"Loading..."</p> } >
+            <div>
+                <h3>"Security hardening"</h3>
+                <table>
+                    <tbody>
+                        <tr><th><strong>Property</strong></th><th><strong>Status</strong></th></tr>
+                        {move || {
+                            checksec.get().map(
+                                |info| {
+                                    {info.unwrap_or_default().into_iter()
+                                        .map(|(key, value)| view! { <tr><td>{key}</td><td>{value}</td></tr>})
+                                        .collect::<Vec<_>>()}
+                                }
+                            )
+                        }}
+                    </tbody>
+                </table>
+            </div>
+       </Suspense>
+    }
+}
@@ -8,7 +8,7 @@ pub fn FileInfo() -> impl IntoView {
     // Update the file info
     pub async fn update_file_info() -> Result<Vec<(String, String)>, ServerFnError> {
         match BINARY_STORE.write() {
-            Ok(binary_lock) => {
+            Ok(mut binary_lock) => {
                 let file_info = binary_lock.file_info();
                 // send event to update the view
                 return Ok(file_info);
@@ -7,13 +7,24 @@ use leptos_router::{
     StaticSegment,
 };
 use log::info;
+use rubilib::table::RowAction;
 
+mod checksec;
 pub mod error_template;
 mod file_info;
 mod files;
+mod members;
+mod overlay;
+mod slices;
 
+use checksec::Checksec;
 use file_info::FileInfo;
 use files::FileUpload;
+use members::MemberSelect;
+#[cfg(feature = "ssr")]
+pub use overlay::overlay_data;
+use overlay::OverlayInfo;
+use slices::SliceSelect;
 
 use serde::{Deserialize, Serialize};
 use std::ops::DerefMut;
@@ -59,6 +70,9 @@ pub fn App() -> impl IntoView {
                     <ParentRoute path=StaticSegment("") view=HomePage>
                         <Route path=StaticSegment("elf") view=ElfPage/>
                         <Route path=StaticSegment("pe") view=PePage/>
+                        <Route path=StaticSegment("coff") view=CoffPage/>
+                        <Route path=StaticSegment("macho") view=MachOPage/>
+                        <Route path=StaticSegment("archive") view=ArchivePage/>
                         <Route path=StaticSegment("unknown") view=UnknownPage/>
                          <Route path=StaticSegment("") view=|| view!{
                              <p>"Load a file to start analyzing"</p>} />
@@ -85,17 +99,36 @@ pub enum ElfTable {
     Symbols,
     DynSymbols,
     SectionHeaders,
+    ProgramHeaders,
+    SegmentMapping,
+    Dynamic,
+    Relocations,
+    Plt,
+    Notes,
+    VersionNeeds,
+    VersionDefinitions,
 }
 
 #[server]
-pub async fn fetch_elf_table(table_type: ElfTable) -> Result<rubilib::table::Table, ServerFnError> {
+pub async fn fetch_elf_table(
+    table_type: ElfTable,
+    demangle: bool,
+) -> Result<rubilib::table::Table, ServerFnError> {
     let mut binary = files::BINARY_STORE.write().unwrap();
     match binary.deref_mut() {
         rubilib::binary::Binary::Elf(elf_binary) => {
             let table = match table_type {
                 ElfTable::SectionHeaders => elf_binary.section_headers_table()?,
-                ElfTable::Symbols => elf_binary.symbols_table()?,
-                ElfTable::DynSymbols => elf_binary.dyn_symbols_table()?,
+                ElfTable::ProgramHeaders => elf_binary.program_headers_table()?,
+                ElfTable::SegmentMapping => elf_binary.section_to_segment_table()?,
+                ElfTable::Symbols => elf_binary.symbols_table(demangle)?,
+                ElfTable::DynSymbols => elf_binary.dyn_symbols_table(demangle)?,
+                ElfTable::Dynamic => elf_binary.dynamic_table()?,
+                ElfTable::Relocations => elf_binary.relocations_table()?,
+                ElfTable::Plt => elf_binary.plt_table()?,
+                ElfTable::Notes => elf_binary.notes_table()?,
+                ElfTable::VersionNeeds => elf_binary.version_needs_table()?,
+                ElfTable::VersionDefinitions => elf_binary.version_definitions_table()?,
             };
             Ok(table)
         }
@@ -111,11 +144,17 @@ pub async fn fetch_elf_table(table_type: ElfTable) -> Result<rubilib::table::Tab
 fn ElfPage() -> impl IntoView {
     info!("display elf table");
     let (tab, set_tab) = signal(ElfTable::SectionHeaders);
-    let table = Resource::new(tab, |tab| async move { fetch_elf_table(tab).await });
+    let (demangle, set_demangle) = signal(true);
+    let table = Resource::new(
+        move || (tab(), demangle()),
+        |(tab, demangle)| async move { fetch_elf_table(tab, demangle).await },
+    );
 
     view! {
         <h2>"Analyzing ELF file"</h2>
         <FileInfo/>
+        <Checksec/>
+        <OverlayInfo/>
         <span class="tabs">
             <button
                 on:click=move |_| set_tab(ElfTable::SectionHeaders)
@@ -124,6 +163,20 @@ fn ElfPage() -> impl IntoView {
             >
                 "Section Headers"
             </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::ProgramHeaders)
+                class:selected=move || tab() == ElfTable::ProgramHeaders
+                class="tab"
+            >
+                "Segments"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::SegmentMapping)
+                class:selected=move || tab() == ElfTable::SegmentMapping
+                class="tab"
+            >
+                "Segment Mapping"
+            </button>
             <button
                 on:click=move |_| set_tab(ElfTable::Symbols)
                 class:selected=move || tab() == ElfTable::Symbols
@@ -138,17 +191,499 @@ fn ElfPage() -> impl IntoView {
             >
                 "Dynamic Symbols"
             </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::Dynamic)
+                class:selected=move || tab() == ElfTable::Dynamic
+                class="tab"
+            >
+                "Dynamic"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::Relocations)
+                class:selected=move || tab() == ElfTable::Relocations
+                class="tab"
+            >
+                "Relocations"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::Plt)
+                class:selected=move || tab() == ElfTable::Plt
+                class="tab"
+            >
+                "PLT"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::Notes)
+                class:selected=move || tab() == ElfTable::Notes
+                class="tab"
+            >
+                "Notes"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::VersionNeeds)
+                class:selected=move || tab() == ElfTable::VersionNeeds
+                class="tab"
+            >
+                "Version Needs"
+            </button>
+            <button
+                on:click=move |_| set_tab(ElfTable::VersionDefinitions)
+                class:selected=move || tab() == ElfTable::VersionDefinitions
+                class="tab"
+            >
+                "Version Definitions"
+            </button>
         </span>
+        <label>
+            <input
+                type="checkbox"
+                prop:checked=demangle
+                on:change=move |ev| set_demangle(event_target_checked(&ev))
+            />
+            "Demangle symbol names"
+        </label>
         <Table table/>
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PeTable {
+    SectionHeaders,
+    Imports,
+    Exports,
+    Resources,
+    ResourceStrings,
+    Manifest,
+    Signatures,
+    BaseRelocations,
+    Tls,
+    LoadConfig,
+    Debug,
+    RichHeader,
+    DotNet,
+    Exceptions,
+}
+
+#[server]
+pub async fn fetch_pe_table(table_type: PeTable) -> Result<rubilib::table::Table, ServerFnError> {
+    let mut binary = files::BINARY_STORE.write().unwrap();
+    match binary.deref_mut() {
+        rubilib::binary::Binary::Pe(pe_binary) => {
+            let table = match table_type {
+                PeTable::SectionHeaders => pe_binary.section_headers_table()?,
+                PeTable::Imports => pe_binary.imports_table()?,
+                PeTable::Exports => pe_binary.exports_table()?,
+                PeTable::Resources => pe_binary.resources_table()?,
+                PeTable::ResourceStrings => pe_binary.resource_strings_table()?,
+                PeTable::Manifest => pe_binary.manifest_table()?,
+                PeTable::Signatures => pe_binary.signatures_table()?,
+                PeTable::BaseRelocations => pe_binary.base_relocations_table()?,
+                PeTable::Tls => pe_binary.tls_table()?,
+                PeTable::LoadConfig => pe_binary.load_config_table()?,
+                PeTable::Debug => pe_binary.debug_table()?,
+                PeTable::RichHeader => pe_binary.rich_header_table()?,
+                PeTable::DotNet => pe_binary.dotnet_table()?,
+                PeTable::Exceptions => pe_binary.exceptions_table()?,
+            };
+            Ok(table)
+        }
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
 /// Renders the home page of your application.
 #[component]
 fn PePage() -> impl IntoView {
+    info!("display pe table");
+    let (tab, set_tab) = signal(PeTable::SectionHeaders);
+    let table = Resource::new(tab, |tab| async move { fetch_pe_table(tab).await });
+
     view! {
         <h2>"Analyzing PE file"</h2>
         <FileInfo/>
+        <Checksec/>
+        <OverlayInfo/>
+        <span class="tabs">
+            <button
+                on:click=move |_| set_tab(PeTable::SectionHeaders)
+                class:selected=move || tab() == PeTable::SectionHeaders
+                class="tab"
+            >
+                "Sections"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Imports)
+                class:selected=move || tab() == PeTable::Imports
+                class="tab"
+            >
+                "Imports"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Exports)
+                class:selected=move || tab() == PeTable::Exports
+                class="tab"
+            >
+                "Exports"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Resources)
+                class:selected=move || tab() == PeTable::Resources
+                class="tab"
+            >
+                "Resources"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::ResourceStrings)
+                class:selected=move || tab() == PeTable::ResourceStrings
+                class="tab"
+            >
+                "Strings"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Manifest)
+                class:selected=move || tab() == PeTable::Manifest
+                class="tab"
+            >
+                "Manifest"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Signatures)
+                class:selected=move || tab() == PeTable::Signatures
+                class="tab"
+            >
+                "Signatures"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::BaseRelocations)
+                class:selected=move || tab() == PeTable::BaseRelocations
+                class="tab"
+            >
+                "Relocations"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Tls)
+                class:selected=move || tab() == PeTable::Tls
+                class="tab"
+            >
+                "TLS"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::LoadConfig)
+                class:selected=move || tab() == PeTable::LoadConfig
+                class="tab"
+            >
+                "Load Config"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Debug)
+                class:selected=move || tab() == PeTable::Debug
+                class="tab"
+            >
+                "Debug"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::RichHeader)
+                class:selected=move || tab() == PeTable::RichHeader
+                class="tab"
+            >
+                "Rich Header"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::DotNet)
+                class:selected=move || tab() == PeTable::DotNet
+                class="tab"
+            >
+                ".NET"
+            </button>
+            <button
+                on:click=move |_| set_tab(PeTable::Exceptions)
+                class:selected=move || tab() == PeTable::Exceptions
+                class="tab"
+            >
+                "Exceptions"
+            </button>
+        </span>
+        <Table table/>
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum CoffTable {
+    SectionHeaders,
+    Relocations,
+    Symbols,
+    Strings,
+}
+
+#[server]
+pub async fn fetch_coff_table(
+    table_type: CoffTable,
+    demangle: bool,
+) -> Result<rubilib::table::Table, ServerFnError> {
+    let mut binary = files::BINARY_STORE.write().unwrap();
+    match binary.deref_mut() {
+        rubilib::binary::Binary::Coff(coff_object) => {
+            let table = match table_type {
+                CoffTable::SectionHeaders => coff_object.section_headers_table()?,
+                CoffTable::Relocations => coff_object.relocations_table()?,
+                CoffTable::Symbols => coff_object.symbols_table(demangle)?,
+                CoffTable::Strings => coff_object.strings_table()?,
+            };
+            Ok(table)
+        }
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+/// Section, relocation, symbol and string tables of a COFF object file
+#[component]
+fn CoffPage() -> impl IntoView {
+    info!("display coff table");
+    let (tab, set_tab) = signal(CoffTable::SectionHeaders);
+    let (demangle, set_demangle) = signal(true);
+    let table = Resource::new(
+        move || (tab(), demangle()),
+        |(tab, demangle)| async move { fetch_coff_table(tab, demangle).await },
+    );
+
+    view! {
+        <h2>"Analyzing COFF object file"</h2>
+        <FileInfo/>
+        <span class="tabs">
+            <button
+                on:click=move |_| set_tab(CoffTable::SectionHeaders)
+                class:selected=move || tab() == CoffTable::SectionHeaders
+                class="tab"
+            >
+                "Sections"
+            </button>
+            <button
+                on:click=move |_| set_tab(CoffTable::Relocations)
+                class:selected=move || tab() == CoffTable::Relocations
+                class="tab"
+            >
+                "Relocations"
+            </button>
+            <button
+                on:click=move |_| set_tab(CoffTable::Symbols)
+                class:selected=move || tab() == CoffTable::Symbols
+                class="tab"
+            >
+                "Symbols"
+            </button>
+            <button
+                on:click=move |_| set_tab(CoffTable::Strings)
+                class:selected=move || tab() == CoffTable::Strings
+                class="tab"
+            >
+                "Strings"
+            </button>
+        </span>
+        <label>
+            <input
+                type="checkbox"
+                prop:checked=demangle
+                on:change=move |ev| set_demangle(event_target_checked(&ev))
+            />
+            "Demangle symbol names"
+        </label>
+        <Table table/>
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum MachOTable {
+    LoadCommands,
+    Segments,
+    Sections,
+    Symbols,
+    Dylibs,
+    Fixups,
+    Architectures,
+}
+
+#[server]
+pub async fn fetch_macho_table(
+    table_type: MachOTable,
+    demangle: bool,
+) -> Result<rubilib::table::Table, ServerFnError> {
+    let mut binary = files::BINARY_STORE.write().unwrap();
+    match binary.deref_mut() {
+        rubilib::binary::Binary::MachO(macho_binary) => {
+            let table = match table_type {
+                MachOTable::LoadCommands => macho_binary.load_commands_table()?,
+                MachOTable::Segments => macho_binary.segments_table()?,
+                MachOTable::Sections => macho_binary.sections_table()?,
+                MachOTable::Symbols => macho_binary.symbols_table(demangle)?,
+                MachOTable::Dylibs => macho_binary.dylibs_table()?,
+                MachOTable::Fixups => macho_binary.fixups_table()?,
+                MachOTable::Architectures => macho_binary.architectures_table()?,
+            };
+            Ok(table)
+        }
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+/// Tables of a Mach-O image, universal binaries also list their architectures
+#[component]
+fn MachOPage() -> impl IntoView {
+    info!("display mach-o table");
+    let (tab, set_tab) = signal(MachOTable::LoadCommands);
+    let (demangle, set_demangle) = signal(true);
+    // Selected architecture of a universal binary
+    let (slice, set_slice) = signal(0);
+    let table = Resource::new(
+        move || (tab(), demangle(), slice()),
+        |(tab, demangle, _)| async move { fetch_macho_table(tab, demangle).await },
+    );
+
+    view! {
+        <h2>"Analyzing Mach-O file"</h2>
+        <SliceSelect slice set_slice/>
+        {move || {
+            slice.track();
+            view! { <FileInfo/> }
+        }}
+        <span class="tabs">
+            <button
+                on:click=move |_| set_tab(MachOTable::LoadCommands)
+                class:selected=move || tab() == MachOTable::LoadCommands
+                class="tab"
+            >
+                "Load Commands"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Segments)
+                class:selected=move || tab() == MachOTable::Segments
+                class="tab"
+            >
+                "Segments"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Sections)
+                class:selected=move || tab() == MachOTable::Sections
+                class="tab"
+            >
+                "Sections"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Symbols)
+                class:selected=move || tab() == MachOTable::Symbols
+                class="tab"
+            >
+                "Symbols"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Dylibs)
+                class:selected=move || tab() == MachOTable::Dylibs
+                class="tab"
+            >
+                "Libraries"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Fixups)
+                class:selected=move || tab() == MachOTable::Fixups
+                class="tab"
+            >
+                "Fixups"
+            </button>
+            <button
+                on:click=move |_| set_tab(MachOTable::Architectures)
+                class:selected=move || tab() == MachOTable::Architectures
+                class="tab"
+            >
+                "Architectures"
+            </button>
+        </span>
+        <label>
+            <input
+                type="checkbox"
+                prop:checked=demangle
+                on:change=move |ev| set_demangle(event_target_checked(&ev))
+            />
+            "Demangle symbol names"
+        </label>
+        <Table table/>
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ArchiveTable {
+    Members,
+    Symbols,
+}
+
+#[server]
+pub async fn fetch_archive_table(
+    table_type: ArchiveTable,
+    demangle: bool,
+) -> Result<rubilib::table::Table, ServerFnError> {
+    let binary = files::BINARY_STORE.read().unwrap();
+    match &*binary {
+        rubilib::binary::Binary::Archive(archive) => {
+            let table = match table_type {
+                ArchiveTable::Members => archive.members_table()?,
+                ArchiveTable::Symbols => archive.symbols_table(demangle)?,
+            };
+            Ok(table)
+        }
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+/// Members and symbol index of an archive
+#[component]
+fn ArchivePage() -> impl IntoView {
+    info!("display archive table");
+    let (tab, set_tab) = signal(ArchiveTable::Members);
+    let (demangle, set_demangle) = signal(true);
+    let table = Resource::new(
+        move || (tab(), demangle()),
+        |(tab, demangle)| async move { fetch_archive_table(tab, demangle).await },
+    );
+
+    view! {
+        <h2>"Analyzing ar archive"</h2>
+        <FileInfo/>
+        <MemberSelect/>
+        <span class="tabs">
+            <button
+                on:click=move |_| set_tab(ArchiveTable::Members)
+                class:selected=move || tab() == ArchiveTable::Members
+                class="tab"
+            >
+                "Members"
+            </button>
+            <button
+                on:click=move |_| set_tab(ArchiveTable::Symbols)
+                class:selected=move || tab() == ArchiveTable::Symbols
+                class="tab"
+            >
+                "Symbol Index"
+            </button>
+        </span>
+        <label>
+            <input
+                type="checkbox"
+                prop:checked=demangle
+                on:change=move |ev| set_demangle(event_target_checked(&ev))
+            />
+            "Demangle symbol names"
+        </label>
+        <Table table/>
     }
 }
 
@@ -186,6 +721,28 @@ pub async fn fetch_hex_table(
     }
 }
 
+#[server]
+pub async fn fetch_hex_view(
+    offset: usize,
+    size: usize,
+) -> Result<rubilib::table::Table, ServerFnError> {
+    let binary = files::BINARY_STORE.read().unwrap();
+    Ok(binary.hex_view(offset, size, HexTableSettings::default().bytes_per_line)?)
+}
+
+/// Hex dump of the bytes behind a selected table row
+#[component]
+fn HexView(offset: usize, size: usize) -> impl IntoView {
+    let table = Resource::new(
+        move || (offset, size),
+        |(offset, size)| async move { fetch_hex_view(offset, size).await },
+    );
+    view! {
+        <h3>{format!("{size} bytes at offset 0x{offset:x}")}</h3>
+        <Table table/>
+    }
+}
+
 /// Renders the home page of your application.
 #[component]
 fn UnknownPage() -> impl IntoView {
@@ -218,8 +775,12 @@ fn UnknownPage() -> impl IntoView {
 #[component]
 fn Table(table: Resource<Result<rubilib::table::Table, ServerFnError>>) -> impl IntoView {
     info!("Try to display table");
+    let (search, set_search) = signal(String::new());
+    // File range of the selected row, shown as hex dump below the table
+    let (view_range, set_view_range) = signal(None::<(usize, usize)>);
     let display_table = move || {
         let table = table.get();
+        let search = search().to_lowercase();
         if let Some(Ok(table)) = table {
             EitherOf3::A(view! {
                 <div style="overflow-x:auto; overflow-y:auto;">
@@ -228,11 +789,22 @@ fn Table(table: Resource<Result<rubilib::table::Table, ServerFnError>>) -> impl
                         <tr>
                             {table.headline.into_iter().map(|header| view! { <th>{header}</th> }).collect::<Vec<_>>() }
                         </tr>
-                        {table.rows.into_iter().map(|row| view! {
-                            <tr>
-                                {row.content.into_iter().map(|cell| view! { <td>{cell}</td> }).collect::<Vec<_>>() }
-                                </tr>
-                        }).collect::<Vec<_>>() }
+                        {table.rows.into_iter()
+                            .filter(|row| search.is_empty() || row.content.iter().any(|cell| cell.to_lowercase().contains(&search)))
+                            .map(|row| {
+                                let range = match row.action {
+                                    RowAction::View { offset, size } => Some((offset, size)),
+                                    RowAction::None => None,
+                                };
+                                view! {
+                                    <tr
+                                        class:selectable=range.is_some()
+                                        on:click=move |_| if range.is_some() { set_view_range(range) }
+                                    >
+                                        {row.content.into_iter().map(|cell| view! { <td>{cell}</td> }).collect::<Vec<_>>() }
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>() }
                     </tbody>
                 </table>
                 </div>
@@ -248,8 +820,15 @@ fn Table(table: Resource<Result<rubilib::table::Table, ServerFnError>>) -> impl
         }
     };
     view! {
+        <input
+            type="search"
+            placeholder="Search"
+            prop:value=search
+            on:input=move |ev| set_search(event_target_value(&ev))
+        />
         <Suspense fallback=move || view! { <p>"Preparing table..."</p> }>
         {move || display_table}
         </Suspense>
+        {move || view_range().map(|(offset, size)| view! { <HexView offset size/> }.into_any())}
     }
 }
@@ -0,0 +1,75 @@
+use crate::error_template::AppError;
+use crate::files::BINARY_STORE;
+use leptos::prelude::*;
+use rubilib::binary::Binary;
+
+#[server]
+// Names of the members of the current archive
+pub async fn fetch_archive_members() -> Result<Vec<String>, ServerFnError> {
+    match &*BINARY_STORE.read().unwrap() {
+        Binary::Archive(archive) => Ok(archive
+            .members()
+            .iter()
+            .map(|member| member.name().to_string())
+            .collect()),
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+#[server]
+// Replace the current archive by one of its members and show the page of its file type
+pub async fn open_archive_member(idx: usize) -> Result<String, ServerFnError> {
+    let mut binary = BINARY_STORE.write().unwrap();
+    let member = match &*binary {
+        Binary::Archive(archive) => archive.member(idx)?,
+        _ => {
+            log::error!("Binary has invalid type");
+            return Err(ServerFnError::from(AppError::NotFound));
+        }
+    };
+    *binary = member;
+    let file_type = binary.file_type();
+    leptos_axum::redirect(&format!("/{file_type}"));
+    Ok(file_type)
+}
+
+/// Selection of an archive member to be analyzed on its own
+#[component]
+pub fn MemberSelect() -> impl IntoView {
+    let members = Resource::new(|| (), |_| async move { fetch_archive_members().await });
+    let open_action = Action::new(|idx: &usize| open_archive_member(*idx));
+    let (selected, set_selected) = signal(0);
+
+    view! {
+        <Suspense fallback=|| view!{ <p>"Loading..."</p> } >
+            {move || {
+                members.get().and_then(|members| members.ok()).filter(|members| !members.is_empty()).map(|members| {
+                    view! {
+                        <label>
+                            "Member "
+                            <select on:change=move |ev| {
+                                if let Ok(idx) = event_target_value(&ev).parse::<usize>() {
+                                    set_selected(idx);
+                                }
+                            }>
+                                {members.into_iter().enumerate().map(|(idx, name)| view! {
+                                    <option value=idx.to_string()>{format!("{idx}: {name}")}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                        <button on:click=move |_| { open_action.dispatch(selected()); } class="tab">
+                            "Open"
+                        </button>
+                        {move || match open_action.value().get() {
+                            Some(Err(e)) => format!("Error: {e}"),
+                            _ => String::new(),
+                        }}
+                    }
+                })
+            }}
+        </Suspense>
+    }
+}
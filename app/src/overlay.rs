@@ -0,0 +1,64 @@
+use crate::files::BINARY_STORE;
+use crate::HexView;
+use leptos::prelude::*;
+use log::error;
+use rubilib::overlay::Overlay;
+
+// Larger overlays are only dumped partially, the download contains all data
+const MAX_VIEW_SIZE: usize = 0x10000;
+
+#[server]
+// Fetch the overlay of the current binary
+pub async fn fetch_overlay() -> Result<Option<Overlay>, ServerFnError> {
+    match BINARY_STORE.write() {
+        Ok(mut binary_lock) => Ok(binary_lock.overlay()?),
+        Err(e) => {
+            error!("Failed to get binary lock: {:#?}", e);
+            Err(ServerFnError::from(e))
+        }
+    }
+}
+
+/// Content of the overlay of the current binary, served as download
+#[cfg(feature = "ssr")]
+pub fn overlay_data() -> Option<Vec<u8>> {
+    match BINARY_STORE.write() {
+        Ok(mut binary_lock) => binary_lock.overlay_data().ok().flatten(),
+        Err(e) => {
+            error!("Failed to get binary lock: {:#?}", e);
+            None
+        }
+    }
+}
+
+#[component]
+pub fn OverlayInfo() -> impl IntoView {
+    let overlay = Resource::new(|| (), |_| async move { fetch_overlay().await });
+    let (show, set_show) = signal(false);
+
+    view! {
+        <Suspense fallback=|| view!{ <p>"Loading..."</p> } >
+            {move || {
+                overlay.get().and_then(|overlay| overlay.ok().flatten()).map(|overlay| {
+                    let offset = overlay.offset;
+                    let size = overlay.size.min(MAX_VIEW_SIZE);
+                    view! {
+                        <div>
+                            <h3>"Overlay"</h3>
+                            <p>{overlay.to_string()}</p>
+                            <button
+                                on:click=move |_| set_show.update(|show| *show = !*show)
+                                class:selected=show
+                                class="tab"
+                            >
+                                "View"
+                            </button>
+                            <a href="/overlay" download="overlay.bin" class="tab">"Download"</a>
+                            {move || show().then(|| view! { <HexView offset size/> })}
+                        </div>
+                    }
+                })
+            }}
+        </Suspense>
+    }
+}
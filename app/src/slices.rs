@@ -0,0 +1,66 @@
+use crate::error_template::AppError;
+use crate::files::BINARY_STORE;
+use leptos::prelude::*;
+use rubilib::binary::Binary;
+
+#[server]
+// Architectures contained in the current universal binary
+pub async fn fetch_macho_slices() -> Result<Vec<String>, ServerFnError> {
+    match &*BINARY_STORE.read().unwrap() {
+        Binary::MachO(macho_binary) => Ok(macho_binary.slices()),
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+#[server]
+// Analyze another architecture of the current universal binary
+pub async fn select_macho_slice(idx: usize) -> Result<(), ServerFnError> {
+    match &mut *BINARY_STORE.write().unwrap() {
+        Binary::MachO(macho_binary) => Ok(macho_binary.select_slice(idx)?),
+        _ => {
+            log::error!("Binary has invalid type");
+            Err(ServerFnError::from(AppError::NotFound))
+        }
+    }
+}
+
+/// Selection of the analyzed architecture, only shown for universal binaries
+#[component]
+pub fn SliceSelect(slice: ReadSignal<usize>, set_slice: WriteSignal<usize>) -> impl IntoView {
+    let slices = Resource::new(|| (), |_| async move { fetch_macho_slices().await });
+    let select_action = Action::new(|idx: &usize| {
+        let idx = *idx;
+        async move { select_macho_slice(idx).await.map(|_| idx) }
+    });
+    Effect::new(move |_| {
+        if let Some(Ok(idx)) = select_action.value().get() {
+            set_slice(idx);
+        }
+    });
+
+    view! {
+        <Suspense fallback=|| view!{ <p>"Loading..."</p> } >
+            {move || {
+                slices.get().and_then(|slices| slices.ok()).filter(|slices| slices.len() > 1).map(|slices| {
+                    view! {
+                        <label>
+                            "Architecture "
+                            <select on:change=move |ev| {
+                                if let Ok(idx) = event_target_value(&ev).parse::<usize>() {
+                                    select_action.dispatch(idx);
+                                }
+                            }>
+                                {slices.into_iter().enumerate().map(|(idx, name)| view! {
+                                    <option value=idx.to_string() selected=move || slice() == idx>{name}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                    }
+                })
+            }}
+        </Suspense>
+    }
+}
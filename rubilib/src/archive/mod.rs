@@ -0,0 +1,505 @@
+use crate::binary::{Binary, BinaryError};
+use crate::blob::{BinaryType, Blob, BlobError};
+use crate::pe::{format_timestamp, MachineType};
+use crate::table::{Row, RowAction, Table, TableType};
+use std::fmt::{self, Display};
+use thiserror::Error;
+
+type Result<T> = std::result::Result<T, ArchiveError>;
+
+mod symbol_index;
+#[cfg(test)]
+mod tests;
+
+use symbol_index::{parse_bsd_index, parse_gnu_index, IndexSymbol};
+
+#[derive(Error, Debug, Clone)]
+pub enum ArchiveError {
+    #[error("no ar archive")]
+    NoArchive,
+    #[error("invalid archive member header")]
+    InvalidMemberHeader,
+    #[error("no such archive member")]
+    NoSuchMember,
+    #[error("member of thin archive is stored in a separate file")]
+    ThinMember,
+    #[error("invalid binary")]
+    InternalError,
+    #[error("binary corrupted")]
+    BlobCorrupted(#[from] BlobError),
+}
+
+pub const ARCHIVE_MAGIC: &[u8] = b"!<arch>\n";
+// Thin archives only reference the member files by path
+pub const THIN_ARCHIVE_MAGIC: &[u8] = b"!<thin>\n";
+
+const MEMBER_HEADER_SIZE: usize = 60;
+const MEMBER_HEADER_END: &[u8] = b"`\n";
+
+// Names of the special members of the GNU and COFF variants
+const SYMBOL_INDEX_NAME: &str = "/";
+const SYMBOL_INDEX_64_NAME: &str = "/SYM64/";
+const LONG_NAMES_NAME: &str = "//";
+// Symbol index of the ARM64EC code of hybrid COFF archives
+const EC_SYMBOL_INDEX_NAME: &str = "/<ECSYMBOLS>/";
+
+// Names of the BSD symbol index, optionally followed by " SORTED"
+const BSD_SYMBOL_INDEX_NAME: &str = "__.SYMDEF";
+const BSD_SYMBOL_INDEX_64_NAME: &str = "__.SYMDEF_64";
+// BSD names longer than 16 bytes or containing spaces are stored in front of the member data
+const BSD_LONG_NAME_PREFIX: &str = "#1/";
+
+// Signatures of a COFF short import object, the first two bytes of a regular object are the machine
+const IMPORT_OBJECT_SIG1: u16 = 0;
+const IMPORT_OBJECT_SIG2: u16 = 0xffff;
+const IMPORT_OBJECT_HEADER_SIZE: usize = 20;
+const IMPORT_TYPE_NAMES: [&str; 3] = ["code", "data", "const"];
+const IMPORT_OBJECT_ORDINAL: u16 = 0;
+
+/// Layout of the archive, the variants differ in long names and symbol index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveVariant {
+    Gnu,
+    Bsd,
+    // Microsoft libraries, with a second little endian linker member
+    Coff,
+}
+
+impl Display for ArchiveVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        match self {
+            ArchiveVariant::Gnu => write!(f, "GNU"),
+            ArchiveVariant::Bsd => write!(f, "BSD"),
+            ArchiveVariant::Coff => write!(f, "COFF"),
+        }
+    }
+}
+
+/// Fields of a member header, all stored as space padded ASCII text
+struct MemberHeader {
+    name: String,
+    date: String,
+    uid: String,
+    gid: String,
+    mode: String,
+    size: usize,
+}
+
+impl MemberHeader {
+    fn new(blob: &Blob, offset: usize) -> Result<Self> {
+        let header = blob.get_slice(offset, MEMBER_HEADER_SIZE)?;
+        if &header[58..60] != MEMBER_HEADER_END {
+            return Err(ArchiveError::InvalidMemberHeader);
+        }
+        let field = |start: usize, end: usize| {
+            String::from_utf8_lossy(&header[start..end])
+                .trim_end()
+                .to_string()
+        };
+        Ok(Self {
+            name: field(0, 16),
+            date: field(16, 28),
+            uid: field(28, 34),
+            gid: field(34, 40),
+            mode: field(40, 48),
+            size: field(48, 58)
+                .parse()
+                .map_err(|_| ArchiveError::InvalidMemberHeader)?,
+        })
+    }
+}
+
+/// A file stored in the archive
+pub struct ArchiveMember {
+    name: String,
+    header_offset: usize,
+    // Absolute offset and size of the member data, without a BSD long name
+    offset: usize,
+    size: usize,
+    date: String,
+    uid: String,
+    gid: String,
+    mode: String,
+}
+
+impl ArchiveMember {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn date_as_string(&self) -> String {
+        match self.date.parse::<u32>() {
+            Ok(0) => "0".to_string(),
+            Ok(date) => format_timestamp(date),
+            Err(_) => self.date.clone(),
+        }
+    }
+
+    fn to_vec(&self, kind: String, thin: bool) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            kind,
+            format!("0x{:08x}", self.header_offset),
+            if thin {
+                format!("0x{:x} (external)", self.size)
+            } else {
+                format!("0x{:x}", self.size)
+            },
+            self.date_as_string(),
+            self.uid.clone(),
+            self.gid.clone(),
+            self.mode.clone(),
+        ]
+    }
+}
+
+/// Symbol of the archive index
+pub struct ArchiveSymbol {
+    name: String,
+    // Index of the defining member, none if the index points to no member header
+    member: Option<usize>,
+}
+
+pub struct ArchiveBinary {
+    blob: Blob,
+    variant: ArchiveVariant,
+    thin: bool,
+    members: Vec<ArchiveMember>,
+    symbols: Vec<ArchiveSymbol>,
+    // Name of the special member holding the symbol index
+    symbol_index: Option<String>,
+    has_long_names: bool,
+}
+
+/// Name of a GNU or COFF member stored in the long names table at `offset`
+fn long_name(blob: &Blob, long_names: Option<(usize, usize)>, offset: usize) -> Option<String> {
+    let (start, size) = long_names?;
+    if offset >= size {
+        return None;
+    }
+    let data = blob.get_slice(start + offset, size - offset).ok()?;
+    // GNU terminates names with "/\n", Microsoft with a zero byte
+    let end = data
+        .iter()
+        .position(|c| *c == b'\n' || *c == 0)
+        .unwrap_or(data.len());
+    let name = String::from_utf8_lossy(&data[..end]);
+    Some(name.strip_suffix('/').unwrap_or(&name).to_string())
+}
+
+/// Description of a COFF short import object, the stub of a DLL import in an import library
+fn import_object_info(blob: &Blob, offset: usize, size: usize) -> Option<String> {
+    if size < IMPORT_OBJECT_HEADER_SIZE
+        || blob.get_u16(offset).ok()? != IMPORT_OBJECT_SIG1
+        || blob.get_u16(offset + 2).ok()? != IMPORT_OBJECT_SIG2
+    {
+        return None;
+    }
+    let machine = blob.get_u16(offset + 6).ok()?;
+    let ordinal_or_hint = blob.get_u16(offset + 16).ok()?;
+    let type_info = blob.get_u16(offset + 18).ok()?;
+    let symbol = blob
+        .get_cstr(offset + IMPORT_OBJECT_HEADER_SIZE)
+        .ok()?
+        .to_string_lossy()
+        .to_string();
+    let dll = blob
+        .get_cstr(offset + IMPORT_OBJECT_HEADER_SIZE + symbol.len() + 1)
+        .ok()?
+        .to_string_lossy()
+        .to_string();
+    let import_type = IMPORT_TYPE_NAMES
+        .get(type_info as usize & 3)
+        .unwrap_or(&"unknown");
+    let imported = if (type_info >> 2) & 7 == IMPORT_OBJECT_ORDINAL {
+        format!("{symbol} by ordinal {ordinal_or_hint}")
+    } else {
+        symbol
+    };
+    let machine = match MachineType::from_repr(machine) {
+        Some(machine) => machine.to_string(),
+        None => format!("0x{machine:04x}"),
+    };
+    Some(format!(
+        "import {import_type} {imported} from {dll} ({machine})"
+    ))
+}
+
+impl ArchiveBinary {
+    pub fn new(blob: Blob) -> Result<Self> {
+        let thin = match blob.get_slice(0, ARCHIVE_MAGIC.len())? {
+            magic if magic == ARCHIVE_MAGIC => false,
+            magic if magic == THIN_ARCHIVE_MAGIC => true,
+            _ => return Err(ArchiveError::NoArchive),
+        };
+        let mut variant = ArchiveVariant::Gnu;
+        let mut members = Vec::new();
+        let mut index_symbols = Vec::new();
+        let mut symbol_index = None;
+        let mut long_names = None;
+        let mut offset = ARCHIVE_MAGIC.len();
+        while offset + MEMBER_HEADER_SIZE <= blob.len() {
+            let header = MemberHeader::new(&blob, offset)?;
+            let mut data_offset = offset + MEMBER_HEADER_SIZE;
+            // Member data has to lie within the file, except for the members of thin archives
+            let within_file = data_offset
+                .checked_add(header.size)
+                .is_some_and(|end| end <= blob.len());
+            let is_special = matches!(
+                header.name.as_str(),
+                SYMBOL_INDEX_NAME | SYMBOL_INDEX_64_NAME | LONG_NAMES_NAME | EC_SYMBOL_INDEX_NAME
+            );
+            if !within_file && (!thin || is_special) {
+                return Err(ArchiveError::InvalidMemberHeader);
+            }
+            let mut size = header.size;
+            let mut name = header.name.clone();
+            let mut special = true;
+            match header.name.as_str() {
+                SYMBOL_INDEX_NAME if symbol_index.is_none() => {
+                    index_symbols = parse_gnu_index(&blob, data_offset, size, false)?;
+                    symbol_index = Some(header.name.clone());
+                }
+                // Microsoft libraries repeat the index sorted by name in little endian byte order
+                SYMBOL_INDEX_NAME => variant = ArchiveVariant::Coff,
+                SYMBOL_INDEX_64_NAME => {
+                    index_symbols = parse_gnu_index(&blob, data_offset, size, true)?;
+                    symbol_index = Some(header.name.clone());
+                }
+                LONG_NAMES_NAME => long_names = Some((data_offset, size)),
+                EC_SYMBOL_INDEX_NAME => {}
+                _ => {
+                    special = false;
+                    if let Some(length) = header.name.strip_prefix(BSD_LONG_NAME_PREFIX) {
+                        variant = ArchiveVariant::Bsd;
+                        let length: usize = length
+                            .parse()
+                            .map_err(|_| ArchiveError::InvalidMemberHeader)?;
+                        if length > size {
+                            return Err(ArchiveError::InvalidMemberHeader);
+                        }
+                        let long_name = blob.get_slice(data_offset, length)?;
+                        let end = long_name.iter().position(|c| *c == 0).unwrap_or(length);
+                        name = String::from_utf8_lossy(&long_name[..end]).to_string();
+                        data_offset += length;
+                        size -= length;
+                    } else if let Some(long_offset) = header
+                        .name
+                        .strip_prefix('/')
+                        .and_then(|offset| offset.parse().ok())
+                    {
+                        name = long_name(&blob, long_names, long_offset).unwrap_or(name);
+                    } else if let Some(short_name) = header.name.strip_suffix('/') {
+                        name = short_name.to_string();
+                    }
+                    if name.starts_with(BSD_SYMBOL_INDEX_NAME) {
+                        variant = ArchiveVariant::Bsd;
+                        let is_64 = name.starts_with(BSD_SYMBOL_INDEX_64_NAME);
+                        index_symbols = parse_bsd_index(&blob, data_offset, size, is_64)?;
+                        symbol_index = Some(name.clone());
+                        special = true;
+                    }
+                }
+            }
+            if !special {
+                members.push(ArchiveMember {
+                    name,
+                    header_offset: offset,
+                    offset: data_offset,
+                    size,
+                    date: header.date,
+                    uid: header.uid,
+                    gid: header.gid,
+                    mode: header.mode,
+                });
+            }
+            // Thin archives contain the data of the special members only
+            offset += MEMBER_HEADER_SIZE;
+            if !thin || special {
+                offset += header.size + header.size % 2;
+            }
+        }
+        let symbols = index_symbols
+            .into_iter()
+            .map(|symbol: IndexSymbol| ArchiveSymbol {
+                member: members
+                    .iter()
+                    .position(|member| member.header_offset == symbol.member_offset),
+                name: symbol.name,
+            })
+            .collect();
+        Ok(Self {
+            blob,
+            variant,
+            thin,
+            members,
+            symbols,
+            symbol_index,
+            has_long_names: long_names.is_some(),
+        })
+    }
+
+    pub fn ident(&self) -> String {
+        format!(
+            "{}{} ar archive",
+            if self.thin { "thin " } else { "" },
+            self.variant
+        )
+    }
+
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    pub fn variant(&self) -> ArchiveVariant {
+        self.variant
+    }
+
+    pub fn members(&self) -> &[ArchiveMember] {
+        &self.members
+    }
+
+    /// Open member `idx` as binary of its own
+    pub fn member(&self, idx: usize) -> std::result::Result<Binary, BinaryError> {
+        let member = self.members.get(idx).ok_or(ArchiveError::NoSuchMember)?;
+        if self.thin {
+            return Err(ArchiveError::ThinMember.into());
+        }
+        let data = self.blob.get_slice(member.offset, member.size)?;
+        Binary::new(Blob::new(data.to_vec())?)
+    }
+
+    /// Indices of the members defining `symbol` according to the archive index
+    pub fn find_symbol(&self, symbol: &str) -> Vec<usize> {
+        let mut members: Vec<usize> = self
+            .symbols
+            .iter()
+            .filter(|entry| {
+                entry.name == symbol || crate::demangle::demangle(&entry.name) == symbol
+            })
+            .filter_map(|entry| entry.member)
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        members
+    }
+
+    pub fn header_info(&self) -> Vec<(String, String)> {
+        let mut info = vec![
+            ("Ident".to_string(), self.ident()),
+            ("Members".to_string(), self.members.len().to_string()),
+        ];
+        match &self.symbol_index {
+            Some(name) => {
+                info.push((
+                    "Symbol index".to_string(),
+                    format!("{name}, {} symbols", self.symbols.len()),
+                ));
+                let missing = self
+                    .symbols
+                    .iter()
+                    .filter(|symbol| symbol.member.is_none())
+                    .count();
+                if missing > 0 {
+                    info.push((
+                        "Invalid index entries".to_string(),
+                        format!("{missing} symbols point to no member"),
+                    ));
+                }
+            }
+            None => info.push(("Symbol index".to_string(), "none".to_string())),
+        }
+        if self.has_long_names {
+            info.push(("Long names".to_string(), LONG_NAMES_NAME.to_string()));
+        }
+        info
+    }
+
+    /// Kind of a member, guessed from its content
+    fn member_kind(&self, member: &ArchiveMember) -> String {
+        if self.thin {
+            return "external file".to_string();
+        }
+        if let Some(info) = import_object_info(&self.blob, member.offset, member.size) {
+            return info;
+        }
+        let blob = self
+            .blob
+            .get_slice(member.offset, member.size)
+            .ok()
+            .and_then(|data| Blob::new(data.to_vec()).ok());
+        match blob.map(|blob| blob.bin_type) {
+            Some(BinaryType::Elf(ident)) => format!("ELF {ident}"),
+            Some(BinaryType::Pe) => "PE".to_string(),
+            Some(BinaryType::Coff) => "COFF object".to_string(),
+            Some(BinaryType::MachO) => "Mach-O".to_string(),
+            Some(BinaryType::Archive) => "ar archive".to_string(),
+            Some(BinaryType::Unknown) | None => "unknown".to_string(),
+        }
+    }
+
+    pub fn members_table(&self) -> Result<Table> {
+        let headers = [
+            "Nr.", "Name", "Kind", "Offset", "Size", "Date", "UID", "GID", "Mode",
+        ];
+        let mut rows = Vec::with_capacity(self.members.len());
+        for (idx, member) in self.members.iter().enumerate() {
+            let mut v = member.to_vec(self.member_kind(member), self.thin);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(ArchiveError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: if self.thin {
+                    RowAction::None
+                } else {
+                    RowAction::View {
+                        offset: member.offset,
+                        size: member.size,
+                    }
+                },
+            });
+        }
+        Ok(Table::new(TableType::ArchiveMembers, &headers, rows))
+    }
+
+    pub fn symbols_table(&self, demangle: bool) -> Result<Table> {
+        let headers = ["Nr.", "Symbol", "Member", "Member Nr."];
+        let mut rows = Vec::with_capacity(self.symbols.len());
+        for (idx, symbol) in self.symbols.iter().enumerate() {
+            let content = vec![
+                idx.to_string(),
+                if demangle {
+                    crate::demangle::demangle(&symbol.name)
+                } else {
+                    symbol.name.clone()
+                },
+                match symbol.member {
+                    Some(member) => self.members[member].name.clone(),
+                    None => "invalid member offset".to_string(),
+                },
+                symbol
+                    .member
+                    .map(|member| member.to_string())
+                    .unwrap_or_default(),
+            ];
+            if headers.len() != content.len() {
+                return Err(ArchiveError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: match symbol.member {
+                    Some(member) if !self.thin => RowAction::View {
+                        offset: self.members[member].offset,
+                        size: self.members[member].size,
+                    },
+                    _ => RowAction::None,
+                },
+            });
+        }
+        Ok(Table::new(TableType::ArchiveSymbols, &headers, rows))
+    }
+}
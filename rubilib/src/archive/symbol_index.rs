@@ -0,0 +1,128 @@
+use super::Result;
+use crate::blob::{Blob, BlobError};
+
+// Size of a ranlib entry of the BSD symbol index, string index and member offset
+const RANLIB_SIZE: usize = 8;
+const RANLIB_64_SIZE: usize = 16;
+
+/// Symbol of the archive index and the header offset of the member defining it
+pub(super) struct IndexSymbol {
+    pub(super) name: String,
+    pub(super) member_offset: usize,
+}
+
+fn get_u32(blob: &Blob, offset: usize, lsb: bool) -> Result<u32> {
+    let bytes = blob
+        .get_slice(offset, 4)?
+        .try_into()
+        .map_err(|_| BlobError::InvalidSliceSize)?;
+    Ok(if lsb {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn get_u64(blob: &Blob, offset: usize, lsb: bool) -> Result<u64> {
+    let bytes = blob
+        .get_slice(offset, 8)?
+        .try_into()
+        .map_err(|_| BlobError::InvalidSliceSize)?;
+    Ok(if lsb {
+        u64::from_le_bytes(bytes)
+    } else {
+        u64::from_be_bytes(bytes)
+    })
+}
+
+fn get_name(blob: &Blob, offset: usize) -> Result<String> {
+    Ok(blob.get_cstr(offset)?.to_string_lossy().to_string())
+}
+
+/// Parse the big endian GNU and COFF index `/` or its 64 bit variant `/SYM64/`
+pub(super) fn parse_gnu_index(
+    blob: &Blob,
+    offset: usize,
+    size: usize,
+    is_64: bool,
+) -> Result<Vec<IndexSymbol>> {
+    let word = if is_64 { 8 } else { 4 };
+    // The index has to lie within the file before its counts are trusted
+    blob.get_slice(offset, size)?;
+    let count = if is_64 {
+        get_u64(blob, offset, false)? as usize
+    } else {
+        get_u32(blob, offset, false)? as usize
+    };
+    // Each symbol needs at least its member offset and a terminating zero
+    if count > size / (word + 1) {
+        return Err(BlobError::InvalidSliceSize.into());
+    }
+    let mut name_offset = offset + word * (count + 1);
+    let mut symbols = Vec::with_capacity(count);
+    for idx in 0..count {
+        let entry = offset + word * (idx + 1);
+        let member_offset = if is_64 {
+            get_u64(blob, entry, false)? as usize
+        } else {
+            get_u32(blob, entry, false)? as usize
+        };
+        // Names follow each other, a lossy conversion may change their length
+        let name = blob.get_cstr(name_offset)?;
+        name_offset += name.to_bytes().len() + 1;
+        symbols.push(IndexSymbol {
+            name: name.to_string_lossy().to_string(),
+            member_offset,
+        });
+    }
+    Ok(symbols)
+}
+
+/// Parse the BSD index `__.SYMDEF` or `__.SYMDEF_64`, stored in the byte order of the archiver
+pub(super) fn parse_bsd_index(
+    blob: &Blob,
+    offset: usize,
+    size: usize,
+    is_64: bool,
+) -> Result<Vec<IndexSymbol>> {
+    let (word, entry_size) = if is_64 {
+        (8, RANLIB_64_SIZE)
+    } else {
+        (4, RANLIB_SIZE)
+    };
+    blob.get_slice(offset, size)?;
+    let ranlib_size = |lsb| -> Result<usize> {
+        Ok(if is_64 {
+            get_u64(blob, offset, lsb)? as usize
+        } else {
+            get_u32(blob, offset, lsb)? as usize
+        })
+    };
+    // The byte order is not recorded, only one of both gives a plausible table size
+    let lsb = ranlib_size(true)? <= size.saturating_sub(2 * word);
+    let ranlib_size = ranlib_size(lsb)?;
+    if ranlib_size > size.saturating_sub(2 * word) {
+        return Err(BlobError::InvalidSliceSize.into());
+    }
+    let strings_offset = offset + 2 * word + ranlib_size;
+    let mut symbols = Vec::with_capacity(ranlib_size / entry_size);
+    for idx in 0..ranlib_size / entry_size {
+        let entry = offset + word + idx * entry_size;
+        let (name_index, member_offset) = if is_64 {
+            (
+                get_u64(blob, entry, lsb)? as usize,
+                get_u64(blob, entry + 8, lsb)? as usize,
+            )
+        } else {
+            (
+                get_u32(blob, entry, lsb)? as usize,
+                get_u32(blob, entry + 4, lsb)? as usize,
+            )
+        };
+        symbols.push(IndexSymbol {
+            name: get_name(blob, strings_offset + name_index)?,
+            member_offset,
+        });
+    }
+    Ok(symbols)
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+fn member_header(name: &str, size: &str) -> Vec<u8> {
+    format!("{name:<16}{:<12}{:<6}{:<6}{:<8}{size:<10}`\n", 0, 0, 0, 644).into_bytes()
+}
+
+fn archive(
+    magic: &[u8],
+    members: &[(&str, Vec<u8>)],
+) -> std::result::Result<ArchiveBinary, ArchiveError> {
+    let mut data = magic.to_vec();
+    for (name, content) in members {
+        data.extend(member_header(name, &content.len().to_string()));
+        data.extend_from_slice(content);
+        if data.len() % 2 != 0 {
+            data.push(b'\n');
+        }
+    }
+    ArchiveBinary::new(Blob::new(data).unwrap())
+}
+
+/// GNU index with one symbol defined by the member whose header is at `member_offset`
+fn gnu_index(symbol: &str, member_offset: u32) -> Vec<u8> {
+    let mut index = 1u32.to_be_bytes().to_vec();
+    index.extend_from_slice(&member_offset.to_be_bytes());
+    index.extend_from_slice(symbol.as_bytes());
+    index.push(0);
+    index
+}
+
+#[test]
+fn gnu_index_maps_symbols_to_members() {
+    // The member header follows the magic, the index header and the 12 byte index
+    let archive = archive(
+        ARCHIVE_MAGIC,
+        &[("/", gnu_index("foo", 80)), ("foo.o/", b"object".to_vec())],
+    )
+    .unwrap();
+    assert_eq!(archive.members().len(), 1);
+    assert_eq!(archive.members()[0].name(), "foo.o");
+    assert_eq!(archive.find_symbol("foo"), [0]);
+}
+
+#[test]
+fn gnu_index_skips_names_by_their_raw_length() {
+    // The first name is no valid UTF-8 and grows by the lossy conversion
+    let mut index = 2u32.to_be_bytes().to_vec();
+    for _ in 0..2 {
+        index.extend_from_slice(&88u32.to_be_bytes());
+    }
+    index.extend_from_slice(b"\xff\xfe\xfd\0foo\0");
+    let archive = archive(
+        ARCHIVE_MAGIC,
+        &[("/", index), ("foo.o/", b"object".to_vec())],
+    )
+    .unwrap();
+    assert_eq!(archive.find_symbol("foo"), [0]);
+}
+
+#[test]
+fn member_size_beyond_file_is_rejected() {
+    let mut data = ARCHIVE_MAGIC.to_vec();
+    data.extend(member_header("/", "9999999999"));
+    data.extend_from_slice(&1999999999u32.to_be_bytes());
+    data.resize(100, 0);
+    assert!(ArchiveBinary::new(Blob::new(data).unwrap()).is_err());
+
+    let mut data = ARCHIVE_MAGIC.to_vec();
+    data.extend(member_header("foo.o/", "9999999999"));
+    data.extend_from_slice(b"object");
+    assert!(ArchiveBinary::new(Blob::new(data).unwrap()).is_err());
+}
+
+#[test]
+fn bsd_index_size_beyond_file_is_rejected() {
+    let mut data = ARCHIVE_MAGIC.to_vec();
+    data.extend(member_header("#1/12", "9999999999"));
+    data.extend_from_slice(b"__.SYMDEF\0\0\0");
+    data.extend_from_slice(&0x7fffffffu32.to_le_bytes());
+    assert!(ArchiveBinary::new(Blob::new(data).unwrap()).is_err());
+}
+
+#[test]
+fn thin_archive_members_are_stored_outside() {
+    let mut data = THIN_ARCHIVE_MAGIC.to_vec();
+    data.extend(member_header("foo.o/", "4096"));
+    let archive = ArchiveBinary::new(Blob::new(data).unwrap()).unwrap();
+    assert_eq!(archive.members()[0].name(), "foo.o");
+    assert!(archive.member(0).is_err());
+}
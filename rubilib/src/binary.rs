@@ -1,7 +1,12 @@
+use crate::archive;
 use crate::blob::{BinaryType, Blob, BlobError};
+use crate::coff;
 use crate::elf;
 use crate::hex;
+use crate::macho;
+use crate::overlay::Overlay;
 use crate::pe;
+use crate::table::Table;
 use std::{
     fmt::{self, Display},
     fs,
@@ -15,15 +20,26 @@ pub enum BinaryError {
     NoPeBinary(#[from] pe::PeError),
     #[error("corrupt elf binary")]
     NoElfBinary(#[from] elf::ElfError),
+    #[error("corrupt coff object")]
+    NoCoffObject(#[from] coff::CoffError),
+    #[error("corrupt mach-o binary")]
+    NoMachOBinary(#[from] macho::MachOError),
+    #[error("corrupt ar archive")]
+    NoArchive(#[from] archive::ArchiveError),
     #[error("corrupt binary blob")]
     BlobCorrupted(#[from] BlobError),
+    #[error("hex dump failed")]
+    HexDump(#[from] hex::HexError),
 }
 
 type Result<T> = std::result::Result<T, BinaryError>;
 
 pub enum Binary {
-    Elf(elf::ElfBinary),
-    Pe(pe::PeBinary),
+    Elf(Box<elf::ElfBinary>),
+    Pe(Box<pe::PeBinary>),
+    Coff(Box<coff::CoffObject>),
+    MachO(Box<macho::MachOBinary>),
+    Archive(Box<archive::ArchiveBinary>),
     Unknown(hex::HexBinary),
 }
 
@@ -38,11 +54,23 @@ impl Binary {
         match blob.bin_type {
             BinaryType::Elf(_) => {
                 let elf_binary = elf::ElfBinary::new(blob)?;
-                Ok(Self::Elf(elf_binary))
+                Ok(Self::Elf(Box::new(elf_binary)))
             }
             BinaryType::Pe => {
                 let pe_binary = pe::PeBinary::new(blob)?;
-                Ok(Self::Pe(pe_binary))
+                Ok(Self::Pe(Box::new(pe_binary)))
+            }
+            BinaryType::Coff => {
+                let coff_object = coff::CoffObject::new(blob)?;
+                Ok(Self::Coff(Box::new(coff_object)))
+            }
+            BinaryType::MachO => {
+                let macho_binary = macho::MachOBinary::new(blob)?;
+                Ok(Self::MachO(Box::new(macho_binary)))
+            }
+            BinaryType::Archive => {
+                let archive = archive::ArchiveBinary::new(blob)?;
+                Ok(Self::Archive(Box::new(archive)))
             }
             _ => Ok(Self::Unknown(hex::HexBinary::new(blob))),
         }
@@ -53,20 +81,77 @@ impl Binary {
         Ok(())
     }
 
-    pub fn file_info(&self) -> Vec<(String, String)> {
+    pub fn file_info(&mut self) -> Vec<(String, String)> {
         match self {
             Binary::Elf(elf_binary) => elf_binary.header_info(),
             Binary::Pe(pe_binary) => pe_binary.header_info(),
+            Binary::Coff(coff_object) => coff_object.header_info(),
+            Binary::MachO(macho_binary) => macho_binary.header_info(),
+            Binary::Archive(archive) => archive.header_info(),
             Binary::Unknown(_) => {
                 vec![("Ident".to_string(), "Unknown binary".to_string())]
             }
         }
     }
 
+    /// Security hardening report, empty for unknown binaries
+    pub fn checksec(&mut self) -> Result<Vec<(String, String)>> {
+        match self {
+            Binary::Elf(elf_binary) => Ok(elf_binary.checksec()?),
+            Binary::Pe(pe_binary) => Ok(pe_binary.checksec()?),
+            Binary::Coff(_) | Binary::MachO(_) | Binary::Archive(_) | Binary::Unknown(_) => {
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Data appended behind the file image, none for unknown binaries
+    pub fn overlay(&mut self) -> Result<Option<Overlay>> {
+        match self {
+            Binary::Elf(elf_binary) => Ok(elf_binary.overlay()?),
+            Binary::Pe(pe_binary) => Ok(pe_binary.overlay()?),
+            Binary::Coff(_) | Binary::MachO(_) | Binary::Archive(_) | Binary::Unknown(_) => {
+                Ok(None)
+            }
+        }
+    }
+
+    /// Content of the overlay, e.g. to extract an installer payload
+    pub fn overlay_data(&mut self) -> Result<Option<Vec<u8>>> {
+        let Some(overlay) = self.overlay()? else {
+            return Ok(None);
+        };
+        let blob = match self {
+            Binary::Elf(elf_binary) => elf_binary.blob(),
+            Binary::Pe(pe_binary) => pe_binary.blob(),
+            Binary::Coff(coff_object) => coff_object.blob(),
+            Binary::MachO(macho_binary) => macho_binary.blob(),
+            Binary::Archive(archive) => archive.blob(),
+            Binary::Unknown(hex_binary) => hex_binary.blob(),
+        };
+        Ok(Some(blob.get_slice(overlay.offset, overlay.size)?.to_vec()))
+    }
+
+    /// Hex dump of a range of the file, e.g. the data behind a table row
+    pub fn hex_view(&self, offset: usize, size: usize, bytes_per_line: u32) -> Result<Table> {
+        let blob = match self {
+            Binary::Elf(elf_binary) => elf_binary.blob(),
+            Binary::Pe(pe_binary) => pe_binary.blob(),
+            Binary::Coff(coff_object) => coff_object.blob(),
+            Binary::MachO(macho_binary) => macho_binary.blob(),
+            Binary::Archive(archive) => archive.blob(),
+            Binary::Unknown(hex_binary) => hex_binary.blob(),
+        };
+        Ok(hex::hex_dump_table(blob, offset, size, bytes_per_line)?)
+    }
+
     pub fn file_type(&self) -> String {
         match self {
             Binary::Elf(_) => "elf".to_string(),
             Binary::Pe(_) => "pe".to_string(),
+            Binary::Coff(_) => "coff".to_string(),
+            Binary::MachO(_) => "macho".to_string(),
+            Binary::Archive(_) => "archive".to_string(),
             Binary::Unknown(_) => "unknown".to_string(),
         }
     }
@@ -80,6 +165,18 @@ impl Display for Binary {
                 write!(f, "{}", elf_binary.ident())
             }
             Binary::Pe(_) => write!(f, "pe"),
+            Binary::Coff(coff_object) => {
+                write!(f, "coff")?;
+                write!(f, "{}", coff_object.ident())
+            }
+            Binary::MachO(macho_binary) => {
+                write!(f, "macho")?;
+                write!(f, "{}", macho_binary.ident())
+            }
+            Binary::Archive(archive) => {
+                write!(f, "archive")?;
+                write!(f, "{}", archive.ident())
+            }
             Binary::Unknown(_) => write!(f, "unknown"),
         }
     }
@@ -1,3 +1,5 @@
+use crate::archive::{ARCHIVE_MAGIC, THIN_ARCHIVE_MAGIC};
+use crate::coff::is_coff_object;
 use crate::elf::ElfIdent;
 use std::ffi::CStr;
 use std::fmt::{self, Display};
@@ -47,6 +49,19 @@ impl Blob {
         self.data.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Copy of the data in front of `end`, reads behind it fail as if the file ended there
+    pub(crate) fn truncated(&self, end: usize) -> Self {
+        Self {
+            bin_type: self.bin_type.clone(),
+            lsb: self.lsb,
+            data: self.data[..end.min(self.data.len())].to_vec(),
+        }
+    }
+
     pub fn get_u8(&self, offset: usize) -> Result<u8> {
         Ok(*self.data.get(offset).ok_or(BlobError::InvalidSliceSize)?)
     }
@@ -54,13 +69,17 @@ impl Blob {
     pub fn get_u16(&self, offset: usize) -> Result<u16> {
         if self.lsb {
             Ok(u16::from_le_bytes(
-                self.data[offset..offset + 2]
+                self.data
+                    .get(offset..offset + 2)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
         } else {
             Ok(u16::from_be_bytes(
-                self.data[offset..offset + 2]
+                self.data
+                    .get(offset..offset + 2)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
@@ -70,13 +89,17 @@ impl Blob {
     pub fn get_u32(&self, offset: usize) -> Result<u32> {
         if self.lsb {
             Ok(u32::from_le_bytes(
-                self.data[offset..offset + 4]
+                self.data
+                    .get(offset..offset + 4)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
         } else {
             Ok(u32::from_be_bytes(
-                self.data[offset..offset + 4]
+                self.data
+                    .get(offset..offset + 4)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
@@ -86,21 +109,71 @@ impl Blob {
     pub fn get_u64(&self, offset: usize) -> Result<u64> {
         if self.lsb {
             Ok(u64::from_le_bytes(
-                self.data[offset..offset + 8]
+                self.data
+                    .get(offset..offset + 8)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
         } else {
             Ok(u64::from_be_bytes(
-                self.data[offset..offset + 8]
+                self.data
+                    .get(offset..offset + 8)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             ))
         }
     }
 
+    /// Read an unsigned LEB128 value, returns the value and the number of bytes read
+    pub fn get_uleb128(&self, offset: usize) -> Result<(u64, usize)> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        let mut len = 0;
+        loop {
+            let b = self.get_u8(offset + len)?;
+            len += 1;
+            if shift < 64 {
+                value |= ((b & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if b & 0x80 == 0 {
+                return Ok((value, len));
+            }
+        }
+    }
+
+    /// Read a signed LEB128 value, returns the value and the number of bytes read
+    pub fn get_sleb128(&self, offset: usize) -> Result<(i64, usize)> {
+        let mut value = 0i64;
+        let mut shift = 0;
+        let mut len = 0;
+        loop {
+            let b = self.get_u8(offset + len)?;
+            len += 1;
+            if shift < 64 {
+                value |= ((b & 0x7f) as i64) << shift;
+            }
+            shift += 7;
+            if b & 0x80 == 0 {
+                if shift < 64 && b & 0x40 != 0 {
+                    value |= -1i64 << shift;
+                }
+                return Ok((value, len));
+            }
+        }
+    }
+
+    pub fn get_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.data
+            .get(offset..offset.checked_add(len).ok_or(BlobError::InvalidSliceSize)?)
+            .ok_or(BlobError::InvalidSliceSize)
+    }
+
     pub fn get_cstr(&self, offset: usize) -> Result<&CStr> {
-        CStr::from_bytes_until_nul(&self.data[offset..]).map_err(|_| BlobError::InvalidSliceSize)
+        CStr::from_bytes_until_nul(self.data.get(offset..).ok_or(BlobError::InvalidSliceSize)?)
+            .map_err(|_| BlobError::InvalidSliceSize)
     }
 
     pub fn get_cname(&self, offset: Option<usize>) -> Result<String> {
@@ -118,9 +191,13 @@ impl Blob {
     }
 
     fn guess_file_type(&mut self) -> Result<()> {
-        if self.data[0..4] == [0x7f, b'E', b'L', b'F'] {
+        let magic = self.data.get(0..4).unwrap_or_default();
+        if magic == [0x7f, b'E', b'L', b'F'] {
             let elf_ident = ElfIdent::from_slice(
-                &self.data[4..16]
+                &self
+                    .data
+                    .get(4..16)
+                    .ok_or(BlobError::InvalidSliceSize)?
                     .try_into()
                     .map_err(|_| BlobError::InvalidSliceSize)?,
             )
@@ -130,24 +207,67 @@ impl Blob {
             return Ok(());
         }
 
-        if self.data[0..2] == [b'M', b'Z'] {
+        if magic.starts_with(b"MZ") {
             self.lsb = true;
             let pe_offset = self.get_u32(0x3c)? as usize;
-            if self.data[pe_offset..pe_offset + 4] == [b'P', b'E', 0, 0] {
+            if self.data.get(pe_offset..pe_offset + 4) == Some(&[b'P', b'E', 0, 0][..]) {
                 self.bin_type = BinaryType::Pe;
                 return Ok(());
             }
         }
 
+        match magic {
+            [0xce | 0xcf, 0xfa, 0xed, 0xfe] => {
+                self.lsb = true;
+                self.bin_type = BinaryType::MachO;
+                return Ok(());
+            }
+            [0xfe, 0xed, 0xfa, 0xce | 0xcf] => {
+                self.lsb = false;
+                self.bin_type = BinaryType::MachO;
+                return Ok(());
+            }
+            // Java class files share the magic, their version makes the architecture count large
+            [0xca, 0xfe, 0xba, 0xbe | 0xbf] => {
+                self.lsb = false;
+                if (1..45).contains(&self.get_u32(4)?) {
+                    self.bin_type = BinaryType::MachO;
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+
+        if self.data.starts_with(ARCHIVE_MAGIC) || self.data.starts_with(THIN_ARCHIVE_MAGIC) {
+            // Only the import objects of COFF libraries are read with the blob byte order
+            self.lsb = true;
+            self.bin_type = BinaryType::Archive;
+            return Ok(());
+        }
+
+        // Object files have no magic, their header is checked last
+        self.lsb = true;
+        if is_coff_object(self) {
+            self.bin_type = BinaryType::Coff;
+            return Ok(());
+        }
+
         self.bin_type = BinaryType::Unknown;
 
         Ok(())
     }
 }
 
+#[derive(Clone)]
 pub enum BinaryType {
     Elf(ElfIdent),
     Pe,
+    // Object file without an MS-DOS stub, as produced by Windows compilers
+    Coff,
+    // Mach-O image or universal binary containing several of them
+    MachO,
+    // Unix ar archive, e.g. a static library
+    Archive,
     Unknown,
 }
 
@@ -159,6 +279,9 @@ impl Display for BinaryType {
                 write!(f, "{elf_ident}")
             }
             BinaryType::Pe => write!(f, "pe"),
+            BinaryType::Coff => write!(f, "coff"),
+            BinaryType::MachO => write!(f, "macho"),
+            BinaryType::Archive => write!(f, "archive"),
             BinaryType::Unknown => write!(f, "unknown"),
         }
     }
@@ -0,0 +1,410 @@
+use crate::blob::{BinaryType, Blob, BlobError};
+use crate::pe::{
+    flags_as_string, format_timestamp, MachineType, PeError, SectionHeader, CHARACTERISTICS_NAMES,
+};
+use crate::table::{Row, RowAction, Table, TableType};
+use thiserror::Error;
+
+type Result<T> = std::result::Result<T, CoffError>;
+
+mod relocations;
+mod symbols;
+#[cfg(test)]
+mod tests;
+
+use relocations::Relocation;
+use symbols::CoffSymbol;
+
+#[derive(Error, Debug, Clone)]
+pub enum CoffError {
+    #[error("no coff object")]
+    NoCoffObject,
+    #[error("invalid binary")]
+    InternalError,
+    #[error("invalid section header")]
+    InvalidSectionHeader(#[from] PeError),
+    #[error("binary corrupted")]
+    BlobCorrupted(#[from] BlobError),
+}
+
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+const SYMBOL_SIZE: usize = 18;
+
+// ANON_OBJECT_HEADER_BIGOBJ of objects with more than 65279 sections
+const BIGOBJ_HEADER_SIZE: usize = 56;
+const BIGOBJ_SYMBOL_SIZE: usize = 20;
+const BIGOBJ_MIN_VERSION: u16 = 2;
+// Class id {d1baa1c7-baee-4ba9-af20-faf66aa4dcb8}, in the byte order of the file
+const BIGOBJ_CLASS_ID: [u8; 16] = [
+    0xc7, 0xa1, 0xba, 0xd1, 0xee, 0xba, 0xa9, 0x4b, 0xaf, 0x20, 0xfa, 0xf6, 0x6a, 0xa4, 0xdc, 0xb8,
+];
+
+/// File header of an object file, either the plain COFF header or the big object header
+pub struct ObjectHeader {
+    // Version of the big object header, none for plain COFF objects
+    big_obj_version: Option<u16>,
+    // Raw machine value, kept for unknown machines
+    machine_raw: u16,
+    machine: MachineType,
+    number_of_sections: u32,
+    time_date_stamp: u32,
+    pointer_to_symbol_table: u32,
+    number_of_symbols: u32,
+    size_of_optional_header: u16,
+    characteristics: u16,
+}
+
+impl ObjectHeader {
+    pub fn new(blob: &Blob) -> Result<Self> {
+        let is_big_obj = blob.get_u16(0)? == 0
+            && blob.get_u16(2)? == 0xffff
+            && blob.get_u16(4)? >= BIGOBJ_MIN_VERSION
+            && blob.get_slice(12, 16)? == BIGOBJ_CLASS_ID;
+        if is_big_obj {
+            let machine_raw = blob.get_u16(6)?;
+            Ok(Self {
+                big_obj_version: Some(blob.get_u16(4)?),
+                machine_raw,
+                machine: MachineType::from_repr(machine_raw).unwrap_or(MachineType::Reserved),
+                number_of_sections: blob.get_u32(44)?,
+                time_date_stamp: blob.get_u32(8)?,
+                pointer_to_symbol_table: blob.get_u32(48)?,
+                number_of_symbols: blob.get_u32(52)?,
+                size_of_optional_header: 0,
+                characteristics: 0,
+            })
+        } else {
+            let machine_raw = blob.get_u16(0)?;
+            Ok(Self {
+                big_obj_version: None,
+                machine_raw,
+                machine: MachineType::from_repr(machine_raw).unwrap_or(MachineType::Reserved),
+                number_of_sections: blob.get_u16(2)? as u32,
+                time_date_stamp: blob.get_u32(4)?,
+                pointer_to_symbol_table: blob.get_u32(8)?,
+                number_of_symbols: blob.get_u32(12)?,
+                size_of_optional_header: blob.get_u16(16)?,
+                characteristics: blob.get_u16(18)?,
+            })
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self.big_obj_version {
+            Some(_) => BIGOBJ_HEADER_SIZE,
+            None => COFF_HEADER_SIZE + self.size_of_optional_header as usize,
+        }
+    }
+
+    fn symbol_size(&self) -> usize {
+        match self.big_obj_version {
+            Some(_) => BIGOBJ_SYMBOL_SIZE,
+            None => SYMBOL_SIZE,
+        }
+    }
+
+    /// The string table follows the symbol table and starts with its size
+    fn string_table_offset(&self) -> Option<usize> {
+        if self.pointer_to_symbol_table == 0 {
+            None
+        } else {
+            Some(
+                self.pointer_to_symbol_table as usize
+                    + self.symbol_size() * self.number_of_symbols as usize,
+            )
+        }
+    }
+
+    pub fn info(&self) -> Vec<(String, String)> {
+        let mut info = vec![
+            (
+                "Machine type".to_string(),
+                format!("{} (0x{:04x})", self.machine, self.machine_raw),
+            ),
+            (
+                "Number of sections".to_string(),
+                self.number_of_sections.to_string(),
+            ),
+            (
+                "Time stamp".to_string(),
+                format!(
+                    "0x{:08x} ({})",
+                    self.time_date_stamp,
+                    format_timestamp(self.time_date_stamp)
+                ),
+            ),
+            (
+                "Symbol table".to_string(),
+                format!(
+                    "0x{:08x}, count: {}",
+                    self.pointer_to_symbol_table, self.number_of_symbols
+                ),
+            ),
+        ];
+        match self.big_obj_version {
+            Some(version) => info.push(("Big object version".to_string(), version.to_string())),
+            None => info.push((
+                "Characteristics".to_string(),
+                format!(
+                    "0x{:04x} {}",
+                    self.characteristics,
+                    flags_as_string(self.characteristics as u32, &CHARACTERISTICS_NAMES)
+                ),
+            )),
+        }
+        info
+    }
+}
+
+/// Object files have no signature, accept a header of a known machine whose tables fit into the file
+pub(crate) fn is_coff_object(blob: &Blob) -> bool {
+    let Ok(header) = ObjectHeader::new(blob) else {
+        return false;
+    };
+    if header.big_obj_version.is_none()
+        && (matches!(header.machine, MachineType::Unknown | MachineType::Reserved)
+            || header.size_of_optional_header != 0)
+    {
+        return false;
+    }
+    if header.number_of_sections == 0 && header.number_of_symbols == 0 {
+        return false;
+    }
+    let fits = |offset: usize, size: usize| {
+        offset
+            .checked_add(size)
+            .is_some_and(|end| end <= blob.len())
+    };
+    if !fits(
+        header.size(),
+        header.number_of_sections as usize * SECTION_HEADER_SIZE,
+    ) {
+        return false;
+    }
+    if let Some(string_table_offset) = header.string_table_offset() {
+        let string_table_size = blob.get_u32(string_table_offset).unwrap_or(0) as usize;
+        if string_table_size < 4 || !fits(string_table_offset, string_table_size) {
+            return false;
+        }
+    }
+    (0..header.number_of_sections as usize).all(|idx| {
+        let offset = header.size() + idx * SECTION_HEADER_SIZE;
+        let raw_data = blob.get_u32(offset + 20).unwrap_or(u32::MAX) as usize;
+        let raw_size = blob.get_u32(offset + 16).unwrap_or(u32::MAX) as usize;
+        raw_data == 0 || fits(raw_data, raw_size)
+    })
+}
+
+pub struct CoffObject {
+    blob: Blob,
+    header: ObjectHeader,
+    section_headers: Vec<SectionHeader>,
+    relocations: Vec<Relocation>,
+    symbols: Vec<CoffSymbol>,
+}
+
+impl CoffObject {
+    pub fn new(blob: Blob) -> Result<Self> {
+        if !matches!(blob.bin_type, BinaryType::Coff) {
+            return Err(CoffError::NoCoffObject);
+        }
+        let header = ObjectHeader::new(&blob)?;
+        Ok(Self {
+            blob,
+            header,
+            section_headers: Vec::new(),
+            relocations: Vec::new(),
+            symbols: Vec::new(),
+        })
+    }
+
+    pub fn ident(&self) -> String {
+        match self.header.big_obj_version {
+            Some(_) => "COFF big object file".to_string(),
+            None => "COFF object file".to_string(),
+        }
+    }
+
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    pub fn header_info(&mut self) -> Vec<(String, String)> {
+        let mut info = vec![("Ident".to_string(), self.ident())];
+        info.extend(self.header.info());
+        if let Some(offset) = self.header.string_table_offset() {
+            if let Ok(size) = self.blob.get_u32(offset) {
+                info.push((
+                    "String table".to_string(),
+                    format!("0x{offset:08x}, size: 0x{size:x}"),
+                ));
+            }
+        }
+        info
+    }
+
+    pub fn section_headers_table(&mut self) -> Result<Table> {
+        self.get_sections()?;
+        let headers = [
+            "Nr.",
+            "Name",
+            "Characteristics",
+            "Flags",
+            "VirtAddress",
+            "VirtSize",
+            "FileOffset",
+            "FileSize",
+            "Relocations",
+            "Line Numbers",
+        ];
+        let mut rows = Vec::with_capacity(self.section_headers.len());
+        for (idx, section) in self.section_headers.iter().enumerate() {
+            let mut v = section.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push((idx + 1).to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(CoffError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: if section.pointer_to_raw_data != 0 && section.size_of_raw_data != 0 {
+                    RowAction::View {
+                        offset: section.pointer_to_raw_data as usize,
+                        size: section.size_of_raw_data as usize,
+                    }
+                } else {
+                    RowAction::None
+                },
+            });
+        }
+        Ok(Table::new(TableType::CoffSectionHeaders, &headers, rows))
+    }
+
+    pub fn relocations_table(&mut self) -> Result<Table> {
+        self.get_relocations()?;
+        self.get_symbols()?;
+        let headers = ["Nr.", "Section", "Offset", "Type", "Symbol Nr.", "Symbol"];
+        let mut rows = Vec::with_capacity(self.relocations.len());
+        for (idx, relocation) in self.relocations.iter().enumerate() {
+            let symbol = match self
+                .symbols
+                .binary_search_by_key(&relocation.symbol, |symbol| symbol.index)
+            {
+                Ok(symbol) => self.symbols[symbol].name.clone(),
+                Err(_) => String::new(),
+            };
+            let content = vec![
+                idx.to_string(),
+                self.section_headers[relocation.section].name.clone(),
+                format!("0x{:08x}", relocation.offset),
+                relocations::relocation_type_name(self.header.machine, relocation.r_type),
+                relocation.symbol.to_string(),
+                symbol,
+            ];
+            if headers.len() != content.len() {
+                return Err(CoffError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::CoffRelocations, &headers, rows))
+    }
+
+    pub fn symbols_table(&mut self, demangle: bool) -> Result<Table> {
+        self.get_sections()?;
+        self.get_symbols()?;
+        let headers = [
+            "Nr.",
+            "Name",
+            "Value",
+            "Section",
+            "Type",
+            "Storage Class",
+            "Auxiliary",
+        ];
+        let mut rows = Vec::with_capacity(self.symbols.len());
+        for symbol in &self.symbols {
+            let mut v = symbol.to_vec(&self.symbols, &self.section_headers, demangle);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(symbol.index.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(CoffError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::CoffSymbols, &headers, rows))
+    }
+
+    /// Strings of the string table, offsets are relative to its start as used by names
+    pub fn strings_table(&self) -> Result<Table> {
+        let headers = ["Offset", "String"];
+        let mut rows = Vec::new();
+        if let Some(start) = self.header.string_table_offset() {
+            let size = self.blob.get_u32(start)? as usize;
+            let mut offset = 4;
+            while offset < size {
+                let string = self.blob.get_cstr(start + offset)?;
+                let content = vec![
+                    format!("0x{offset:08x}"),
+                    string.to_string_lossy().to_string(),
+                ];
+                offset += string.to_bytes().len() + 1;
+                if headers.len() != content.len() {
+                    return Err(CoffError::InternalError);
+                }
+                rows.push(Row {
+                    content,
+                    action: RowAction::None,
+                });
+            }
+        }
+        Ok(Table::new(TableType::CoffStrings, &headers, rows))
+    }
+
+    fn get_sections(&mut self) -> Result<()> {
+        if self.section_headers.is_empty() {
+            let string_table_offset = self.header.string_table_offset();
+            let mut idx = self.header.size();
+            for _ in 0..self.header.number_of_sections {
+                self.section_headers.push(SectionHeader::new(
+                    &self.blob,
+                    idx,
+                    string_table_offset,
+                )?);
+                idx += SECTION_HEADER_SIZE;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_relocations(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.relocations.is_empty() {
+            self.relocations = relocations::parse_relocations(&self.blob, &self.section_headers)?;
+        }
+        Ok(())
+    }
+
+    fn get_symbols(&mut self) -> Result<()> {
+        if self.symbols.is_empty() {
+            if let Some(string_table_offset) = self.header.string_table_offset() {
+                self.symbols = symbols::parse_symbols(
+                    &self.blob,
+                    self.header.pointer_to_symbol_table as usize,
+                    self.header.number_of_symbols,
+                    self.header.symbol_size(),
+                    string_table_offset,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use super::Result;
+use crate::blob::Blob;
+use crate::pe::{MachineType, SectionHeader};
+
+// The relocation count overflowed, the first relocation holds the real count
+const IMAGE_SCN_LNK_NRELOC_OVFL: u32 = 0x1000000;
+const RELOCATION_SIZE: usize = 10;
+
+const AMD64_RELOCATIONS: [(u16, &str); 17] = [
+    (0x0, "ABSOLUTE"),
+    (0x1, "ADDR64"),
+    (0x2, "ADDR32"),
+    (0x3, "ADDR32NB"),
+    (0x4, "REL32"),
+    (0x5, "REL32_1"),
+    (0x6, "REL32_2"),
+    (0x7, "REL32_3"),
+    (0x8, "REL32_4"),
+    (0x9, "REL32_5"),
+    (0xa, "SECTION"),
+    (0xb, "SECREL"),
+    (0xc, "SECREL7"),
+    (0xd, "TOKEN"),
+    (0xe, "SREL32"),
+    (0xf, "PAIR"),
+    (0x10, "SSPAN32"),
+];
+
+const I386_RELOCATIONS: [(u16, &str); 11] = [
+    (0x0, "ABSOLUTE"),
+    (0x1, "DIR16"),
+    (0x2, "REL16"),
+    (0x6, "DIR32"),
+    (0x7, "DIR32NB"),
+    (0x9, "SEG12"),
+    (0xa, "SECTION"),
+    (0xb, "SECREL"),
+    (0xc, "TOKEN"),
+    (0xd, "SECREL7"),
+    (0x14, "REL32"),
+];
+
+const ARM_RELOCATIONS: [(u16, &str); 14] = [
+    (0x0, "ABSOLUTE"),
+    (0x1, "ADDR32"),
+    (0x2, "ADDR32NB"),
+    (0x3, "BRANCH24"),
+    (0x4, "BRANCH11"),
+    (0xa, "REL32"),
+    (0xe, "SECTION"),
+    (0xf, "SECREL"),
+    (0x10, "MOV32"),
+    (0x11, "THUMB_MOV32"),
+    (0x12, "THUMB_BRANCH20"),
+    (0x14, "THUMB_BRANCH24"),
+    (0x15, "THUMB_BLX23"),
+    (0x16, "PAIR"),
+];
+
+const ARM64_RELOCATIONS: [(u16, &str); 18] = [
+    (0x0, "ABSOLUTE"),
+    (0x1, "ADDR32"),
+    (0x2, "ADDR32NB"),
+    (0x3, "BRANCH26"),
+    (0x4, "PAGEBASE_REL21"),
+    (0x5, "REL21"),
+    (0x6, "PAGEOFFSET_12A"),
+    (0x7, "PAGEOFFSET_12L"),
+    (0x8, "SECREL"),
+    (0x9, "SECREL_LOW12A"),
+    (0xa, "SECREL_HIGH12A"),
+    (0xb, "SECREL_LOW12L"),
+    (0xc, "TOKEN"),
+    (0xd, "SECTION"),
+    (0xe, "ADDR64"),
+    (0xf, "BRANCH19"),
+    (0x10, "BRANCH14"),
+    (0x11, "REL32"),
+];
+
+/// Entry of the relocation table of a section
+pub(super) struct Relocation {
+    // Index of the section the relocation applies to, starting at 0
+    pub(super) section: usize,
+    pub(super) offset: u32,
+    pub(super) symbol: u32,
+    pub(super) r_type: u16,
+}
+
+/// Name of a relocation type, e.g. `IMAGE_REL_AMD64_REL32`, or its number for
+/// unsupported machines and unknown types
+pub(super) fn relocation_type_name(machine: MachineType, r_type: u16) -> String {
+    let (prefix, names): (&str, &[(u16, &str)]) = match machine {
+        MachineType::Amd64 | MachineType::Arm64Ec => ("AMD64", &AMD64_RELOCATIONS),
+        MachineType::I386 => ("I386", &I386_RELOCATIONS),
+        MachineType::Arm | MachineType::Thumb | MachineType::ArmNt => ("ARM", &ARM_RELOCATIONS),
+        MachineType::Arm64 | MachineType::Arm64X => ("ARM64", &ARM64_RELOCATIONS),
+        _ => return format!("0x{r_type:04x}"),
+    };
+    match names.iter().find(|(value, _)| *value == r_type) {
+        Some((_, name)) => format!("IMAGE_REL_{prefix}_{name}"),
+        None => format!("0x{r_type:04x}"),
+    }
+}
+
+/// Relocations of all sections
+pub(super) fn parse_relocations(
+    blob: &Blob,
+    sections: &[SectionHeader],
+) -> Result<Vec<Relocation>> {
+    let mut relocations = Vec::new();
+    for (idx, section) in sections.iter().enumerate() {
+        let mut offset = section.pointer_to_relocations as usize;
+        let mut count = section.number_of_relocations as usize;
+        if section.characteristics & IMAGE_SCN_LNK_NRELOC_OVFL != 0 && count == 0xffff {
+            // The count includes the entry holding it
+            count = (blob.get_u32(offset)? as usize).saturating_sub(1);
+            offset += RELOCATION_SIZE;
+        }
+        for _ in 0..count {
+            relocations.push(Relocation {
+                section: idx,
+                offset: blob.get_u32(offset)?,
+                symbol: blob.get_u32(offset + 4)?,
+                r_type: blob.get_u16(offset + 8)?,
+            });
+            offset += RELOCATION_SIZE;
+        }
+    }
+    Ok(relocations)
+}
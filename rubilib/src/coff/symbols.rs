@@ -0,0 +1,328 @@
+use super::Result;
+use crate::blob::Blob;
+use crate::pe::SectionHeader;
+
+// Special section numbers
+const IMAGE_SYM_UNDEFINED: i32 = 0;
+const IMAGE_SYM_ABSOLUTE: i32 = -1;
+const IMAGE_SYM_DEBUG: i32 = -2;
+
+// Storage classes with auxiliary records of a known layout
+const IMAGE_SYM_CLASS_EXTERNAL: u8 = 2;
+const IMAGE_SYM_CLASS_STATIC: u8 = 3;
+const IMAGE_SYM_CLASS_FUNCTION: u8 = 101;
+const IMAGE_SYM_CLASS_FILE: u8 = 103;
+const IMAGE_SYM_CLASS_WEAK_EXTERNAL: u8 = 105;
+
+// Complex type of function symbols, stored in the upper bits of the type
+const IMAGE_SYM_DTYPE_FUNCTION: u16 = 2;
+
+const STORAGE_CLASS_NAMES: [(u8, &str); 27] = [
+    (0, "NULL"),
+    (1, "AUTOMATIC"),
+    (2, "EXTERNAL"),
+    (3, "STATIC"),
+    (4, "REGISTER"),
+    (5, "EXTERNAL_DEF"),
+    (6, "LABEL"),
+    (7, "UNDEFINED_LABEL"),
+    (8, "MEMBER_OF_STRUCT"),
+    (9, "ARGUMENT"),
+    (10, "STRUCT_TAG"),
+    (11, "MEMBER_OF_UNION"),
+    (12, "UNION_TAG"),
+    (13, "TYPE_DEFINITION"),
+    (14, "UNDEFINED_STATIC"),
+    (15, "ENUM_TAG"),
+    (16, "MEMBER_OF_ENUM"),
+    (17, "REGISTER_PARAM"),
+    (18, "BIT_FIELD"),
+    (100, "BLOCK"),
+    (101, "FUNCTION"),
+    (102, "END_OF_STRUCT"),
+    (103, "FILE"),
+    (104, "SECTION"),
+    (105, "WEAK_EXTERNAL"),
+    (107, "CLR_TOKEN"),
+    (0xff, "END_OF_FUNCTION"),
+];
+
+const BASE_TYPE_NAMES: [&str; 16] = [
+    "NULL", "VOID", "CHAR", "SHORT", "INT", "LONG", "FLOAT", "DOUBLE", "STRUCT", "UNION", "ENUM",
+    "MOE", "BYTE", "WORD", "UINT", "DWORD",
+];
+
+const COMPLEX_TYPE_NAMES: [&str; 4] = ["", "POINTER", "FUNCTION", "ARRAY"];
+
+const COMDAT_SELECTION_NAMES: [&str; 7] = [
+    "",
+    "NODUPLICATES",
+    "ANY",
+    "SAME_SIZE",
+    "EXACT_MATCH",
+    "ASSOCIATIVE",
+    "LARGEST",
+];
+
+// COMDAT selection linking the section to the one given by its number
+const IMAGE_COMDAT_SELECT_ASSOCIATIVE: u8 = 5;
+
+const WEAK_EXTERNAL_NAMES: [&str; 5] = ["", "NOLIBRARY", "LIBRARY", "ALIAS", "ANTI_DEPENDENCY"];
+
+/// Content of the auxiliary records following a symbol
+enum AuxRecord {
+    // Source file name, spread over all auxiliary records
+    File(String),
+    SectionDefinition {
+        length: u32,
+        relocations: u16,
+        line_numbers: u16,
+        checksum: u32,
+        // Associated section of COMDAT sections with associative selection
+        number: u32,
+        selection: u8,
+    },
+    FunctionDefinition {
+        tag_index: u32,
+        total_size: u32,
+    },
+    // Begin or end of a function, .bf and .ef
+    Function {
+        line_number: u16,
+    },
+    WeakExternal {
+        tag_index: u32,
+        characteristics: u32,
+    },
+    Other(u8),
+}
+
+/// An entry of the symbol table, auxiliary records are attached to their symbol
+pub(super) struct CoffSymbol {
+    // Index in the symbol table, auxiliary records count as entries
+    pub(super) index: u32,
+    pub(super) name: String,
+    value: u32,
+    section_number: i32,
+    symbol_type: u16,
+    storage_class: u8,
+    aux: Option<AuxRecord>,
+}
+
+impl CoffSymbol {
+    fn type_as_string(&self) -> String {
+        let base = (self.symbol_type & 0xf) as usize;
+        let complex = ((self.symbol_type >> 4) & 0x3) as usize;
+        match (base, complex) {
+            (_, 0) => BASE_TYPE_NAMES[base].to_string(),
+            (0, _) => COMPLEX_TYPE_NAMES[complex].to_string(),
+            _ => format!("{} {}", BASE_TYPE_NAMES[base], COMPLEX_TYPE_NAMES[complex]),
+        }
+    }
+
+    fn storage_class_as_string(&self) -> String {
+        match STORAGE_CLASS_NAMES
+            .iter()
+            .find(|(value, _)| *value == self.storage_class)
+        {
+            Some((_, name)) => name.to_string(),
+            None => format!("0x{:02x}", self.storage_class),
+        }
+    }
+
+    fn section_as_string(&self, sections: &[SectionHeader]) -> String {
+        match self.section_number {
+            IMAGE_SYM_UNDEFINED
+                if self.storage_class == IMAGE_SYM_CLASS_EXTERNAL && self.value != 0 =>
+            {
+                "COMMON".to_string()
+            }
+            IMAGE_SYM_UNDEFINED => "UNDEF".to_string(),
+            IMAGE_SYM_ABSOLUTE => "ABS".to_string(),
+            IMAGE_SYM_DEBUG => "DEBUG".to_string(),
+            number => match sections.get((number as usize).wrapping_sub(1)) {
+                Some(section) => section.name.clone(),
+                None => number.to_string(),
+            },
+        }
+    }
+
+    fn aux_as_string(&self, symbols: &[CoffSymbol]) -> String {
+        let tag_name = |tag_index: u32| match symbols
+            .binary_search_by_key(&tag_index, |symbol| symbol.index)
+        {
+            Ok(idx) => format!("#{tag_index} {}", symbols[idx].name),
+            Err(_) => format!("#{tag_index}"),
+        };
+        match &self.aux {
+            None => String::new(),
+            Some(AuxRecord::File(name)) => name.clone(),
+            Some(AuxRecord::SectionDefinition {
+                length,
+                relocations,
+                line_numbers,
+                checksum,
+                number,
+                selection,
+            }) => {
+                let mut details = format!(
+                    "length 0x{length:x}, {relocations} relocations, {line_numbers} line numbers, checksum 0x{checksum:08x}"
+                );
+                if *selection != 0 {
+                    details.push_str(&format!(
+                        ", COMDAT {}",
+                        COMDAT_SELECTION_NAMES
+                            .get(*selection as usize)
+                            .unwrap_or(&"unknown")
+                    ));
+                    if *selection == IMAGE_COMDAT_SELECT_ASSOCIATIVE {
+                        details.push_str(&format!(" with section {number}"));
+                    }
+                }
+                details
+            }
+            Some(AuxRecord::FunctionDefinition {
+                tag_index,
+                total_size,
+            }) => {
+                if *tag_index == 0 {
+                    format!("size 0x{total_size:x}")
+                } else {
+                    format!("size 0x{total_size:x}, debug info {}", tag_name(*tag_index))
+                }
+            }
+            Some(AuxRecord::Function { line_number }) => format!("line {line_number}"),
+            Some(AuxRecord::WeakExternal {
+                tag_index,
+                characteristics,
+            }) => format!(
+                "{} {}",
+                WEAK_EXTERNAL_NAMES
+                    .get(*characteristics as usize)
+                    .unwrap_or(&"unknown"),
+                tag_name(*tag_index)
+            ),
+            Some(AuxRecord::Other(count)) => format!("{count} auxiliary records"),
+        }
+    }
+
+    pub(super) fn to_vec(
+        &self,
+        symbols: &[CoffSymbol],
+        sections: &[SectionHeader],
+        demangle: bool,
+    ) -> Vec<String> {
+        vec![
+            if demangle {
+                crate::demangle::demangle(&self.name)
+            } else {
+                self.name.clone()
+            },
+            format!("0x{:08x}", self.value),
+            self.section_as_string(sections),
+            self.type_as_string(),
+            self.storage_class_as_string(),
+            self.aux_as_string(symbols),
+        ]
+    }
+}
+
+fn parse_aux_record(
+    blob: &Blob,
+    symbol: &CoffSymbol,
+    offset: usize,
+    count: u8,
+    symbol_size: usize,
+) -> Result<AuxRecord> {
+    let aux = match symbol.storage_class {
+        IMAGE_SYM_CLASS_FILE => {
+            let name = blob.get_slice(offset, count as usize * symbol_size)?;
+            let end = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            AuxRecord::File(String::from_utf8_lossy(&name[..end]).to_string())
+        }
+        IMAGE_SYM_CLASS_STATIC => AuxRecord::SectionDefinition {
+            length: blob.get_u32(offset)?,
+            relocations: blob.get_u16(offset + 4)?,
+            line_numbers: blob.get_u16(offset + 6)?,
+            checksum: blob.get_u32(offset + 8)?,
+            // Big object files store the upper half of the section number separately
+            number: blob.get_u16(offset + 12)? as u32
+                | if symbol_size > super::SYMBOL_SIZE {
+                    (blob.get_u16(offset + 16)? as u32) << 16
+                } else {
+                    0
+                },
+            selection: blob.get_u8(offset + 14)?,
+        },
+        IMAGE_SYM_CLASS_EXTERNAL
+            if symbol.symbol_type >> 4 == IMAGE_SYM_DTYPE_FUNCTION && symbol.section_number > 0 =>
+        {
+            AuxRecord::FunctionDefinition {
+                tag_index: blob.get_u32(offset)?,
+                total_size: blob.get_u32(offset + 4)?,
+            }
+        }
+        IMAGE_SYM_CLASS_FUNCTION => AuxRecord::Function {
+            line_number: blob.get_u16(offset + 4)?,
+        },
+        IMAGE_SYM_CLASS_WEAK_EXTERNAL => AuxRecord::WeakExternal {
+            tag_index: blob.get_u32(offset)?,
+            characteristics: blob.get_u32(offset + 4)?,
+        },
+        _ => AuxRecord::Other(count),
+    };
+    Ok(aux)
+}
+
+/// Parse `count` symbol table entries starting at `offset`, names longer than 8 bytes are
+/// stored in the string table
+pub(super) fn parse_symbols(
+    blob: &Blob,
+    offset: usize,
+    count: u32,
+    symbol_size: usize,
+    string_table_offset: usize,
+) -> Result<Vec<CoffSymbol>> {
+    let mut symbols = Vec::new();
+    let mut index = 0;
+    while index < count {
+        let entry = offset + index as usize * symbol_size;
+        let name = if blob.get_u32(entry)? == 0 {
+            blob.get_cname(Some(
+                string_table_offset + blob.get_u32(entry + 4)? as usize,
+            ))?
+        } else {
+            let name = blob.get_slice(entry, 8)?;
+            let end = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            String::from_utf8_lossy(&name[..end]).to_string()
+        };
+        // Big object files use 32 bit section numbers, shifting the remaining fields
+        let (section_number, rest) = if symbol_size > super::SYMBOL_SIZE {
+            (blob.get_u32(entry + 12)? as i32, entry + 16)
+        } else {
+            (blob.get_u16(entry + 12)? as i16 as i32, entry + 14)
+        };
+        let mut symbol = CoffSymbol {
+            index,
+            name,
+            value: blob.get_u32(entry + 8)?,
+            section_number,
+            symbol_type: blob.get_u16(rest)?,
+            storage_class: blob.get_u8(rest + 2)?,
+            aux: None,
+        };
+        let aux_count = blob.get_u8(rest + 3)?;
+        if aux_count > 0 {
+            symbol.aux = Some(parse_aux_record(
+                blob,
+                &symbol,
+                entry + symbol_size,
+                aux_count,
+                symbol_size,
+            )?);
+        }
+        symbols.push(symbol);
+        index += 1 + aux_count as u32;
+    }
+    Ok(symbols)
+}
@@ -0,0 +1,172 @@
+use super::*;
+
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+
+/// Symbol table entry without auxiliary records, `aux` only sets their count
+fn symbol(name: &[u8], value: u32, section: i32, symbol_type: u16, class: u8, aux: u8) -> Vec<u8> {
+    let mut entry = [0u8; 8];
+    entry[..name.len()].copy_from_slice(name);
+    let mut entry = entry.to_vec();
+    entry.extend_from_slice(&value.to_le_bytes());
+    entry.extend_from_slice(&(section as i16).to_le_bytes());
+    entry.extend_from_slice(&symbol_type.to_le_bytes());
+    entry.extend_from_slice(&[class, aux]);
+    entry
+}
+
+/// Symbol whose name is at `offset` in the string table
+fn long_symbol(offset: u32, value: u32, section: i32, class: u8) -> Vec<u8> {
+    let mut entry = symbol(b"", value, section, 0, class, 0);
+    entry[4..8].copy_from_slice(&offset.to_le_bytes());
+    entry
+}
+
+/// Big object files widen the section number to 32 bits
+fn big_symbol(name: &[u8], section: i32, symbol_type: u16, class: u8, aux: u8) -> Vec<u8> {
+    let mut entry = symbol(name, 0, 0, symbol_type, class, aux);
+    entry.splice(12..14, section.to_le_bytes());
+    entry
+}
+
+/// Plain object with a single empty .text section, the string table follows the symbols
+fn build_object_data(symbols: &[Vec<u8>], count: u32, strings: &[u8]) -> Vec<u8> {
+    let mut data = IMAGE_FILE_MACHINE_AMD64.to_le_bytes().to_vec();
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&((COFF_HEADER_SIZE + SECTION_HEADER_SIZE) as u32).to_le_bytes());
+    data.extend_from_slice(&count.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(b".text\0\0\0");
+    data.extend_from_slice(&[0; SECTION_HEADER_SIZE - 8]);
+    for entry in symbols {
+        data.extend_from_slice(entry);
+    }
+    data.extend_from_slice(&(strings.len() as u32 + 4).to_le_bytes());
+    data.extend_from_slice(strings);
+    data
+}
+
+fn object(symbols: &[Vec<u8>], count: u32, strings: &[u8]) -> CoffObject {
+    CoffObject::new(Blob::new(build_object_data(symbols, count, strings)).unwrap()).unwrap()
+}
+
+fn column(table: &Table, column: usize) -> Vec<String> {
+    table
+        .rows
+        .iter()
+        .map(|row| row.content[column].clone())
+        .collect()
+}
+
+#[test]
+fn big_object_header_widens_sections_and_symbols() {
+    let mut data = vec![0, 0, 0xff, 0xff];
+    data.extend_from_slice(&BIGOBJ_MIN_VERSION.to_le_bytes());
+    data.extend_from_slice(&IMAGE_FILE_MACHINE_AMD64.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&BIGOBJ_CLASS_ID);
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&((BIGOBJ_HEADER_SIZE + SECTION_HEADER_SIZE) as u32).to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(b".text$mn");
+    data.extend_from_slice(&[0; SECTION_HEADER_SIZE - 8]);
+    // Section definition associated with section 0x10002, its upper half follows the selection
+    data.extend(big_symbol(b".text$mn", 1, 0, 3, 1));
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&[5, 0]);
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&[0; 2]);
+    data.extend(big_symbol(b"main", 0x10001, 0x20, 2, 0));
+    data.extend_from_slice(&4u32.to_le_bytes());
+
+    let blob = Blob::new(data).unwrap();
+    assert!(matches!(blob.bin_type, BinaryType::Coff));
+    let mut object = CoffObject::new(blob).unwrap();
+    assert_eq!(object.ident(), "COFF big object file");
+    assert!(object
+        .header_info()
+        .contains(&("Big object version".to_string(), "2".to_string())));
+    let sections = object.section_headers_table().unwrap();
+    assert_eq!(column(&sections, 1), [".text$mn"]);
+    let symbols = object.symbols_table(false).unwrap();
+    assert_eq!(column(&symbols, 0), ["0", "2"]);
+    assert_eq!(column(&symbols, 3), [".text$mn", "65537"]);
+    assert_eq!(
+        symbols.rows[0].content[6],
+        "length 0x10, 0 relocations, 0 line numbers, checksum 0x00000000, \
+         COMDAT ASSOCIATIVE with section 65538"
+    );
+    assert_eq!(column(&symbols, 4), ["NULL", "FUNCTION"]);
+}
+
+#[test]
+fn aux_records_are_attached_to_their_symbol() {
+    let mut file = symbol(b".file", 0, -2, 0, 103, 2);
+    let mut name = b"a_long_source_name.c".to_vec();
+    name.resize(2 * SYMBOL_SIZE, 0);
+    file.extend(name);
+    let mut function = symbol(b"main", 0, 1, 0x20, 2, 1);
+    function.extend_from_slice(&0u32.to_le_bytes());
+    function.extend_from_slice(&0x42u32.to_le_bytes());
+    function.extend_from_slice(&[0; 10]);
+    let mut weak = long_symbol(4, 0, 0, 105);
+    weak[17] = 1;
+    weak.extend_from_slice(&3u32.to_le_bytes());
+    weak.extend_from_slice(&3u32.to_le_bytes());
+    weak.extend_from_slice(&[0; 10]);
+    let mut object = object(&[file, function, weak], 7, b"weak_alias\0");
+
+    let symbols = object.symbols_table(false).unwrap();
+    assert_eq!(column(&symbols, 0), ["0", "3", "5"]);
+    assert_eq!(column(&symbols, 1), [".file", "main", "weak_alias"]);
+    assert_eq!(column(&symbols, 3), ["DEBUG", ".text", "UNDEF"]);
+    assert_eq!(
+        column(&symbols, 6),
+        ["a_long_source_name.c", "size 0x42", "ALIAS #3 main"]
+    );
+}
+
+#[test]
+fn strings_are_listed_by_their_raw_length() {
+    // The first string is no valid UTF-8 and grows by the lossy conversion
+    let object = object(&[long_symbol(8, 0, 1, 2)], 1, b"\xff\xfe\xfd\0long_name\0");
+    let strings = object.strings_table().unwrap();
+    assert_eq!(column(&strings, 0), ["0x00000004", "0x00000008"]);
+    assert_eq!(strings.rows[1].content[1], "long_name");
+}
+
+#[test]
+fn files_without_a_valid_header_are_no_objects() {
+    let is_object = |data: Vec<u8>| matches!(Blob::new(data).unwrap().bin_type, BinaryType::Coff);
+    let valid = build_object_data(&[symbol(b"main", 0, 1, 0x20, 2, 0)], 1, b"");
+    assert!(is_object(valid.clone()));
+
+    assert!(!is_object(
+        b"Plain text starting with no machine type\n".to_vec()
+    ));
+    // Optional headers belong to images
+    let mut optional_header = valid.clone();
+    optional_header[16] = 0xe0;
+    assert!(!is_object(optional_header));
+    let mut no_tables = valid.clone();
+    no_tables[2] = 0;
+    no_tables[12] = 0;
+    assert!(!is_object(no_tables));
+    // Section headers behind the end of the file
+    let mut sections = valid.clone();
+    sections[2..4].copy_from_slice(&0x100u16.to_le_bytes());
+    assert!(!is_object(sections));
+    // String table shorter than its size field
+    let mut strings = valid.clone();
+    let size = strings.len() - 4;
+    strings[size..].copy_from_slice(&8u32.to_le_bytes());
+    assert!(!is_object(strings));
+    // Section data behind the end of the file
+    let mut raw_data = valid;
+    raw_data[COFF_HEADER_SIZE + 16..COFF_HEADER_SIZE + 24]
+        .copy_from_slice(&[0x10, 0, 0, 0, 0, 1, 0, 0]);
+    assert!(!is_object(raw_data));
+}
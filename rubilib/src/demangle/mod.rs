@@ -0,0 +1,152 @@
+use cpp_demangle::{DemangleOptions, Symbol};
+
+mod swift;
+#[cfg(test)]
+mod tests;
+
+use swift::demangle_swift;
+
+/// Demangle Rust (legacy and v0), Itanium C++, MSVC and Swift symbol names,
+/// names that are not mangled or fail to decode are returned unchanged.
+pub fn demangle(name: &str) -> String {
+    demangle_rust(name)
+        .or_else(|| demangle_itanium(name))
+        .or_else(|| demangle_msvc(name))
+        .or_else(|| demangle_swift(name))
+        .unwrap_or_else(|| name.to_string())
+}
+
+fn demangle_rust(name: &str) -> Option<String> {
+    // Legacy Rust symbols are valid Itanium names, only take them if they carry the hash
+    let demangled = rustc_demangle::try_demangle(name).ok()?;
+    let with_hash = demangled.to_string();
+    let without_hash = format!("{demangled:#}");
+    if name.starts_with("_R") || name.starts_with("__R") || with_hash != without_hash {
+        Some(without_hash)
+    } else {
+        None
+    }
+}
+
+fn demangle_itanium(name: &str) -> Option<String> {
+    // Mach-O adds an extra leading underscore
+    let name = name
+        .strip_prefix('_')
+        .filter(|n| n.starts_with("_Z"))
+        .unwrap_or(name);
+    if !name.starts_with("_Z") {
+        return None;
+    }
+    let symbol = Symbol::new(name).ok()?;
+    symbol.demangle(&DemangleOptions::default()).ok()
+}
+
+const MSVC_OPERATORS: [(&str, &str); 40] = [
+    ("2", "operator new"),
+    ("3", "operator delete"),
+    ("4", "operator="),
+    ("5", "operator>>"),
+    ("6", "operator<<"),
+    ("7", "operator!"),
+    ("8", "operator=="),
+    ("9", "operator!="),
+    ("A", "operator[]"),
+    ("B", "operator "),
+    ("C", "operator->"),
+    ("D", "operator*"),
+    ("E", "operator++"),
+    ("F", "operator--"),
+    ("G", "operator-"),
+    ("H", "operator+"),
+    ("I", "operator&"),
+    ("J", "operator->*"),
+    ("K", "operator/"),
+    ("L", "operator%"),
+    ("M", "operator<"),
+    ("N", "operator<="),
+    ("O", "operator>"),
+    ("P", "operator>="),
+    ("Q", "operator,"),
+    ("R", "operator()"),
+    ("S", "operator~"),
+    ("T", "operator^"),
+    ("U", "operator|"),
+    ("V", "operator&&"),
+    ("W", "operator||"),
+    ("X", "operator*="),
+    ("Y", "operator+="),
+    ("Z", "operator-="),
+    ("_0", "operator/="),
+    ("_1", "operator%="),
+    ("_2", "operator>>="),
+    ("_3", "operator<<="),
+    ("_7", "`vftable'"),
+    ("_8", "`vbtable'"),
+];
+
+enum SpecialName {
+    Constructor,
+    Destructor,
+    Operator(&'static str),
+}
+
+/// Qualified name of an MSVC decorated symbol, the signature is not decoded.
+/// Names containing templates or anonymous namespaces are not supported and give `None`.
+fn demangle_msvc(name: &str) -> Option<String> {
+    let mut rest = name.strip_prefix('?')?;
+    // Special names like constructors and operators start with a second '?'
+    let mut special = None;
+    if let Some(code) = rest.strip_prefix('?') {
+        let len = if code.starts_with('_') { 2 } else { 1 };
+        let op = code.get(..len)?;
+        special = Some(match op {
+            "0" => SpecialName::Constructor,
+            "1" => SpecialName::Destructor,
+            _ => SpecialName::Operator(
+                MSVC_OPERATORS
+                    .iter()
+                    .find(|(code, _)| *code == op)
+                    .map(|(_, name)| *name)?,
+            ),
+        });
+        rest = &code[len..];
+    }
+
+    // Name fragments, innermost first, terminated by '@@'; digits refer back to earlier fragments
+    let mut fragments: Vec<String> = Vec::new();
+    let mut backrefs: Vec<String> = Vec::new();
+    loop {
+        match rest.as_bytes().first()? {
+            b'@' => break,
+            b'0'..=b'9' => {
+                let idx = (rest.as_bytes()[0] - b'0') as usize;
+                fragments.push(backrefs.get(idx)?.clone());
+                rest = &rest[1..];
+            }
+            // Templates and anonymous namespaces are not decoded
+            b'?' => return None,
+            _ => {
+                let end = rest.find('@')?;
+                let fragment = rest[..end].to_string();
+                if backrefs.len() < 10 {
+                    backrefs.push(fragment.clone());
+                }
+                fragments.push(fragment);
+                rest = &rest[end + 1..];
+            }
+        }
+    }
+    if fragments.is_empty() {
+        return None;
+    }
+
+    let name = match special {
+        Some(SpecialName::Constructor) => fragments[0].clone(),
+        Some(SpecialName::Destructor) => format!("~{}", fragments[0]),
+        Some(SpecialName::Operator(operator)) => operator.to_string(),
+        None => fragments.remove(0),
+    };
+    fragments.reverse();
+    fragments.push(name);
+    Some(fragments.join("::"))
+}
@@ -0,0 +1,513 @@
+// Swift mangling is postfix: names and types are pushed onto a stack and operators combine
+// the topmost nodes, see docs/ABI/Mangling.rst in the Swift repository
+
+// Upper bound of repeated substitutions like `S2i`, as in the Swift demangler
+const MAX_REPEAT_COUNT: usize = 2048;
+// Words of identifiers that can be referenced by word substitutions
+const MAX_WORDS: usize = 26;
+
+const STANDARD_TYPES: [(u8, &str); 20] = [
+    (b'a', "Array"),
+    (b'b', "Bool"),
+    (b'D', "Dictionary"),
+    (b'd', "Double"),
+    (b'f', "Float"),
+    (b'h', "Set"),
+    (b'i', "Int"),
+    (b'J', "Character"),
+    (b'N', "ClosedRange"),
+    (b'n', "Range"),
+    (b'O', "ObjectIdentifier"),
+    (b'P', "UnsafeMutablePointer"),
+    (b'p', "UnsafePointer"),
+    (b'q', "Optional"),
+    (b'R', "UnsafeBufferPointer"),
+    (b'S', "String"),
+    (b's', "Substring"),
+    (b'u', "UInt"),
+    (b'V', "UnsafeRawPointer"),
+    (b'v', "UnsafeMutableRawPointer"),
+];
+
+#[derive(Clone)]
+enum Node {
+    // An identifier names a module when it is used as context
+    Identifier(String),
+    Module(String),
+    // Named type, also usable as the context of a declaration
+    Nominal(String),
+    Tuple(Vec<String>),
+    Function(FunctionType),
+    // Any other type, already printed
+    Type(String),
+    EmptyList,
+    FirstElementMarker,
+    Throws,
+    Async,
+    // Complete symbol like a function, variable or metadata record
+    Entity(String),
+}
+
+#[derive(Clone)]
+struct FunctionType {
+    params: Vec<String>,
+    result: String,
+    effects: &'static str,
+}
+
+impl FunctionType {
+    fn to_string_with_labels(&self, labels: &[Option<String>]) -> String {
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .zip(labels.iter().chain(std::iter::repeat(&None)))
+            .map(|(param, label)| match label {
+                Some(label) => format!("{label}: {param}"),
+                None => param.clone(),
+            })
+            .collect();
+        format!("({}){} -> {}", params.join(", "), self.effects, self.result)
+    }
+}
+
+impl Node {
+    fn as_type(&self) -> Option<String> {
+        match self {
+            Node::Nominal(name) | Node::Type(name) => Some(name.clone()),
+            Node::Tuple(elements) => Some(format!("({})", elements.join(", "))),
+            Node::Function(function) => Some(function.to_string_with_labels(&[])),
+            _ => None,
+        }
+    }
+}
+
+/// Demangle Swift 4.2 and later symbols (`$S` and `$s`, with or without the extra Mach-O
+/// underscore). Functions, variables and their accessors, initializers, deinitializers and
+/// type metadata records of non-generic types are decoded; generic signatures, extensions,
+/// closures, thunks, private names and Swift 4.0 `_T0` symbols give `None`.
+pub(super) fn demangle_swift(name: &str) -> Option<String> {
+    let name = name.strip_prefix('_').unwrap_or(name);
+    let text = name
+        .strip_prefix("$s")
+        .or_else(|| name.strip_prefix("$S"))?;
+    Demangler {
+        text: text.as_bytes(),
+        pos: 0,
+        stack: Vec::new(),
+        substitutions: Vec::new(),
+        words: Vec::new(),
+    }
+    .demangle()
+}
+
+struct Demangler<'a> {
+    text: &'a [u8],
+    pos: usize,
+    stack: Vec<Node>,
+    substitutions: Vec<Node>,
+    words: Vec<String>,
+}
+
+impl Demangler<'_> {
+    fn demangle(mut self) -> Option<String> {
+        while self.pos < self.text.len() {
+            let node = self.operator()?;
+            self.stack.push(node);
+        }
+        match self.stack.as_slice() {
+            [Node::Entity(entity)] => Some(entity.clone()),
+            _ => None,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn next_if(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn natural(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn pop_if(&mut self, predicate: impl Fn(&Node) -> bool) -> Option<Node> {
+        if predicate(self.stack.last()?) {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    fn pop_type(&mut self) -> Option<String> {
+        self.stack.pop()?.as_type()
+    }
+
+    fn pop_identifier(&mut self) -> Option<String> {
+        match self.stack.pop()? {
+            Node::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn pop_context(&mut self) -> Option<String> {
+        match self.stack.pop()? {
+            Node::Identifier(name) | Node::Module(name) | Node::Nominal(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn operator(&mut self) -> Option<Node> {
+        match self.next()? {
+            b'0'..=b'9' => {
+                self.pos -= 1;
+                self.identifier()
+            }
+            b'A' => self.substitution(),
+            b'C' | b'O' | b'P' | b'V' => self.nominal_type(),
+            b'F' => self.function(),
+            b'G' => self.bound_generic_type(),
+            b'K' => Some(Node::Throws),
+            b'M' => self.metadata(),
+            b'N' => Some(Node::Entity(format!(
+                "type metadata for {}",
+                self.pop_type()?
+            ))),
+            b'S' => self.standard_type(),
+            b'Y' if self.next_if(b'a') => Some(Node::Async),
+            b'Z' => match self.stack.pop()? {
+                Node::Entity(entity) => Some(Node::Entity(format!("static {entity}"))),
+                _ => None,
+            },
+            b'_' => Some(Node::FirstElementMarker),
+            b'c' => Some(Node::Function(self.pop_function_type()?)),
+            b'f' => self.special_function(),
+            b's' => Some(Node::Module("Swift".to_string())),
+            b't' => self.tuple(),
+            b'v' => self.variable(),
+            b'y' => Some(Node::EmptyList),
+            b'z' => Some(Node::Type(format!("inout {}", self.pop_type()?))),
+            _ => None,
+        }
+    }
+
+    /// Length prefixed identifier, a leading '0' introduces references to earlier words
+    fn identifier(&mut self) -> Option<Node> {
+        let mut has_word_substitutions = false;
+        if self.next_if(b'0') {
+            // Punycode encoded identifiers are not supported
+            if self.peek() == Some(b'0') {
+                return None;
+            }
+            has_word_substitutions = true;
+        }
+        let mut identifier = String::new();
+        loop {
+            while has_word_substitutions && self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                let c = self.next()?;
+                // Lower case letters continue the identifier, an upper case letter ends the
+                // word substitutions
+                let idx = if c.is_ascii_lowercase() {
+                    c - b'a'
+                } else {
+                    has_word_substitutions = false;
+                    c - b'A'
+                };
+                identifier.push_str(self.words.get(idx as usize)?);
+            }
+            if self.next_if(b'0') {
+                break;
+            }
+            let len = self.natural()?;
+            let end = self.pos.checked_add(len).filter(|_| len > 0)?;
+            let part = std::str::from_utf8(self.text.get(self.pos..end)?).ok()?;
+            self.collect_words(part);
+            identifier.push_str(part);
+            self.pos = end;
+            if !has_word_substitutions {
+                break;
+            }
+        }
+        let node = Node::Identifier(identifier);
+        self.substitutions.push(node.clone());
+        Some(node)
+    }
+
+    /// Remember the words of an identifier, which start at a letter or an upper case letter
+    /// following a lower case one and are at least two characters long
+    fn collect_words(&mut self, part: &str) {
+        let bytes = part.as_bytes();
+        let mut start = None;
+        for idx in 0..=bytes.len() {
+            let c = bytes.get(idx).copied().unwrap_or(0);
+            if let Some(word_start) = start {
+                let prev: u8 = bytes[idx - 1];
+                if c == b'_' || c == 0 || (!prev.is_ascii_uppercase() && c.is_ascii_uppercase()) {
+                    if idx - word_start >= 2 && self.words.len() < MAX_WORDS {
+                        self.words.push(part[word_start..idx].to_string());
+                    }
+                    start = None;
+                }
+            }
+            if start.is_none() && c != 0 && c != b'_' && !c.is_ascii_digit() {
+                start = Some(idx);
+            }
+        }
+    }
+
+    /// Reference to an earlier identifier or type, lower case letters push all but the last
+    fn substitution(&mut self) -> Option<Node> {
+        let mut repeat = None;
+        loop {
+            let c = self.next()?;
+            if c.is_ascii_lowercase() {
+                let node = self.repeated_substitution(repeat.take(), (c - b'a') as usize)?;
+                self.stack.push(node);
+            } else if c.is_ascii_uppercase() {
+                return self.repeated_substitution(repeat, (c - b'A') as usize);
+            } else if c == b'_' {
+                let idx = repeat.map_or(26, |repeat| repeat + 27);
+                return self.substitutions.get(idx).cloned();
+            } else {
+                self.pos -= 1;
+                repeat = Some(self.natural()?);
+            }
+        }
+    }
+
+    fn repeated_substitution(&mut self, repeat: Option<usize>, idx: usize) -> Option<Node> {
+        let node = self.substitutions.get(idx)?.clone();
+        let repeat = repeat.unwrap_or(1);
+        if repeat > MAX_REPEAT_COUNT {
+            return None;
+        }
+        for _ in 1..repeat {
+            self.stack.push(node.clone());
+        }
+        Some(node)
+    }
+
+    /// Class, enum, protocol or struct named by an identifier in a context
+    fn nominal_type(&mut self) -> Option<Node> {
+        let name = self.pop_identifier()?;
+        let context = self.pop_context()?;
+        let node = Node::Nominal(format!("{context}.{name}"));
+        self.substitutions.push(node.clone());
+        Some(node)
+    }
+
+    fn standard_type(&mut self) -> Option<Node> {
+        if self.next_if(b'o') {
+            return Some(Node::Module("__C".to_string()));
+        }
+        if self.next_if(b'g') {
+            let node = Node::Type(format!("Swift.Optional<{}>", self.pop_type()?));
+            self.substitutions.push(node.clone());
+            return Some(node);
+        }
+        let repeat = if self.peek()?.is_ascii_digit() {
+            self.natural()?
+        } else {
+            1
+        };
+        if repeat > MAX_REPEAT_COUNT {
+            return None;
+        }
+        let c = self.next()?;
+        let name = STANDARD_TYPES
+            .iter()
+            .find(|(code, _)| *code == c)
+            .map(|(_, name)| *name)?;
+        let node = Node::Nominal(format!("Swift.{name}"));
+        for _ in 1..repeat {
+            self.stack.push(node.clone());
+        }
+        Some(node)
+    }
+
+    /// Generic type with its arguments, like `Swift.Array<Swift.Int>`
+    fn bound_generic_type(&mut self) -> Option<Node> {
+        let mut arguments = Vec::new();
+        while let Some(argument) = self.pop_if(|node| node.as_type().is_some()) {
+            arguments.push(argument.as_type()?);
+        }
+        arguments.reverse();
+        // Arguments of generic parents are separated by '_' and not supported
+        self.pop_if(|node| matches!(node, Node::EmptyList))?;
+        let Node::Nominal(name) = self.stack.pop()? else {
+            return None;
+        };
+        let node = Node::Type(format!("{name}<{}>", arguments.join(", ")));
+        self.substitutions.push(node.clone());
+        Some(node)
+    }
+
+    /// Tuple elements are types optionally followed by a label, the first one by a '_'
+    fn tuple(&mut self) -> Option<Node> {
+        if self
+            .pop_if(|node| matches!(node, Node::EmptyList))
+            .is_some()
+        {
+            return Some(Node::Tuple(Vec::new()));
+        }
+        let mut elements = Vec::new();
+        loop {
+            let first = self
+                .pop_if(|node| matches!(node, Node::FirstElementMarker))
+                .is_some();
+            let label = self.pop_if(|node| matches!(node, Node::Identifier(_)));
+            let element = self.pop_type()?;
+            elements.push(match label {
+                Some(Node::Identifier(label)) => format!("{label}: {element}"),
+                _ => element,
+            });
+            if first {
+                break;
+            }
+        }
+        elements.reverse();
+        Some(Node::Tuple(elements))
+    }
+
+    fn pop_params(&mut self) -> Option<Vec<String>> {
+        match self.stack.pop()? {
+            Node::EmptyList => Some(Vec::new()),
+            Node::Tuple(elements) => Some(elements),
+            node => Some(vec![node.as_type()?]),
+        }
+    }
+
+    /// Parameters come on top of the result, preceded by the effects
+    fn pop_function_type(&mut self) -> Option<FunctionType> {
+        let throws = self.pop_if(|node| matches!(node, Node::Throws)).is_some();
+        let is_async = self.pop_if(|node| matches!(node, Node::Async)).is_some();
+        let effects = match (is_async, throws) {
+            (true, true) => " async throws",
+            (true, false) => " async",
+            (false, true) => " throws",
+            (false, false) => "",
+        };
+        let params = self.pop_params()?;
+        let result = match self.pop_params()?.as_slice() {
+            [result] => result.clone(),
+            results => format!("({})", results.join(", ")),
+        };
+        Some(FunctionType {
+            params,
+            result,
+            effects,
+        })
+    }
+
+    /// Argument labels follow the function name, either one per parameter or an empty list
+    /// if no parameter has a label
+    fn pop_labels(&mut self, count: usize) -> Option<Vec<Option<String>>> {
+        if self
+            .pop_if(|node| matches!(node, Node::EmptyList))
+            .is_some()
+        {
+            return Some(Vec::new());
+        }
+        let mut labels = Vec::with_capacity(count);
+        for _ in 0..count {
+            labels.push(match self.stack.pop()? {
+                Node::Identifier(label) => Some(label),
+                Node::FirstElementMarker => None,
+                _ => return None,
+            });
+        }
+        labels.reverse();
+        Some(labels)
+    }
+
+    fn function(&mut self) -> Option<Node> {
+        let function = self.pop_function_type()?;
+        let labels = self.pop_labels(function.params.len())?;
+        let name = self.pop_identifier()?;
+        let context = self.pop_context()?;
+        Some(Node::Entity(format!(
+            "{context}.{name}{}",
+            function.to_string_with_labels(&labels)
+        )))
+    }
+
+    /// Initializers and deinitializers
+    fn special_function(&mut self) -> Option<Node> {
+        let name = match self.next()? {
+            b'C' => "__allocating_init",
+            b'c' => "init",
+            b'D' => "__deallocating_deinit",
+            b'd' => "deinit",
+            _ => return None,
+        };
+        if name.ends_with("deinit") {
+            return Some(Node::Entity(format!("{}.{name}", self.pop_context()?)));
+        }
+        let Node::Function(function) = self.stack.pop()? else {
+            return None;
+        };
+        let labels = self.pop_labels(function.params.len())?;
+        let context = self.pop_context()?;
+        Some(Node::Entity(format!(
+            "{context}.{name}{}",
+            function.to_string_with_labels(&labels)
+        )))
+    }
+
+    /// Variable with the accessor following its type
+    fn variable(&mut self) -> Option<Node> {
+        let variable_type = self.pop_type()?;
+        let name = self.pop_identifier()?;
+        let context = self.pop_context()?;
+        let accessor = match self.next()? {
+            b'p' => "",
+            b'g' => ".getter",
+            b's' => ".setter",
+            b'M' => ".modify",
+            b'r' => ".read",
+            b'w' => ".willset",
+            b'W' => ".didset",
+            _ => return None,
+        };
+        Some(Node::Entity(format!(
+            "{context}.{name}{accessor} : {variable_type}"
+        )))
+    }
+
+    fn metadata(&mut self) -> Option<Node> {
+        let description = match self.next()? {
+            b'a' => "type metadata accessor for",
+            b'f' => "full type metadata for",
+            b'm' => "metaclass for",
+            b'n' => "nominal type descriptor for",
+            // Protocols are referenced by name and context only
+            b'p' => {
+                let name = self.pop_identifier()?;
+                let context = self.pop_context()?;
+                return Some(Node::Entity(format!(
+                    "protocol descriptor for {context}.{name}"
+                )));
+            }
+            _ => return None,
+        };
+        Some(Node::Entity(format!("{description} {}", self.pop_type()?)))
+    }
+}
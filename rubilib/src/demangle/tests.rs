@@ -0,0 +1,111 @@
+use super::*;
+
+#[test]
+fn rust_and_itanium_names_are_demangled() {
+    assert_eq!(
+        demangle("_ZN4core3fmt5write17h0123456789abcdefE"),
+        "core::fmt::write"
+    );
+    assert_eq!(demangle("_ZN3foo3barEi"), "foo::bar(int)");
+    // Mach-O adds an extra leading underscore
+    assert_eq!(demangle("__ZN3foo3barEi"), "foo::bar(int)");
+    assert_eq!(demangle("main"), "main");
+}
+
+#[test]
+fn msvc_qualified_names_are_demangled() {
+    assert_eq!(demangle("?foo@bar@@YAXXZ"), "bar::foo");
+    assert_eq!(demangle("??0Widget@ui@@QEAA@XZ"), "ui::Widget::Widget");
+    assert_eq!(demangle("??1Widget@ui@@QEAA@XZ"), "ui::Widget::~Widget");
+    assert_eq!(
+        demangle("??4Widget@ui@@QEAAAEAV01@AEBV01@@Z"),
+        "ui::Widget::operator="
+    );
+    // Back reference to the third fragment "ui", the name itself is the first one
+    assert_eq!(demangle("?draw@Widget@ui@2@SAXXZ"), "ui::ui::Widget::draw");
+}
+
+#[test]
+fn msvc_templates_and_anonymous_namespaces_stay_mangled() {
+    // std::max<int>
+    assert_eq!(demangle_msvc("??$max@H@std@@YAHAEBH0@Z"), None);
+    // Member of a class template Box<int>
+    assert_eq!(demangle_msvc("?get@?$Box@H@@QEAAHXZ"), None);
+    assert_eq!(demangle_msvc("?helper@?A0x1b2c3d4e@@YAXXZ"), None);
+    assert_eq!(
+        demangle("?helper@?A0x1b2c3d4e@@YAXXZ"),
+        "?helper@?A0x1b2c3d4e@@YAXXZ"
+    );
+}
+
+#[test]
+fn swift_functions_are_demangled() {
+    assert_eq!(demangle("$s4main3fooyyF"), "main.foo() -> ()");
+    assert_eq!(
+        demangle("_$s4main5helloyySSF"),
+        "main.hello(Swift.String) -> ()"
+    );
+    // Labels of both parameters, the result Int shares the repetition with the first one
+    assert_eq!(
+        demangle("$s4main3add1a1bS2i_SitF"),
+        "main.add(a: Swift.Int, b: Swift.Int) -> Swift.Int"
+    );
+    assert_eq!(
+        demangle("$s4main6ParserV5parseySaySiGSSKF"),
+        "main.Parser.parse(Swift.String) throws -> Swift.Array<Swift.Int>"
+    );
+    assert_eq!(
+        demangle("$s4main6ParserV5resetyyFZ"),
+        "static main.Parser.reset() -> ()"
+    );
+}
+
+#[test]
+fn swift_members_and_metadata_are_demangled() {
+    assert_eq!(
+        demangle("$s4main3FooC5countSivg"),
+        "main.Foo.count.getter : Swift.Int"
+    );
+    assert_eq!(
+        demangle("$s4main3FooC5countSiSgvs"),
+        "main.Foo.count.setter : Swift.Optional<Swift.Int>"
+    );
+    // The result type main.Foo is a substitution of the class
+    assert_eq!(
+        demangle("$s4main3FooC5countACSi_tcfC"),
+        "main.Foo.__allocating_init(count: Swift.Int) -> main.Foo"
+    );
+    assert_eq!(demangle("$s4main3FooCfD"), "main.Foo.__deallocating_deinit");
+    assert_eq!(
+        demangle("$s4main3FooCMa"),
+        "type metadata accessor for main.Foo"
+    );
+    assert_eq!(demangle("$s4main5PointVN"), "type metadata for main.Point");
+    assert_eq!(
+        demangle("$s4main5PointVMn"),
+        "nominal type descriptor for main.Point"
+    );
+}
+
+#[test]
+fn swift_word_substitutions_are_expanded() {
+    // The method name "World" refers to the third word, after "main" and "Hello"
+    assert_eq!(
+        demangle("$s4main10HelloWorldV0C0yyF"),
+        "main.HelloWorld.World() -> ()"
+    );
+}
+
+#[test]
+fn unsupported_swift_symbols_stay_mangled() {
+    // Generic function with a generic signature
+    assert_eq!(demangle("$s4main8identityyxxlF"), "$s4main8identityyxxlF");
+    // Swift 4.0 mangling
+    assert_eq!(demangle("_T04main3fooyyF"), "_T04main3fooyyF");
+    // Truncated and overlong identifiers
+    assert_eq!(demangle("$s4mai"), "$s4mai");
+    assert_eq!(
+        demangle("$s99999999999999999999999main"),
+        "$s99999999999999999999999main"
+    );
+}
@@ -0,0 +1,165 @@
+use super::{
+    dynamic::DynamicTag, program_headers::SegmentType, ElfBinary, ElfSectionType, MachineType,
+    Result,
+};
+
+const ET_REL: u16 = 1;
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+
+const PF_X: u32 = 0x1;
+const DF_BIND_NOW: u64 = 0x8;
+const DF_1_NOW: u64 = 0x1;
+const DF_1_PIE: u64 = 0x8000000;
+
+const CANARY_SYMBOLS: [&str; 4] = [
+    "__stack_chk_fail",
+    "__stack_chk_fail_local",
+    "__stack_chk_guard",
+    "__intel_security_cookie",
+];
+
+/// Hardening properties of the binary, sections, program headers, dynamic
+/// section, symbols and notes have to be loaded
+pub(super) fn checksec(binary: &ElfBinary) -> Result<Vec<(String, String)>> {
+    let dynamic_value = |tag: DynamicTag| {
+        binary
+            .dynamic
+            .iter()
+            .find(|entry| entry.tag == tag)
+            .map(|entry| entry.value)
+    };
+    let has_segment = |segment_type: SegmentType| {
+        binary
+            .program_headers
+            .iter()
+            .any(|segment| segment.segment_type == segment_type)
+    };
+    let flags = dynamic_value(DynamicTag::Flags).unwrap_or_default();
+    let flags_1 = dynamic_value(DynamicTag::Flags1).unwrap_or_default();
+
+    let mut info = Vec::new();
+
+    let pie = match binary.header.elf_type {
+        ET_EXEC => "No PIE",
+        ET_DYN if flags_1 & DF_1_PIE != 0 || has_segment(SegmentType::Interp) => "PIE enabled",
+        ET_DYN => "DSO",
+        ET_REL => "REL",
+        _ => "n/a",
+    };
+    info.push(("PIE".to_string(), pie.to_string()));
+
+    let nx = match binary
+        .program_headers
+        .iter()
+        .find(|segment| segment.segment_type == SegmentType::GnuStack)
+    {
+        Some(segment) if segment.flags & PF_X != 0 => "NX disabled (executable stack)",
+        Some(_) => "NX enabled",
+        None => "NX disabled (no PT_GNU_STACK)",
+    };
+    info.push(("NX".to_string(), nx.to_string()));
+
+    let bind_now = dynamic_value(DynamicTag::BindNow).is_some()
+        || flags & DF_BIND_NOW != 0
+        || flags_1 & DF_1_NOW != 0;
+    let relro = match (has_segment(SegmentType::GnuRelro), bind_now) {
+        (true, true) => "Full RELRO",
+        (true, false) => "Partial RELRO",
+        (false, _) => "No RELRO",
+    };
+    info.push(("RELRO".to_string(), relro.to_string()));
+
+    let mut names = Vec::new();
+    let mut imports = Vec::new();
+    for symbol in binary.dyn_symbols.iter().chain(binary.symbols.iter()) {
+        if symbol.name().is_some() {
+            let name = binary.blob.get_cname(symbol.name())?;
+            if symbol.section_index() == 0 {
+                imports.push(name.clone());
+            }
+            names.push(name);
+        }
+    }
+    let canary = if names
+        .iter()
+        .any(|name| CANARY_SYMBOLS.contains(&name.as_str()))
+    {
+        "Canary found"
+    } else {
+        "No canary found"
+    };
+    info.push(("Stack canary".to_string(), canary.to_string()));
+
+    // Fortified functions are imported as __<name>_chk, static binaries define them
+    let fortify_source = if imports.is_empty() { &names } else { &imports };
+    let mut fortified = fortify_source
+        .iter()
+        .filter(|name| {
+            name.starts_with("__")
+                && name.ends_with("_chk")
+                && !CANARY_SYMBOLS.contains(&name.as_str())
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    fortified.sort();
+    fortified.dedup();
+    let fortify = if fortified.is_empty() {
+        "No".to_string()
+    } else {
+        format!("Yes ({}): {}", fortified.len(), fortified.join(" "))
+    };
+    info.push(("FORTIFY_SOURCE".to_string(), fortify));
+
+    for (tag, label) in [
+        (DynamicTag::RPath, "RPATH"),
+        (DynamicTag::RunPath, "RUNPATH"),
+    ] {
+        let mut paths = Vec::new();
+        for entry in binary.dynamic.iter().filter(|entry| entry.tag == tag) {
+            if let Some(path) =
+                entry.string_value(&binary.blob, binary.dynamic_string_table_offset)?
+            {
+                paths.push(path);
+            }
+        }
+        let value = if paths.is_empty() {
+            format!("No {label}")
+        } else {
+            paths.join(":")
+        };
+        info.push((label.to_string(), value));
+    }
+
+    let symbol_tables = binary
+        .section_headers
+        .iter()
+        .filter(|section| section.section_type == ElfSectionType::SymTab)
+        .count();
+    let stripped = if symbol_tables == 0 {
+        "Stripped".to_string()
+    } else {
+        format!("Not stripped ({} symbols)", binary.symbols.len())
+    };
+    info.push(("Symbols".to_string(), stripped));
+
+    let label = match binary.header.machine {
+        MachineType::AmdX64 | MachineType::X86 => Some("CET"),
+        MachineType::Arm64 => Some("BTI/PAC"),
+        _ => None,
+    };
+    if let Some(label) = label {
+        let mut features = Vec::new();
+        for note in &binary.notes {
+            features.append(&mut note.control_flow_features(&binary.blob, binary.id.is_64())?);
+        }
+        let value = if features.is_empty() {
+            "None".to_string()
+        } else {
+            features.join(" ")
+        };
+        info.push((label.to_string(), value));
+    }
+
+    Ok(info)
+}
@@ -0,0 +1,259 @@
+use strum::FromRepr;
+
+use super::{ElfError, Result};
+use crate::blob::Blob;
+
+#[repr(u64)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum DynamicTag {
+    Null = 0,            // Marks end of dynamic array.
+    Needed = 1,          // String table offset of needed library.
+    PltRelSz = 2,        // Size of relocation entries in PLT.
+    PltGot = 3,          // Address associated with linkage table.
+    Hash = 4,            // Address of symbolic hash table.
+    StrTab = 5,          // Address of dynamic string table.
+    SymTab = 6,          // Address of dynamic symbol table.
+    Rela = 7,            // Address of relocation table (Rela entries).
+    RelaSz = 8,          // Size of Rela relocation table.
+    RelaEnt = 9,         // Size of a Rela relocation entry.
+    StrSz = 10,          // Total size of the string table.
+    SymEnt = 11,         // Size of a symbol table entry.
+    Init = 12,           // Address of initialization function.
+    Fini = 13,           // Address of termination function.
+    SoName = 14,         // String table offset of a shared objects name.
+    RPath = 15,          // String table offset of library search path.
+    Symbolic = 16,       // Changes symbol resolution algorithm.
+    Rel = 17,            // Address of relocation table (Rel entries).
+    RelSz = 18,          // Size of Rel relocation table.
+    RelEnt = 19,         // Size of a Rel relocation entry.
+    PltRel = 20,         // Type of relocation entry used for linking.
+    Debug = 21,          // Reserved for debugger.
+    TextRel = 22,        // Relocations exist for non-writable segments.
+    JmpRel = 23,         // Address of relocations associated with PLT.
+    BindNow = 24,        // Process all relocations before execution.
+    InitArray = 25,      // Pointer to array of initialization functions.
+    FiniArray = 26,      // Pointer to array of termination functions.
+    InitArraySz = 27,    // Size of DT_INIT_ARRAY.
+    FiniArraySz = 28,    // Size of DT_FINI_ARRAY.
+    RunPath = 29,        // String table offset of lib search path.
+    Flags = 30,          // Flags.
+    PreInitArray = 32,   // Pointer to array of preinit functions.
+    PreInitArraySz = 33, // Size of the DT_PREINIT_ARRAY array.
+    SymTabShNdx = 34,    // Address of the SHT_SYMTAB_SHNDX section.
+    RelrSz = 35,         // Size of Relr relocation table.
+    Relr = 36,           // Address of relocation table (Relr entries).
+    RelrEnt = 37,        // Size of a Relr relocation entry.
+
+    // Start of OS-specific tags
+    AndroidRel = 0x6000000f,
+    AndroidRelSz = 0x60000010,
+    AndroidRela = 0x60000011,
+    AndroidRelaSz = 0x60000012,
+    AndroidRelr = 0x6fffe000,
+    AndroidRelrSz = 0x6fffe001,
+    AndroidRelrEnt = 0x6fffe003,
+    GnuPrelinked = 0x6ffffdf5,  // Prelinking timestamp.
+    GnuConflictSz = 0x6ffffdf6, // Size of conflict section.
+    GnuLibListSz = 0x6ffffdf7,  // Size of library list.
+    Checksum = 0x6ffffdf8,      // Checksum of the object.
+    PltPadSz = 0x6ffffdf9,      // Size of PLT padding.
+    MoveEnt = 0x6ffffdfa,       // Size of a move table entry.
+    MoveSz = 0x6ffffdfb,        // Size of the move table.
+    Feature1 = 0x6ffffdfc,      // Feature selection.
+    PosFlag1 = 0x6ffffdfd,      // Flags for the following entry.
+    SymInSz = 0x6ffffdfe,       // Size of syminfo table.
+    SymInEnt = 0x6ffffdff,      // Size of a syminfo entry.
+    GnuHash = 0x6ffffef5,       // Reference to the GNU hash table.
+    TlsDescPlt = 0x6ffffef6,    // Location of PLT entry for TLS descriptor resolver calls.
+    TlsDescGot = 0x6ffffef7,    // Location of GOT entry used by TLS descriptor resolver PLT entry.
+    GnuConflict = 0x6ffffef8,   // Start of conflict section.
+    GnuLibList = 0x6ffffef9,    // Library list.
+    Config = 0x6ffffefa,        // Configuration information.
+    DepAudit = 0x6ffffefb,      // Dependency auditing.
+    Audit = 0x6ffffefc,         // Object auditing.
+    PltPad = 0x6ffffefd,        // PLT padding.
+    MoveTab = 0x6ffffefe,       // Move table.
+    SymInfo = 0x6ffffeff,       // Syminfo table.
+    VerSym = 0x6ffffff0,        // The address of .gnu.version section.
+    RelaCount = 0x6ffffff9,     // Number of relative Rela relocations.
+    RelCount = 0x6ffffffa,      // Number of relative Rel relocations.
+    Flags1 = 0x6ffffffb,        // Flags_1.
+    VerDef = 0x6ffffffc,        // The address of the version definition table.
+    VerDefNum = 0x6ffffffd,     // The number of entries in DT_VERDEF.
+    VerNeed = 0x6ffffffe,       // The address of the version dependency table.
+    VerNeedNum = 0x6fffffff,    // The number of entries in DT_VERNEED.
+
+    // Start of processor-specific tags
+    Auxiliary = 0x7ffffffd, // Shared object to load before self.
+    Filter = 0x7fffffff,    // Shared object to get values from.
+
+    Unknown = 0x87654321,
+}
+
+const DT_FLAGS_NAMES: [(u64, &str); 5] = [
+    (0x1, "ORIGIN"),
+    (0x2, "SYMBOLIC"),
+    (0x4, "TEXTREL"),
+    (0x8, "BIND_NOW"),
+    (0x10, "STATIC_TLS"),
+];
+
+const DT_FLAGS_1_NAMES: [(u64, &str); 31] = [
+    (0x1, "NOW"),
+    (0x2, "GLOBAL"),
+    (0x4, "GROUP"),
+    (0x8, "NODELETE"),
+    (0x10, "LOADFLTR"),
+    (0x20, "INITFIRST"),
+    (0x40, "NOOPEN"),
+    (0x80, "ORIGIN"),
+    (0x100, "DIRECT"),
+    (0x200, "TRANS"),
+    (0x400, "INTERPOSE"),
+    (0x800, "NODEFLIB"),
+    (0x1000, "NODUMP"),
+    (0x2000, "CONFALT"),
+    (0x4000, "ENDFILTEE"),
+    (0x8000, "DISPRELDNE"),
+    (0x10000, "DISPRELPND"),
+    (0x20000, "NODIRECT"),
+    (0x40000, "IGNMULDEF"),
+    (0x80000, "NOKSYMS"),
+    (0x100000, "NOHDR"),
+    (0x200000, "EDITED"),
+    (0x400000, "NORELOC"),
+    (0x800000, "SYMINTPOSE"),
+    (0x1000000, "GLOBAUDIT"),
+    (0x2000000, "SINGLETON"),
+    (0x4000000, "STUB"),
+    (0x8000000, "PIE"),
+    (0x10000000, "KMOD"),
+    (0x20000000, "WEAKFILTER"),
+    (0x40000000, "NOCOMMON"),
+];
+
+fn flags_as_string(value: u64, names: &[(u64, &str)]) -> String {
+    let mut flags = Vec::new();
+    let mut rest = value;
+    for (flag, name) in names {
+        if value & flag != 0 {
+            flags.push(name.to_string());
+            rest &= !flag;
+        }
+    }
+    if rest != 0 {
+        flags.push(format!("0x{rest:x}"));
+    }
+    flags.join(" ")
+}
+
+pub struct DynamicEntry {
+    // Entry tag, raw value kept for unknown tags
+    pub(super) d_tag: u64,
+    pub(super) tag: DynamicTag,
+    // Integer value or address, depending on the tag
+    pub(super) value: u64,
+}
+
+impl DynamicEntry {
+    pub(super) fn new(blob: &Blob, offset: usize, is_64: bool) -> Result<Self> {
+        let (d_tag, value) = if is_64 {
+            (blob.get_u64(offset)?, blob.get_u64(offset + 8)?)
+        } else {
+            (
+                blob.get_u32(offset)? as u64,
+                blob.get_u32(offset + 4)? as u64,
+            )
+        };
+        Ok(Self {
+            d_tag,
+            tag: DynamicTag::from_repr(d_tag).unwrap_or(DynamicTag::Unknown),
+            value,
+        })
+    }
+
+    /// Whether the value is an offset into the dynamic string table
+    pub(super) fn has_string_value(&self) -> bool {
+        matches!(
+            self.tag,
+            DynamicTag::Needed
+                | DynamicTag::SoName
+                | DynamicTag::RPath
+                | DynamicTag::RunPath
+                | DynamicTag::Config
+                | DynamicTag::DepAudit
+                | DynamicTag::Audit
+                | DynamicTag::Auxiliary
+                | DynamicTag::Filter
+        )
+    }
+
+    /// Resolve a string value through the dynamic string table
+    pub(super) fn string_value(
+        &self,
+        blob: &Blob,
+        string_table_offset: Option<usize>,
+    ) -> Result<Option<String>> {
+        match string_table_offset {
+            Some(string_table_offset) if self.has_string_value() => {
+                let offset = string_table_offset
+                    .checked_add(self.value as usize)
+                    .ok_or(ElfError::InternalError)?;
+                Ok(Some(blob.get_cname(Some(offset))?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn value_as_string(&self, blob: &Blob, string_table_offset: Option<usize>) -> String {
+        // An unresolvable string falls through to the raw value
+        if let Ok(Some(string)) = self.string_value(blob, string_table_offset) {
+            return string;
+        }
+        match self.tag {
+            DynamicTag::Flags => flags_as_string(self.value, &DT_FLAGS_NAMES),
+            DynamicTag::Flags1 => flags_as_string(self.value, &DT_FLAGS_1_NAMES),
+            DynamicTag::PltRel => match self.value {
+                7 => "RELA".to_string(),
+                17 => "REL".to_string(),
+                _ => format!("0x{:x}", self.value),
+            },
+            DynamicTag::PltRelSz
+            | DynamicTag::RelaSz
+            | DynamicTag::RelaEnt
+            | DynamicTag::StrSz
+            | DynamicTag::SymEnt
+            | DynamicTag::RelSz
+            | DynamicTag::RelEnt
+            | DynamicTag::InitArraySz
+            | DynamicTag::FiniArraySz
+            | DynamicTag::PreInitArraySz
+            | DynamicTag::RelrSz
+            | DynamicTag::RelrEnt
+            | DynamicTag::AndroidRelSz
+            | DynamicTag::AndroidRelaSz
+            | DynamicTag::AndroidRelrSz
+            | DynamicTag::AndroidRelrEnt
+            | DynamicTag::GnuConflictSz
+            | DynamicTag::GnuLibListSz
+            | DynamicTag::PltPadSz
+            | DynamicTag::MoveEnt
+            | DynamicTag::MoveSz
+            | DynamicTag::SymInSz
+            | DynamicTag::SymInEnt => format!("{} (bytes)", self.value),
+            DynamicTag::RelaCount
+            | DynamicTag::RelCount
+            | DynamicTag::VerDefNum
+            | DynamicTag::VerNeedNum => self.value.to_string(),
+            _ => format!("0x{:016x}", self.value),
+        }
+    }
+
+    pub(super) fn to_vec(&self, blob: &Blob, string_table_offset: Option<usize>) -> Vec<String> {
+        let mut v = Vec::with_capacity(3);
+        v.push(format!("0x{:016x}", self.d_tag));
+        v.push(format!("{:?}", self.tag));
+        v.push(self.value_as_string(blob, string_table_offset));
+        v
+    }
+}
@@ -1,4 +1,5 @@
 use crate::blob::{BinaryType, Blob, BlobError};
+use crate::overlay::Overlay;
 use crate::table::{Row, RowAction, Table, TableType};
 use std::fmt::{self, Display};
 use strum::FromRepr;
@@ -6,9 +7,26 @@ use thiserror::Error;
 
 type Result<T> = std::result::Result<T, ElfError>;
 
+mod checksec;
+mod dynamic;
+mod notes;
+mod plt;
+mod program_headers;
+mod relocation_types;
+mod relocations;
 mod symbols;
+#[cfg(test)]
+mod tests;
+mod versions;
 
-use symbols::Symbol64;
+pub use plt::PltEntry;
+
+use dynamic::{DynamicEntry, DynamicTag};
+use notes::Note;
+use program_headers::{ProgramHeader, SegmentType};
+use relocations::Relocation;
+use symbols::Symbol;
+use versions::{VersionDefinition, VersionNeed};
 
 #[derive(Error, Debug, Clone)]
 pub enum ElfError {
@@ -41,6 +59,11 @@ impl ElfIdent {
             padding: slice[5..12].try_into().unwrap(),
         })
     }
+
+    /// ELFCLASS64 files use 64 bit addresses and offsets, all others are parsed as ELFCLASS32
+    pub fn is_64(&self) -> bool {
+        self.class == 2
+    }
 }
 
 impl Display for ElfIdent {
@@ -264,7 +287,12 @@ pub struct SectionHeader {
 }
 
 impl SectionHeader {
-    fn new(blob: &Blob, offset: usize, header_string_table_offset: usize) -> Result<Self> {
+    fn new(
+        blob: &Blob,
+        offset: usize,
+        header_string_table_offset: usize,
+        is_64: bool,
+    ) -> Result<Self> {
         let name_addr = header_string_table_offset + (blob.get_u32(offset)? as usize);
         let name = if name_addr == 0 {
             None
@@ -280,18 +308,33 @@ impl SectionHeader {
                 blob.get_u32(offset + 4)?
             );
         }
-        Ok(Self {
-            name,
-            section_type,
-            flags: blob.get_u64(offset + 8)?,
-            addr: blob.get_u64(offset + 16)?,
-            offset: blob.get_u64(offset + 24)?,
-            size: blob.get_u64(offset + 32)?,
-            link: blob.get_u32(offset + 40)?,
-            info: blob.get_u32(offset + 44)?,
-            addr_align: blob.get_u64(offset + 48)?,
-            ent_size: blob.get_u64(offset + 56)?,
-        })
+        if is_64 {
+            Ok(Self {
+                name,
+                section_type,
+                flags: blob.get_u64(offset + 8)?,
+                addr: blob.get_u64(offset + 16)?,
+                offset: blob.get_u64(offset + 24)?,
+                size: blob.get_u64(offset + 32)?,
+                link: blob.get_u32(offset + 40)?,
+                info: blob.get_u32(offset + 44)?,
+                addr_align: blob.get_u64(offset + 48)?,
+                ent_size: blob.get_u64(offset + 56)?,
+            })
+        } else {
+            Ok(Self {
+                name,
+                section_type,
+                flags: blob.get_u32(offset + 8)? as u64,
+                addr: blob.get_u32(offset + 12)? as u64,
+                offset: blob.get_u32(offset + 16)? as u64,
+                size: blob.get_u32(offset + 20)? as u64,
+                link: blob.get_u32(offset + 24)?,
+                info: blob.get_u32(offset + 28)?,
+                addr_align: blob.get_u32(offset + 32)? as u64,
+                ent_size: blob.get_u32(offset + 36)? as u64,
+            })
+        }
     }
 
     fn flags_as_string(&self) -> String {
@@ -334,27 +377,45 @@ impl SectionHeader {
 }
 
 impl ElfHeader {
-    pub fn new(blob: &Blob) -> Result<Self> {
+    pub fn new(blob: &Blob, is_64: bool) -> Result<Self> {
         let machine = if let Some(machine) = MachineType::from_repr(blob.get_u16(18)?) {
             machine
         } else {
             MachineType::Reserved
         };
-        Ok(Self {
-            elf_type: blob.get_u16(16)?,
-            machine,
-            version: blob.get_u32(20)?,
-            entry: blob.get_u64(24)?,
-            phoff: blob.get_u64(32)?,
-            shoff: blob.get_u64(40)?,
-            flags: blob.get_u32(48)?,
-            ehsize: blob.get_u16(52)?,
-            phentsize: blob.get_u16(54)?,
-            phnum: blob.get_u16(56)?,
-            shentsize: blob.get_u16(58)?,
-            shnum: blob.get_u16(60)?,
-            shstrndx: blob.get_u16(62)?,
-        })
+        if is_64 {
+            Ok(Self {
+                elf_type: blob.get_u16(16)?,
+                machine,
+                version: blob.get_u32(20)?,
+                entry: blob.get_u64(24)?,
+                phoff: blob.get_u64(32)?,
+                shoff: blob.get_u64(40)?,
+                flags: blob.get_u32(48)?,
+                ehsize: blob.get_u16(52)?,
+                phentsize: blob.get_u16(54)?,
+                phnum: blob.get_u16(56)?,
+                shentsize: blob.get_u16(58)?,
+                shnum: blob.get_u16(60)?,
+                shstrndx: blob.get_u16(62)?,
+            })
+        } else {
+            Ok(Self {
+                elf_type: blob.get_u16(16)?,
+                machine,
+                version: blob.get_u32(20)?,
+                entry: blob.get_u32(24)? as u64,
+                phoff: blob.get_u32(28)? as u64,
+                shoff: blob.get_u32(32)? as u64,
+                flags: blob.get_u32(36)?,
+                ehsize: blob.get_u16(40)?,
+                phentsize: blob.get_u16(42)?,
+                phnum: blob.get_u16(44)?,
+                shentsize: blob.get_u16(46)?,
+                shnum: blob.get_u16(48)?,
+                shstrndx: blob.get_u16(50)?,
+            })
+        }
     }
 
     pub fn info(&self, full: bool) -> Vec<(String, String)> {
@@ -410,8 +471,17 @@ pub struct ElfBinary {
     id: ElfIdent,
     header: ElfHeader,
     section_headers: Vec<SectionHeader>,
-    symbols: Vec<Symbol64>,
-    dyn_symbols: Vec<Symbol64>,
+    program_headers: Vec<ProgramHeader>,
+    dynamic: Vec<DynamicEntry>,
+    dynamic_string_table_offset: Option<usize>,
+    relocations: Vec<Relocation>,
+    notes: Vec<Note>,
+    symbols: Vec<Box<dyn Symbol>>,
+    dyn_symbols: Vec<Box<dyn Symbol>>,
+    // Version index of each dynamic symbol, from .gnu.version
+    version_symbols: Vec<u16>,
+    version_definitions: Vec<VersionDefinition>,
+    version_needs: Vec<VersionNeed>,
     header_string_table_offset: usize,
 }
 
@@ -423,17 +493,29 @@ impl ElfBinary {
         }?;
         let id = (*id).clone();
 
-        let header = ElfHeader::new(&blob)?;
+        let header = ElfHeader::new(&blob, id.is_64())?;
         let string_table_header_offset =
             (header.shoff + (header.shentsize as u64) * (header.shstrndx as u64)) as usize;
-        let header_string_table_offset = blob.get_u64(string_table_header_offset + 24)? as usize;
+        let header_string_table_offset = if id.is_64() {
+            blob.get_u64(string_table_header_offset + 24)? as usize
+        } else {
+            blob.get_u32(string_table_header_offset + 16)? as usize
+        };
         Ok(Self {
             blob,
             id,
             header,
             section_headers: Vec::new(),
+            program_headers: Vec::new(),
+            dynamic: Vec::new(),
+            dynamic_string_table_offset: None,
+            relocations: Vec::new(),
+            notes: Vec::new(),
             symbols: Vec::new(),
             dyn_symbols: Vec::new(),
+            version_symbols: Vec::new(),
+            version_definitions: Vec::new(),
+            version_needs: Vec::new(),
             header_string_table_offset,
         })
     }
@@ -442,12 +524,70 @@ impl ElfBinary {
         format!("{}", self.id)
     }
 
-    pub fn header_info(&self) -> Vec<(String, String)> {
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    pub fn header_info(&mut self) -> Vec<(String, String)> {
         let mut info = vec![("Ident".to_string(), self.ident())];
         info.extend(self.header.info(true));
+        if let Ok(Some(build_id)) = self.build_id() {
+            info.push(("Build ID".to_string(), build_id));
+        }
+        if let Ok(Some(glibc)) = self.required_glibc() {
+            info.push(("Required glibc".to_string(), glibc));
+        }
+        if let Ok(Some(overlay)) = self.overlay() {
+            info.push(("Overlay".to_string(), overlay.to_string()));
+        }
         info
     }
 
+    /// Data behind the end of the sections, segments and header tables
+    pub fn overlay(&mut self) -> Result<Option<Overlay>> {
+        self.get_sections()?;
+        self.get_program_headers()?;
+        let header = &self.header;
+        let mut end = (header.ehsize as u64)
+            .max(header.phoff + header.phentsize as u64 * header.phnum as u64)
+            .max(header.shoff + header.shentsize as u64 * header.shnum as u64);
+        for section in &self.section_headers {
+            if section.section_type != ElfSectionType::NoBits {
+                end = end.max(section.offset.saturating_add(section.size));
+            }
+        }
+        for segment in &self.program_headers {
+            end = end.max(segment.offset.saturating_add(segment.file_size));
+        }
+        Ok(Overlay::new(&self.blob, end as usize)?)
+    }
+
+    /// Minimum glibc version the binary needs, the highest GLIBC_x.y version referenced
+    pub fn required_glibc(&mut self) -> Result<Option<String>> {
+        self.get_versions()?;
+        Ok(versions::required_glibc(&self.version_needs))
+    }
+
+    /// The GNU build id as hex string, used to match binaries and debug symbols
+    pub fn build_id(&mut self) -> Result<Option<String>> {
+        self.get_notes()?;
+        for note in &self.notes {
+            if note.is_build_id() {
+                return Ok(Some(note.description(&self.blob, self.id.is_64())?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Hardening properties like PIE, NX, RELRO, stack canaries and FORTIFY_SOURCE
+    pub fn checksec(&mut self) -> Result<Vec<(String, String)>> {
+        self.get_dynamic()?;
+        self.get_symbols()?;
+        self.get_dyn_symbols()?;
+        self.get_notes()?;
+        checksec::checksec(self)
+    }
+
     pub fn section_headers_table(&mut self) -> Result<Table> {
         self.get_sections()?;
         let headers = [
@@ -480,15 +620,179 @@ impl ElfBinary {
         Ok(Table::new(TableType::ElfSectionHeader, &headers, rows))
     }
 
+    pub fn program_headers_table(&mut self) -> Result<Table> {
+        self.get_program_headers()?;
+        let headers = [
+            "Nr.",
+            "Type",
+            "Flags",
+            "FileOffset",
+            "VirtAddress",
+            "PhysAddress",
+            "FileSize",
+            "MemSize",
+            "Alignment",
+            "Interpreter",
+        ];
+        let mut rows = Vec::with_capacity(self.program_headers.len());
+        for (idx, segment) in self.program_headers.iter().enumerate() {
+            let mut v = segment.to_vec(&self.blob)?;
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfProgramHeaders, &headers, rows))
+    }
+
+    /// Map sections to the segments containing them, like `readelf -l` does
+    pub fn section_to_segment_table(&mut self) -> Result<Table> {
+        self.get_sections()?;
+        self.get_program_headers()?;
+        let headers = ["Segment", "Type", "Sections"];
+        let mut rows = Vec::with_capacity(self.program_headers.len());
+        for (idx, segment) in self.program_headers.iter().enumerate() {
+            let mut names = Vec::new();
+            // Skip the null section at index 0
+            for section in self.section_headers.iter().skip(1) {
+                if segment.contains_section(section) {
+                    names.push(self.blob.get_cname(section.name)?);
+                }
+            }
+            rows.push(Row {
+                content: vec![idx.to_string(), segment.type_as_string(), names.join(" ")],
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfSegmentMapping, &headers, rows))
+    }
+
+    pub fn dynamic_table(&mut self) -> Result<Table> {
+        self.get_dynamic()?;
+        let headers = ["Nr.", "Tag", "Type", "Value"];
+        let mut rows = Vec::with_capacity(self.dynamic.len());
+        for (idx, entry) in self.dynamic.iter().enumerate() {
+            let mut v = entry.to_vec(&self.blob, self.dynamic_string_table_offset);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfDynamic, &headers, rows))
+    }
+
+    pub fn relocations_table(&mut self) -> Result<Table> {
+        self.get_relocations()?;
+        let headers = ["Nr.", "Section", "Offset", "Type", "Symbol", "Addend"];
+        let mut rows = Vec::with_capacity(self.relocations.len());
+        for (idx, relocation) in self.relocations.iter().enumerate() {
+            let section = &self.section_headers[relocation.section];
+            let symbol = if relocation.symbol == 0 {
+                String::new()
+            } else {
+                self.symbol_name(section.link as usize, relocation.symbol)?
+            };
+            let addend = match relocation.addend {
+                Some(addend) if addend < 0 => format!("-0x{:x}", addend.unsigned_abs()),
+                Some(addend) => format!("0x{addend:x}"),
+                None => String::new(),
+            };
+            let content = vec![
+                idx.to_string(),
+                self.blob.get_cname(section.name)?,
+                format!("0x{:016x}", relocation.offset),
+                relocation_types::relocation_type_name(&self.header.machine, relocation.r_type),
+                symbol,
+                addend,
+            ];
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfRelocations, &headers, rows))
+    }
+
+    /// Resolve PLT stubs to the GOT slots they jump through and the imported symbols
+    pub fn plt_entries(&mut self) -> Result<Vec<PltEntry>> {
+        self.get_relocations()?;
+        // Only needed for the AArch64 stub size, a broken dynamic section leaves the default
+        let _ = self.get_dynamic();
+        plt::find_plt_entries(self)
+    }
+
+    pub fn plt_table(&mut self) -> Result<Table> {
+        let headers = [
+            "Nr.",
+            "Section",
+            "Stub Address",
+            "GOT Address",
+            "Relocation",
+            "Symbol",
+        ];
+        let entries = self.plt_entries()?;
+        let rows = entries
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| Row {
+                content: vec![
+                    idx.to_string(),
+                    entry.section,
+                    format!("0x{:016x}", entry.stub_address),
+                    format!("0x{:016x}", entry.got_address),
+                    entry.relocation,
+                    entry.symbol,
+                ],
+                action: RowAction::None,
+            })
+            .collect();
+        Ok(Table::new(TableType::ElfPlt, &headers, rows))
+    }
+
+    pub fn notes_table(&mut self) -> Result<Table> {
+        self.get_notes()?;
+        let headers = ["Nr.", "Location", "Owner", "Type", "Description"];
+        let mut rows = Vec::with_capacity(self.notes.len());
+        for (idx, note) in self.notes.iter().enumerate() {
+            let mut v = note.to_vec(&self.blob, self.id.is_64())?;
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfNotes, &headers, rows))
+    }
+
     const SYMBOL_HEADERS: [&'static str; 8] = [
         "Nr.", "Type", "Binding", "Other", "Value", "Size", "SecIdx", "Name",
     ];
 
-    pub fn symbols_table(&mut self) -> Result<Table> {
+    pub fn symbols_table(&mut self, demangle: bool) -> Result<Table> {
         self.get_symbols()?;
         let mut rows = Vec::with_capacity(self.symbols.len());
         for (idx, symbol) in self.symbols.iter().enumerate() {
-            let mut v = symbol.to_vec(&self.blob)?;
+            let mut v = symbol.to_vec(&self.blob, demangle)?;
             let mut content = Vec::with_capacity(v.len() + 1);
             content.push(idx.to_string());
             content.append(&mut v);
@@ -507,15 +811,49 @@ impl ElfBinary {
         ))
     }
 
-    pub fn dyn_symbols_table(&mut self) -> Result<Table> {
+    pub fn dyn_symbols_table(&mut self, demangle: bool) -> Result<Table> {
         self.get_dyn_symbols()?;
+        // Versions are optional, broken version sections leave the column empty
+        let _ = self.get_versions();
+        let headers = [
+            "Nr.", "Type", "Binding", "Other", "Value", "Size", "SecIdx", "Name", "Version",
+        ];
         let mut rows = Vec::with_capacity(self.dyn_symbols.len());
         for (idx, symbol) in self.dyn_symbols.iter().enumerate() {
-            let mut v = symbol.to_vec(&self.blob)?;
+            let mut v = symbol.to_vec(&self.blob, demangle)?;
+            let mut content = Vec::with_capacity(v.len() + 2);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            content.push(match self.version_symbols.get(idx) {
+                Some(version) => versions::version_suffix(
+                    *version,
+                    &self.version_definitions,
+                    &self.version_needs,
+                ),
+                None => String::new(),
+            });
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfDynamicSymbols, &headers, rows))
+    }
+
+    /// Versions required from each needed library, from .gnu.version_r
+    pub fn version_needs_table(&mut self) -> Result<Table> {
+        self.get_versions()?;
+        let headers = ["Nr.", "Library", "Version", "Index", "Flags"];
+        let mut rows = Vec::with_capacity(self.version_needs.len());
+        for (idx, need) in self.version_needs.iter().enumerate() {
+            let mut v = need.to_vec();
             let mut content = Vec::with_capacity(v.len() + 1);
             content.push(idx.to_string());
             content.append(&mut v);
-            if Self::SYMBOL_HEADERS.len() != content.len() {
+            if headers.len() != content.len() {
                 return Err(ElfError::InternalError);
             }
             rows.push(Row {
@@ -523,11 +861,28 @@ impl ElfBinary {
                 action: RowAction::None,
             });
         }
-        Ok(Table::new(
-            TableType::ElfDynamicSymbols,
-            &Self::SYMBOL_HEADERS,
-            rows,
-        ))
+        Ok(Table::new(TableType::ElfVersionNeeds, &headers, rows))
+    }
+
+    /// Versions defined by the binary, from .gnu.version_d
+    pub fn version_definitions_table(&mut self) -> Result<Table> {
+        self.get_versions()?;
+        let headers = ["Nr.", "Index", "Flags", "Version", "Parents"];
+        let mut rows = Vec::with_capacity(self.version_definitions.len());
+        for (idx, definition) in self.version_definitions.iter().enumerate() {
+            let mut v = definition.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(ElfError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::ElfVersionDefinitions, &headers, rows))
     }
 
     fn get_sections(&mut self) -> Result<()> {
@@ -538,6 +893,7 @@ impl ElfBinary {
                     &self.blob,
                     idx,
                     self.header_string_table_offset,
+                    self.id.is_64(),
                 )?);
                 idx += self.header.shentsize as usize;
             }
@@ -545,6 +901,198 @@ impl ElfBinary {
         Ok(())
     }
 
+    fn get_program_headers(&mut self) -> Result<()> {
+        if self.program_headers.is_empty() {
+            let mut idx = self.header.phoff as usize;
+            for _ in 0..self.header.phnum {
+                self.program_headers
+                    .push(ProgramHeader::new(&self.blob, idx, self.id.is_64())?);
+                idx += self.header.phentsize as usize;
+            }
+        }
+        Ok(())
+    }
+
+    /// Translate a virtual address into a file offset using the loadable segments
+    fn vaddr_to_offset(&mut self, vaddr: u64) -> Result<Option<usize>> {
+        self.get_program_headers()?;
+        for segment in &self.program_headers {
+            if segment.segment_type == SegmentType::Load
+                && vaddr >= segment.vaddr
+                && vaddr - segment.vaddr < segment.file_size
+            {
+                return Ok(Some((segment.offset + vaddr - segment.vaddr) as usize));
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_dynamic(&mut self) -> Result<()> {
+        self.get_sections()?;
+        self.get_program_headers()?;
+        if self.dynamic.is_empty() {
+            // Prefer the .dynamic section, stripped section headers leave us with PT_DYNAMIC
+            let location = if let Some(section) = self
+                .section_headers
+                .iter()
+                .find(|s| s.section_type == ElfSectionType::Dynamic)
+            {
+                Some((section.offset, section.size))
+            } else {
+                self.program_headers
+                    .iter()
+                    .find(|s| s.segment_type == SegmentType::Dynamic)
+                    .map(|s| (s.offset, s.file_size))
+            };
+            let Some((offset, size)) = location else {
+                return Ok(());
+            };
+            let entry_size = if self.id.is_64() { 16 } else { 8 };
+            let mut idx = offset as usize;
+            let end = idx
+                .checked_add(size as usize)
+                .ok_or(ElfError::InternalError)?;
+            while end - idx >= entry_size {
+                let entry = DynamicEntry::new(&self.blob, idx, self.id.is_64())?;
+                let last = entry.tag == DynamicTag::Null;
+                self.dynamic.push(entry);
+                if last {
+                    break;
+                }
+                idx += entry_size;
+            }
+
+            let string_table_address = self
+                .dynamic
+                .iter()
+                .find(|e| e.tag == DynamicTag::StrTab)
+                .map(|e| e.value);
+            self.dynamic_string_table_offset = match string_table_address {
+                Some(address) => self.vaddr_to_offset(address)?,
+                None => None,
+            };
+            if self.dynamic_string_table_offset.is_none() {
+                self.dynamic_string_table_offset = self.get_section_offset(".dynstr")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_relocations(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.relocations.is_empty() {
+            let relative_type = relocation_types::relative_relocation_type(&self.header.machine);
+            for (idx, section) in self.section_headers.iter().enumerate() {
+                self.relocations
+                    .append(&mut relocations::parse_relocation_section(
+                        &self.blob,
+                        idx,
+                        section,
+                        self.id.is_64(),
+                        relative_type,
+                    )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn get_notes(&mut self) -> Result<()> {
+        self.get_sections()?;
+        self.get_program_headers()?;
+        if self.notes.is_empty() {
+            for section in &self.section_headers {
+                if section.section_type == ElfSectionType::Note {
+                    let name = self.blob.get_cname(section.name)?;
+                    self.notes.append(&mut notes::parse_notes(
+                        &self.blob,
+                        &name,
+                        section.offset,
+                        section.size,
+                        section.addr_align,
+                    ));
+                }
+            }
+            // Core dumps come without section headers, their notes are only found in PT_NOTE
+            if self.notes.is_empty() {
+                for (idx, segment) in self.program_headers.iter().enumerate() {
+                    if segment.segment_type == SegmentType::Note {
+                        self.notes.append(&mut notes::parse_notes(
+                            &self.blob,
+                            &format!("segment {idx}"),
+                            segment.offset,
+                            segment.file_size,
+                            segment.align,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_versions(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.version_symbols.is_empty()
+            && self.version_definitions.is_empty()
+            && self.version_needs.is_empty()
+        {
+            // Keep nothing of broken version sections, so the next call fails again
+            let mut version_symbols = Vec::new();
+            let mut version_definitions = Vec::new();
+            let mut version_needs = Vec::new();
+            for section in &self.section_headers {
+                let string_table_offset = self
+                    .section_headers
+                    .get(section.link as usize)
+                    .map(|s| s.offset as usize)
+                    .unwrap_or_default();
+                match section.section_type {
+                    ElfSectionType::GnuVerSym => {
+                        version_symbols = versions::parse_version_symbols(&self.blob, section)?
+                    }
+                    ElfSectionType::GnuVerDef => {
+                        version_definitions = versions::parse_version_definitions(
+                            &self.blob,
+                            section,
+                            string_table_offset,
+                        )?
+                    }
+                    ElfSectionType::GnuVerNeed => {
+                        version_needs =
+                            versions::parse_version_needs(&self.blob, section, string_table_offset)?
+                    }
+                    _ => {}
+                }
+            }
+            self.version_symbols = version_symbols;
+            self.version_definitions = version_definitions;
+            self.version_needs = version_needs;
+        }
+        Ok(())
+    }
+
+    /// Name of entry `index` of the symbol table stored in section `symbol_section`
+    fn symbol_name(&self, symbol_section: usize, index: u32) -> Result<String> {
+        let Some(section) = self.section_headers.get(symbol_section) else {
+            return Ok(index.to_string());
+        };
+        let Some(string_table) = self.section_headers.get(section.link as usize) else {
+            return Ok(index.to_string());
+        };
+        let ent_size = match section.ent_size {
+            0 if self.id.is_64() => 24,
+            0 => 16,
+            ent_size => ent_size,
+        };
+        let symbol = symbols::new_symbol(
+            &self.blob,
+            (section.offset + ent_size * index as u64) as usize,
+            string_table.offset as usize,
+            self.id.is_64(),
+        )?;
+        Ok(self.blob.get_cname(symbol.name())?)
+    }
+
     fn get_section_offset(&mut self, section_name: &str) -> Result<Option<usize>> {
         self.get_sections()?;
         for section in &self.section_headers {
@@ -567,8 +1115,12 @@ impl ElfBinary {
                         let mut idx = section.offset as usize;
                         let end = idx + section.size as usize;
                         while idx < end {
-                            self.symbols
-                                .push(Symbol64::new(&self.blob, idx, string_table_offset)?);
+                            self.symbols.push(symbols::new_symbol(
+                                &self.blob,
+                                idx,
+                                string_table_offset,
+                                self.id.is_64(),
+                            )?);
                             idx += section.ent_size as usize;
                         }
                     }
@@ -587,10 +1139,11 @@ impl ElfBinary {
                         let mut idx = section.offset as usize;
                         let end = idx + section.size as usize;
                         while idx < end {
-                            self.dyn_symbols.push(Symbol64::new(
+                            self.dyn_symbols.push(symbols::new_symbol(
                                 &self.blob,
                                 idx,
                                 string_table_offset,
+                                self.id.is_64(),
                             )?);
                             idx += section.ent_size as usize;
                         }
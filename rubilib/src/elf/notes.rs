@@ -0,0 +1,371 @@
+use super::{ElfError, Result};
+use crate::blob::Blob;
+use crate::pe::{align_up, flags_as_string, hex_string};
+
+// Note types with owner "GNU"
+const NT_GNU_ABI_TAG: u32 = 1;
+const NT_GNU_HWCAP: u32 = 2;
+const NT_GNU_BUILD_ID: u32 = 3;
+const NT_GNU_GOLD_VERSION: u32 = 4;
+const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+// Note types with owner "Go"
+const NT_GO_BUILDID: u32 = 4;
+
+// Note types with owner "CORE" or "LINUX"
+const NT_PRSTATUS: u32 = 1;
+const NT_PRFPREG: u32 = 2;
+const NT_PRPSINFO: u32 = 3;
+const NT_TASKSTRUCT: u32 = 4;
+const NT_AUXV: u32 = 6;
+const NT_PRXFPREG: u32 = 0x46e62b7f;
+const NT_X86_XSTATE: u32 = 0x202;
+const NT_SIGINFO: u32 = 0x53494749;
+const NT_FILE: u32 = 0x46494c45;
+
+// GNU property types
+const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+const GNU_PROPERTY_1_NEEDED: u32 = 0xb0008000;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+
+const X86_FEATURE_NAMES: [(u32, &str); 2] = [(0x1, "IBT"), (0x2, "SHSTK")];
+const AARCH64_FEATURE_NAMES: [(u32, &str); 3] = [(0x1, "BTI"), (0x2, "PAC"), (0x4, "GCS")];
+const X86_ISA_NAMES: [(u32, &str); 4] = [
+    (0x1, "x86-64-baseline"),
+    (0x2, "x86-64-v2"),
+    (0x4, "x86-64-v3"),
+    (0x8, "x86-64-v4"),
+];
+
+const AUXV_NAMES: [(u64, &str); 22] = [
+    (3, "AT_PHDR"),
+    (4, "AT_PHENT"),
+    (5, "AT_PHNUM"),
+    (6, "AT_PAGESZ"),
+    (7, "AT_BASE"),
+    (8, "AT_FLAGS"),
+    (9, "AT_ENTRY"),
+    (11, "AT_UID"),
+    (12, "AT_EUID"),
+    (13, "AT_GID"),
+    (14, "AT_EGID"),
+    (15, "AT_PLATFORM"),
+    (16, "AT_HWCAP"),
+    (17, "AT_CLKTCK"),
+    (23, "AT_SECURE"),
+    (24, "AT_BASE_PLATFORM"),
+    (25, "AT_RANDOM"),
+    (26, "AT_HWCAP2"),
+    (31, "AT_EXECFN"),
+    (32, "AT_SYSINFO"),
+    (33, "AT_SYSINFO_EHDR"),
+    (51, "AT_MINSIGSTKSZ"),
+];
+
+pub struct Note {
+    // Section name or segment the note was found in
+    pub(super) location: String,
+    pub(super) owner: String,
+    pub(super) n_type: u32,
+    // File offset and size of the note descriptor
+    pub(super) desc_offset: usize,
+    pub(super) desc_size: usize,
+}
+
+/// Split the content of a note section or segment into single notes, a note running out
+/// of the section or the file ends the list and keeps the notes before it
+pub(super) fn parse_notes(
+    blob: &Blob,
+    location: &str,
+    offset: u64,
+    size: u64,
+    align: u64,
+) -> Vec<Note> {
+    // Notes are 4 byte aligned, except for 8 byte aligned sections like .note.gnu.property
+    let align = if align == 8 { 8 } else { 4 };
+    let mut notes = Vec::new();
+    let mut idx = offset as usize;
+    let end = offset.saturating_add(size) as usize;
+    while end.saturating_sub(idx) >= 12 {
+        let Ok(Some((note, next))) = parse_note(blob, location, idx, end, align) else {
+            break;
+        };
+        notes.push(note);
+        idx = next;
+    }
+    notes
+}
+
+/// The note at `idx` and the offset of the next one, None if it does not end before `end`
+fn parse_note(
+    blob: &Blob,
+    location: &str,
+    idx: usize,
+    end: usize,
+    align: usize,
+) -> Result<Option<(Note, usize)>> {
+    let name_size = blob.get_u32(idx)? as usize;
+    let desc_size = blob.get_u32(idx + 4)? as usize;
+    let n_type = blob.get_u32(idx + 8)?;
+    let name_offset = idx + 12;
+    let owner = if name_size == 0 {
+        String::new()
+    } else {
+        let name = blob.get_slice(name_offset, name_size)?;
+        let name = name.split(|b| *b == 0).next().unwrap_or_default();
+        String::from_utf8_lossy(name).to_string()
+    };
+    let desc_offset = idx + align_up(12 + name_size, align);
+    if desc_offset + desc_size > end {
+        return Ok(None);
+    }
+    blob.get_slice(desc_offset, desc_size)?;
+    let note = Note {
+        location: location.to_string(),
+        owner,
+        n_type,
+        desc_offset,
+        desc_size,
+    };
+    Ok(Some((
+        note,
+        idx + align_up(desc_offset - idx + desc_size, align),
+    )))
+}
+
+/// Feature bits of a GNU property, "none" if no bit is set
+fn feature_names(value: u32, names: &[(u32, &str)]) -> String {
+    if value == 0 {
+        "none".to_string()
+    } else {
+        flags_as_string(value, names)
+    }
+}
+
+fn string_from(bytes: &[u8]) -> String {
+    let bytes = bytes.split(|b| *b == 0).next().unwrap_or_default();
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// A GNU property with a bit mask value, as found in NT_GNU_PROPERTY_TYPE_0
+pub(super) struct GnuProperty {
+    pub(super) pr_type: u32,
+    pub(super) value: u32,
+}
+
+impl Note {
+    pub(super) fn type_as_string(&self) -> String {
+        let name = match (self.owner.as_str(), self.n_type) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG",
+            ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP",
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID",
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION",
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+            ("Go", NT_GO_BUILDID) => "NT_GO_BUILDID",
+            ("CORE", NT_PRSTATUS) => "NT_PRSTATUS",
+            ("CORE", NT_PRFPREG) => "NT_PRFPREG",
+            ("CORE", NT_PRPSINFO) => "NT_PRPSINFO",
+            ("CORE", NT_TASKSTRUCT) => "NT_TASKSTRUCT",
+            ("CORE", NT_AUXV) => "NT_AUXV",
+            ("CORE", NT_SIGINFO) => "NT_SIGINFO",
+            ("CORE", NT_FILE) => "NT_FILE",
+            ("LINUX", NT_PRXFPREG) => "NT_PRXFPREG",
+            ("LINUX", NT_X86_XSTATE) => "NT_X86_XSTATE",
+            _ => return format!("0x{:08x}", self.n_type),
+        };
+        name.to_string()
+    }
+
+    pub(super) fn is_build_id(&self) -> bool {
+        self.owner == "GNU" && self.n_type == NT_GNU_BUILD_ID
+    }
+
+    pub(super) fn desc<'a>(&self, blob: &'a Blob) -> Result<&'a [u8]> {
+        Ok(blob.get_slice(self.desc_offset, self.desc_size)?)
+    }
+
+    /// Bit mask properties of a NT_GNU_PROPERTY_TYPE_0 note
+    pub(super) fn gnu_properties(&self, blob: &Blob, is_64: bool) -> Result<Vec<GnuProperty>> {
+        let mut properties = Vec::new();
+        if self.owner != "GNU" || self.n_type != NT_GNU_PROPERTY_TYPE_0 {
+            return Ok(properties);
+        }
+        let align = if is_64 { 8 } else { 4 };
+        let mut idx = self.desc_offset;
+        let end = self.desc_offset + self.desc_size;
+        while idx + 8 <= end {
+            let pr_type = blob.get_u32(idx)?;
+            let data_size = blob.get_u32(idx + 4)? as usize;
+            let value = if data_size >= 4 {
+                blob.get_u32(idx + 8)?
+            } else {
+                0
+            };
+            properties.push(GnuProperty { pr_type, value });
+            idx += 8 + align_up(data_size, align);
+        }
+        Ok(properties)
+    }
+
+    /// Names of the control flow protection features (x86 IBT/SHSTK, AArch64 BTI/PAC/GCS)
+    pub(super) fn control_flow_features(&self, blob: &Blob, is_64: bool) -> Result<Vec<String>> {
+        let mut features = Vec::new();
+        for property in self.gnu_properties(blob, is_64)? {
+            let names: &[(u32, &str)] = match property.pr_type {
+                GNU_PROPERTY_AARCH64_FEATURE_1_AND => &AARCH64_FEATURE_NAMES,
+                GNU_PROPERTY_X86_FEATURE_1_AND => &X86_FEATURE_NAMES,
+                _ => continue,
+            };
+            for (flag, name) in names {
+                if property.value & flag != 0 {
+                    features.push(name.to_string());
+                }
+            }
+        }
+        Ok(features)
+    }
+
+    fn describe_gnu_properties(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let mut descriptions = Vec::new();
+        for property in self.gnu_properties(blob, is_64)? {
+            descriptions.push(match property.pr_type {
+                GNU_PROPERTY_STACK_SIZE => format!("stack size: 0x{:x}", property.value),
+                GNU_PROPERTY_NO_COPY_ON_PROTECTED => "no copy on protected".to_string(),
+                GNU_PROPERTY_1_NEEDED => format!("1_needed: 0x{:x}", property.value),
+                GNU_PROPERTY_AARCH64_FEATURE_1_AND => format!(
+                    "AArch64 feature: {}",
+                    feature_names(property.value, &AARCH64_FEATURE_NAMES)
+                ),
+                GNU_PROPERTY_X86_FEATURE_1_AND => format!(
+                    "x86 feature: {}",
+                    feature_names(property.value, &X86_FEATURE_NAMES)
+                ),
+                GNU_PROPERTY_X86_ISA_1_NEEDED => format!(
+                    "x86 ISA needed: {}",
+                    feature_names(property.value, &X86_ISA_NAMES)
+                ),
+                GNU_PROPERTY_X86_ISA_1_USED => format!(
+                    "x86 ISA used: {}",
+                    feature_names(property.value, &X86_ISA_NAMES)
+                ),
+                pr_type => format!("0x{pr_type:08x}: 0x{:x}", property.value),
+            });
+        }
+        Ok(descriptions.join("; "))
+    }
+
+    fn get_word(blob: &Blob, offset: usize, is_64: bool) -> Result<u64> {
+        if is_64 {
+            Ok(blob.get_u64(offset)?)
+        } else {
+            Ok(blob.get_u32(offset)? as u64)
+        }
+    }
+
+    fn describe_prstatus(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let signal = blob.get_u16(self.desc_offset + 12)?;
+        let pid_offset = if is_64 { 32 } else { 24 };
+        let pid = blob.get_u32(self.desc_offset + pid_offset)?;
+        Ok(format!("signal: {signal}, pid: {pid}"))
+    }
+
+    fn describe_prpsinfo(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let (pid_offset, fname_offset) = if is_64 { (24, 40) } else { (12, 28) };
+        let pid = blob.get_u32(self.desc_offset + pid_offset)?;
+        let fname = string_from(blob.get_slice(self.desc_offset + fname_offset, 16)?);
+        let args = string_from(blob.get_slice(self.desc_offset + fname_offset + 16, 80)?);
+        Ok(format!("pid: {pid}, name: {fname}, args: {args}"))
+    }
+
+    fn describe_file(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let word = if is_64 { 8 } else { 4 };
+        let count = Self::get_word(blob, self.desc_offset, is_64)? as usize;
+        let page_size = Self::get_word(blob, self.desc_offset + word, is_64)?;
+        // Each file has a start, end and page offset word in the descriptor
+        if count > self.desc_size.saturating_sub(2 * word) / (3 * word) {
+            return Err(ElfError::InternalError);
+        }
+        let mut name_offset = self.desc_offset + 2 * word + 3 * word * count;
+        let mut files = Vec::with_capacity(count);
+        for idx in 0..count {
+            let entry = self.desc_offset + 2 * word + 3 * word * idx;
+            let start = Self::get_word(blob, entry, is_64)?;
+            let end = Self::get_word(blob, entry + word, is_64)?;
+            let page = Self::get_word(blob, entry + 2 * word, is_64)?;
+            let name = blob.get_cstr(name_offset)?;
+            name_offset = name_offset
+                .checked_add(name.to_bytes_with_nul().len())
+                .ok_or(ElfError::InternalError)?;
+            files.push(format!(
+                "0x{start:x}-0x{end:x} @0x{:x} {}",
+                page.wrapping_mul(page_size),
+                name.to_string_lossy()
+            ));
+        }
+        Ok(files.join("; "))
+    }
+
+    fn describe_auxv(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let word = if is_64 { 8 } else { 4 };
+        let mut entries = Vec::new();
+        let mut idx = self.desc_offset;
+        while idx + 2 * word <= self.desc_offset + self.desc_size {
+            let a_type = Self::get_word(blob, idx, is_64)?;
+            if a_type == 0 {
+                break;
+            }
+            let value = Self::get_word(blob, idx + word, is_64)?;
+            let name = AUXV_NAMES
+                .iter()
+                .find(|(t, _)| *t == a_type)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| a_type.to_string());
+            entries.push(format!("{name}=0x{value:x}"));
+            idx += 2 * word;
+        }
+        Ok(entries.join(", "))
+    }
+
+    pub(super) fn description(&self, blob: &Blob, is_64: bool) -> Result<String> {
+        let desc = self.desc(blob)?;
+        match (self.owner.as_str(), self.n_type) {
+            ("GNU", NT_GNU_BUILD_ID) => Ok(hex_string(desc)),
+            ("GNU", NT_GNU_ABI_TAG) if desc.len() >= 16 => {
+                let os = match blob.get_u32(self.desc_offset)? {
+                    0 => "Linux",
+                    1 => "Hurd",
+                    2 => "Solaris",
+                    3 => "FreeBSD",
+                    _ => "unknown",
+                };
+                Ok(format!(
+                    "OS: {os}, ABI: {}.{}.{}",
+                    blob.get_u32(self.desc_offset + 4)?,
+                    blob.get_u32(self.desc_offset + 8)?,
+                    blob.get_u32(self.desc_offset + 12)?
+                ))
+            }
+            ("GNU", NT_GNU_GOLD_VERSION) | ("Go", NT_GO_BUILDID) => Ok(string_from(desc)),
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => self.describe_gnu_properties(blob, is_64),
+            ("CORE", NT_PRSTATUS) => self.describe_prstatus(blob, is_64),
+            ("CORE", NT_PRPSINFO) => self.describe_prpsinfo(blob, is_64),
+            ("CORE", NT_FILE) => self.describe_file(blob, is_64),
+            ("CORE", NT_AUXV) => self.describe_auxv(blob, is_64),
+            _ if desc.len() > 64 => Ok(format!("{}...", hex_string(&desc[..64]))),
+            _ => Ok(hex_string(desc)),
+        }
+    }
+
+    pub(super) fn to_vec(&self, blob: &Blob, is_64: bool) -> Result<Vec<String>> {
+        Ok(vec![
+            self.location.clone(),
+            self.owner.clone(),
+            self.type_as_string(),
+            self.description(blob, is_64)?,
+        ])
+    }
+}
@@ -0,0 +1,193 @@
+use super::{
+    relocation_types, ElfBinary, ElfError, ElfSectionType, MachineType, Result, SectionHeader,
+};
+
+/// A PLT stub together with the GOT slot it jumps through and the imported symbol
+#[derive(Clone, Debug)]
+pub struct PltEntry {
+    pub section: String,
+    pub stub_address: u64,
+    pub got_address: u64,
+    pub relocation: String,
+    pub symbol: String,
+}
+
+const PLT_SECTIONS: [&str; 3] = [".plt", ".plt.sec", ".plt.got"];
+
+// Dynamic tags marking AArch64 PLTs with BTI or PAC instructions in every stub
+const DT_AARCH64_BTI_PLT: u64 = 0x70000001;
+const DT_AARCH64_PAC_PLT: u64 = 0x70000003;
+
+/// GOT slot filled by the dynamic linker
+struct GotSlot {
+    address: u64,
+    r_type: u32,
+    symbol: String,
+}
+
+/// Pair PLT stubs with GOT slots and symbols, sections and relocations have to be loaded
+pub(super) fn find_plt_entries(binary: &ElfBinary) -> Result<Vec<PltEntry>> {
+    let machine = &binary.header.machine;
+    let jump_slot = relocation_types::jump_slot_relocation_type(machine);
+    let glob_dat = relocation_types::glob_dat_relocation_type(machine);
+    let mut slots = Vec::new();
+    for relocation in &binary.relocations {
+        if relocation.symbol != 0
+            && (Some(relocation.r_type) == jump_slot || Some(relocation.r_type) == glob_dat)
+        {
+            let link = binary.section_headers[relocation.section].link as usize;
+            slots.push(GotSlot {
+                address: relocation.offset,
+                r_type: relocation.r_type,
+                symbol: binary.symbol_name(link, relocation.symbol)?,
+            });
+        }
+    }
+
+    let mut entries = Vec::new();
+    for section in &binary.section_headers {
+        if section.section_type != ElfSectionType::ProgBits {
+            continue;
+        }
+        let name = binary.blob.get_cname(section.name)?;
+        if !PLT_SECTIONS.contains(&name.as_str()) {
+            continue;
+        }
+        match machine {
+            MachineType::AmdX64 | MachineType::X86 => {
+                x86_entries(binary, section, &name, &slots, &mut entries)?
+            }
+            _ if name == ".plt" => {
+                ordered_entries(binary, section, &name, jump_slot, &slots, &mut entries)
+            }
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+fn push_entry(
+    binary: &ElfBinary,
+    entries: &mut Vec<PltEntry>,
+    section_name: &str,
+    stub_address: u64,
+    slot: &GotSlot,
+) {
+    entries.push(PltEntry {
+        section: section_name.to_string(),
+        stub_address,
+        got_address: slot.address,
+        relocation: relocation_types::relocation_type_name(&binary.header.machine, slot.r_type),
+        symbol: slot.symbol.clone(),
+    });
+}
+
+/// Decode the indirect jump of every x86 stub to find the GOT slot it uses
+fn x86_entries(
+    binary: &ElfBinary,
+    section: &SectionHeader,
+    section_name: &str,
+    slots: &[GotSlot],
+    entries: &mut Vec<PltEntry>,
+) -> Result<()> {
+    let is_64 = binary.id.is_64();
+    // i386 PIC stubs jump relative to %ebx, which holds the address of .got.plt
+    let mut got_base = None;
+    for section in &binary.section_headers {
+        if binary.blob.get_cname(section.name)? == ".got.plt" {
+            got_base = Some(section.addr);
+        }
+    }
+    // i386 linkers put 4 into sh_entsize of .plt, stubs are 16 bytes apart nevertheless
+    let ent_size = if section.ent_size < 8 {
+        16
+    } else {
+        section.ent_size
+    };
+    let mut stub_offset: u64 = 0;
+    while stub_offset
+        .checked_add(ent_size)
+        .is_some_and(|end| end <= section.size)
+    {
+        let code_offset = section
+            .offset
+            .checked_add(stub_offset)
+            .ok_or(ElfError::InternalError)?;
+        let code = binary
+            .blob
+            .get_slice(code_offset as usize, ent_size as usize)?;
+        let stub_address = section.addr.wrapping_add(stub_offset);
+        if let Some(target) = x86_jump_target(code, stub_address, is_64, got_base) {
+            if let Some(slot) = slots.iter().find(|slot| slot.address == target) {
+                push_entry(binary, entries, section_name, stub_address, slot);
+            }
+        }
+        stub_offset += ent_size;
+    }
+    Ok(())
+}
+
+/// Target of the first `jmp *mem` instruction in a stub
+fn x86_jump_target(code: &[u8], address: u64, is_64: bool, got_base: Option<u64>) -> Option<u64> {
+    for pos in 0..code.len().saturating_sub(5) {
+        if code[pos] != 0xff || (code[pos + 1] != 0x25 && code[pos + 1] != 0xa3) {
+            continue;
+        }
+        let disp = i32::from_le_bytes(code[pos + 2..pos + 6].try_into().ok()?) as i64;
+        return match (is_64, code[pos + 1]) {
+            // jmp *disp(%rip)
+            (true, 0x25) => Some((address as i64 + pos as i64 + 6 + disp) as u64),
+            // jmp *abs32
+            (false, 0x25) => Some(disp as u32 as u64),
+            // jmp *disp(%ebx)
+            (false, 0xa3) => got_base.map(|base| (base as i64 + disp) as u64),
+            _ => None,
+        };
+    }
+    None
+}
+
+/// Stubs of other architectures follow a fixed sized header in the order of
+/// the JUMP_SLOT relocations
+fn ordered_entries(
+    binary: &ElfBinary,
+    section: &SectionHeader,
+    section_name: &str,
+    jump_slot: Option<u32>,
+    slots: &[GotSlot],
+    entries: &mut Vec<PltEntry>,
+) {
+    let (header_size, entry_size) = match binary.header.machine {
+        MachineType::Arm64 => (32, aarch64_entry_size(binary, section)),
+        MachineType::Arm => (20, 12),
+        MachineType::RiscV => (32, 16),
+        _ => return,
+    };
+    let jump_slots = slots.iter().filter(|slot| Some(slot.r_type) == jump_slot);
+    for (idx, slot) in jump_slots.enumerate() {
+        let stub_offset = header_size + entry_size * idx as u64;
+        if stub_offset + entry_size > section.size {
+            break;
+        }
+        push_entry(
+            binary,
+            entries,
+            section_name,
+            section.addr.wrapping_add(stub_offset),
+            slot,
+        );
+    }
+}
+
+/// AArch64 stubs grow from 16 to 24 bytes when they start with BTI or authenticate with PAC
+fn aarch64_entry_size(binary: &ElfBinary, section: &SectionHeader) -> u64 {
+    let protected = binary
+        .dynamic
+        .iter()
+        .any(|entry| entry.d_tag == DT_AARCH64_BTI_PLT || entry.d_tag == DT_AARCH64_PAC_PLT);
+    if protected || section.ent_size == 24 {
+        24
+    } else {
+        16
+    }
+}
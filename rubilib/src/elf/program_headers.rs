@@ -0,0 +1,211 @@
+use strum::FromRepr;
+
+use super::{ElfSectionType, Result, SectionHeader};
+use crate::blob::Blob;
+
+#[repr(u32)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum SegmentType {
+    Null = 0x0,                    // Unused entry.
+    Load = 0x1,                    // Loadable segment.
+    Dynamic = 0x2,                 // Dynamic linking information.
+    Interp = 0x3,                  // Interpreter pathname.
+    Note = 0x4,                    // Auxiliary information.
+    ShLib = 0x5,                   // Reserved.
+    Phdr = 0x6,                    // The program header table itself.
+    Tls = 0x7,                     // The thread-local storage template.
+    GnuEhFrame = 0x6474e550,       // Exception handling frame header.
+    GnuStack = 0x6474e551,         // Indicates stack executability.
+    GnuRelro = 0x6474e552,         // Read-only after relocation.
+    GnuProperty = 0x6474e553,      // .note.gnu.property notes sections.
+    GnuSFrame = 0x6474e554,        // Stack frame information.
+    OpenBsdRandomize = 0x65a3dbe6, // Fill with random data.
+    OpenBsdWxNeeded = 0x65a3dbe7,  // Program does W^X violations.
+    OpenBsdBootData = 0x65a41be6,  // Section for boot arguments.
+
+    // Start of arch-specific types
+    Proc0 = 0x70000000, // MIPS: Register usage information
+    Proc1 = 0x70000001, // ARM: Exception unwind tables, MIPS: Runtime procedure table
+    Proc2 = 0x70000002, // MIPS: Options segment
+    Proc3 = 0x70000003, // ARM, MIPS, RISCV: ABI flags / attributes
+
+    // Values above 0x80000000 are reserved
+    Unknown = 0x87654321,
+}
+
+pub struct ProgramHeader {
+    // Segment type, raw value kept for unknown types
+    pub(super) p_type: u32,
+    pub(super) segment_type: SegmentType,
+    // Segment flags
+    pub(super) flags: u32,
+    // Segment file offset
+    pub(super) offset: u64,
+    // Segment virtual address
+    pub(super) vaddr: u64,
+    // Segment physical address
+    pub(super) paddr: u64,
+    // Segment size in file
+    pub(super) file_size: u64,
+    // Segment size in memory
+    pub(super) mem_size: u64,
+    // Segment alignment
+    pub(super) align: u64,
+}
+
+impl ProgramHeader {
+    pub(super) fn new(blob: &Blob, offset: usize, is_64: bool) -> Result<Self> {
+        let p_type = blob.get_u32(offset)?;
+        let segment_type = SegmentType::from_repr(p_type).unwrap_or(SegmentType::Unknown);
+        if is_64 {
+            Ok(Self {
+                p_type,
+                segment_type,
+                flags: blob.get_u32(offset + 4)?,
+                offset: blob.get_u64(offset + 8)?,
+                vaddr: blob.get_u64(offset + 16)?,
+                paddr: blob.get_u64(offset + 24)?,
+                file_size: blob.get_u64(offset + 32)?,
+                mem_size: blob.get_u64(offset + 40)?,
+                align: blob.get_u64(offset + 48)?,
+            })
+        } else {
+            Ok(Self {
+                p_type,
+                segment_type,
+                offset: blob.get_u32(offset + 4)? as u64,
+                vaddr: blob.get_u32(offset + 8)? as u64,
+                paddr: blob.get_u32(offset + 12)? as u64,
+                file_size: blob.get_u32(offset + 16)? as u64,
+                mem_size: blob.get_u32(offset + 20)? as u64,
+                flags: blob.get_u32(offset + 24)?,
+                align: blob.get_u32(offset + 28)? as u64,
+            })
+        }
+    }
+
+    pub(super) fn type_as_string(&self) -> String {
+        if self.segment_type == SegmentType::Unknown {
+            format!("0x{:08x}", self.p_type)
+        } else {
+            format!("{:?}", self.segment_type)
+        }
+    }
+
+    fn flags_as_string(&self) -> String {
+        let mut flag_string = String::new();
+        flag_string.push(if self.flags & 0x4 != 0 { 'r' } else { '-' });
+        flag_string.push(if self.flags & 0x2 != 0 { 'w' } else { '-' });
+        flag_string.push(if self.flags & 0x1 != 0 { 'x' } else { '-' });
+        flag_string
+    }
+
+    pub(super) fn to_vec(&self, blob: &Blob) -> Result<Vec<String>> {
+        let mut v = Vec::with_capacity(9);
+        v.push(self.type_as_string());
+        v.push(self.flags_as_string());
+        v.push(format!("0x{:016x}", self.offset));
+        v.push(format!("0x{:016x}", self.vaddr));
+        v.push(format!("0x{:016x}", self.paddr));
+        v.push(format!("0x{:016x}", self.file_size));
+        v.push(format!("0x{:016x}", self.mem_size));
+        v.push(format!("0x{:x}", self.align));
+        v.push(if self.segment_type == SegmentType::Interp {
+            blob.get_cname(Some(self.offset as usize))?
+        } else {
+            String::new()
+        });
+        Ok(v)
+    }
+}
+
+const SHF_ALLOC: u64 = 0x2;
+const SHF_TLS: u64 = 0x400;
+
+impl ProgramHeader {
+    /// Size a section occupies inside this segment; .tbss only takes space in PT_TLS
+    fn section_size(&self, section: &SectionHeader) -> u64 {
+        if section.flags & SHF_TLS == 0
+            || section.section_type != ElfSectionType::NoBits
+            || self.segment_type == SegmentType::Tls
+        {
+            section.size
+        } else {
+            0
+        }
+    }
+
+    /// Check whether a section lies within this segment, following the rules
+    /// `readelf -l` uses to build its section to segment mapping.
+    pub(super) fn contains_section(&self, section: &SectionHeader) -> bool {
+        let tls = section.flags & SHF_TLS != 0;
+        let alloc = section.flags & SHF_ALLOC != 0;
+        let nobits = section.section_type == ElfSectionType::NoBits;
+        let size = self.section_size(section);
+
+        // Only PT_LOAD, PT_GNU_RELRO and PT_TLS segments can contain TLS sections,
+        // PT_TLS contains only TLS sections, PT_PHDR no sections at all
+        let type_matches = if tls {
+            matches!(
+                self.segment_type,
+                SegmentType::Tls | SegmentType::GnuRelro | SegmentType::Load
+            )
+        } else {
+            !matches!(self.segment_type, SegmentType::Tls | SegmentType::Phdr)
+        };
+        if !type_matches {
+            return false;
+        }
+
+        // Loadable and similar segments only hold SHF_ALLOC sections
+        if !alloc
+            && matches!(
+                self.segment_type,
+                SegmentType::Load
+                    | SegmentType::Dynamic
+                    | SegmentType::GnuEhFrame
+                    | SegmentType::GnuStack
+                    | SegmentType::GnuRelro
+                    | SegmentType::GnuSFrame
+            )
+        {
+            return false;
+        }
+
+        // Sections with file content must have file offsets within the segment
+        if !nobits
+            && (section.offset < self.offset
+                || section.offset - self.offset > self.file_size.wrapping_sub(1)
+                || (section.offset - self.offset)
+                    .checked_add(size)
+                    .map_or(true, |end| end > self.file_size))
+        {
+            return false;
+        }
+
+        // SHF_ALLOC sections must have addresses within the segment
+        if alloc
+            && (section.addr < self.vaddr
+                || section.addr - self.vaddr > self.mem_size.wrapping_sub(1)
+                || (section.addr - self.vaddr)
+                    .checked_add(size)
+                    .map_or(true, |end| end > self.mem_size))
+        {
+            return false;
+        }
+
+        // No zero sized sections at the start or end of PT_DYNAMIC and PT_NOTE
+        if matches!(self.segment_type, SegmentType::Dynamic | SegmentType::Note)
+            && section.size == 0
+            && self.mem_size != 0
+        {
+            let offset_inside = nobits
+                || (section.offset > self.offset && section.offset - self.offset < self.file_size);
+            let addr_inside =
+                !alloc || (section.addr > self.vaddr && section.addr - self.vaddr < self.mem_size);
+            return offset_inside && addr_inside;
+        }
+
+        true
+    }
+}
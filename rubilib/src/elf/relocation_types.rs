@@ -0,0 +1,350 @@
+use super::MachineType;
+
+const X86_64_RELOCATIONS: [(u32, &str); 44] = [
+    (0, "NONE"),
+    (1, "64"),
+    (2, "PC32"),
+    (3, "GOT32"),
+    (4, "PLT32"),
+    (5, "COPY"),
+    (6, "GLOB_DAT"),
+    (7, "JUMP_SLOT"),
+    (8, "RELATIVE"),
+    (9, "GOTPCREL"),
+    (10, "32"),
+    (11, "32S"),
+    (12, "16"),
+    (13, "PC16"),
+    (14, "8"),
+    (15, "PC8"),
+    (16, "DTPMOD64"),
+    (17, "DTPOFF64"),
+    (18, "TPOFF64"),
+    (19, "TLSGD"),
+    (20, "TLSLD"),
+    (21, "DTPOFF32"),
+    (22, "GOTTPOFF"),
+    (23, "TPOFF32"),
+    (24, "PC64"),
+    (25, "GOTOFF64"),
+    (26, "GOTPC32"),
+    (27, "GOT64"),
+    (28, "GOTPCREL64"),
+    (29, "GOTPC64"),
+    (30, "GOTPLT64"),
+    (31, "PLTOFF64"),
+    (32, "SIZE32"),
+    (33, "SIZE64"),
+    (34, "GOTPC32_TLSDESC"),
+    (35, "TLSDESC_CALL"),
+    (36, "TLSDESC"),
+    (37, "IRELATIVE"),
+    (38, "RELATIVE64"),
+    (41, "GOTPCRELX"),
+    (42, "REX_GOTPCRELX"),
+    (43, "CODE_4_GOTPCRELX"),
+    (44, "CODE_4_GOTTPOFF"),
+    (45, "CODE_4_GOTPC32_TLSDESC"),
+];
+
+const I386_RELOCATIONS: [(u32, &str); 41] = [
+    (0, "NONE"),
+    (1, "32"),
+    (2, "PC32"),
+    (3, "GOT32"),
+    (4, "PLT32"),
+    (5, "COPY"),
+    (6, "GLOB_DAT"),
+    (7, "JUMP_SLOT"),
+    (8, "RELATIVE"),
+    (9, "GOTOFF"),
+    (10, "GOTPC"),
+    (11, "32PLT"),
+    (14, "TLS_TPOFF"),
+    (15, "TLS_IE"),
+    (16, "TLS_GOTIE"),
+    (17, "TLS_LE"),
+    (18, "TLS_GD"),
+    (19, "TLS_LDM"),
+    (20, "16"),
+    (21, "PC16"),
+    (22, "8"),
+    (23, "PC8"),
+    (24, "TLS_GD_32"),
+    (25, "TLS_GD_PUSH"),
+    (26, "TLS_GD_CALL"),
+    (27, "TLS_GD_POP"),
+    (28, "TLS_LDM_32"),
+    (29, "TLS_LDM_PUSH"),
+    (30, "TLS_LDM_CALL"),
+    (31, "TLS_LDM_POP"),
+    (32, "TLS_LDO_32"),
+    (33, "TLS_IE_32"),
+    (34, "TLS_LE_32"),
+    (35, "TLS_DTPMOD32"),
+    (36, "TLS_DTPOFF32"),
+    (37, "TLS_TPOFF32"),
+    (38, "SIZE32"),
+    (39, "TLS_GOTDESC"),
+    (40, "TLS_DESC_CALL"),
+    (41, "TLS_DESC"),
+    (42, "IRELATIVE"),
+];
+
+const AARCH64_RELOCATIONS: [(u32, &str); 65] = [
+    (0, "NONE"),
+    (257, "ABS64"),
+    (258, "ABS32"),
+    (259, "ABS16"),
+    (260, "PREL64"),
+    (261, "PREL32"),
+    (262, "PREL16"),
+    (263, "MOVW_UABS_G0"),
+    (264, "MOVW_UABS_G0_NC"),
+    (265, "MOVW_UABS_G1"),
+    (266, "MOVW_UABS_G1_NC"),
+    (267, "MOVW_UABS_G2"),
+    (268, "MOVW_UABS_G2_NC"),
+    (269, "MOVW_UABS_G3"),
+    (270, "MOVW_SABS_G0"),
+    (271, "MOVW_SABS_G1"),
+    (272, "MOVW_SABS_G2"),
+    (273, "LD_PREL_LO19"),
+    (274, "ADR_PREL_LO21"),
+    (275, "ADR_PREL_PG_HI21"),
+    (276, "ADR_PREL_PG_HI21_NC"),
+    (277, "ADD_ABS_LO12_NC"),
+    (278, "LDST8_ABS_LO12_NC"),
+    (279, "TSTBR14"),
+    (280, "CONDBR19"),
+    (282, "JUMP26"),
+    (283, "CALL26"),
+    (284, "LDST16_ABS_LO12_NC"),
+    (285, "LDST32_ABS_LO12_NC"),
+    (286, "LDST64_ABS_LO12_NC"),
+    (287, "MOVW_PREL_G0"),
+    (288, "MOVW_PREL_G0_NC"),
+    (289, "MOVW_PREL_G1"),
+    (290, "MOVW_PREL_G1_NC"),
+    (291, "MOVW_PREL_G2"),
+    (292, "MOVW_PREL_G2_NC"),
+    (293, "MOVW_PREL_G3"),
+    (299, "LDST128_ABS_LO12_NC"),
+    (307, "GOTREL64"),
+    (308, "GOTREL32"),
+    (309, "GOT_LD_PREL19"),
+    (311, "ADR_GOT_PAGE"),
+    (312, "LD64_GOT_LO12_NC"),
+    (512, "TLSGD_ADR_PREL21"),
+    (513, "TLSGD_ADR_PAGE21"),
+    (514, "TLSGD_ADD_LO12_NC"),
+    (541, "TLSIE_ADR_GOTTPREL_PAGE21"),
+    (542, "TLSIE_LD64_GOTTPREL_LO12_NC"),
+    (549, "TLSLE_ADD_TPREL_HI12"),
+    (550, "TLSLE_ADD_TPREL_LO12"),
+    (551, "TLSLE_ADD_TPREL_LO12_NC"),
+    (562, "TLSDESC_ADR_PAGE21"),
+    (563, "TLSDESC_LD64_LO12"),
+    (564, "TLSDESC_ADD_LO12"),
+    (569, "TLSDESC_CALL"),
+    (1024, "COPY"),
+    (1025, "GLOB_DAT"),
+    (1026, "JUMP_SLOT"),
+    (1027, "RELATIVE"),
+    (1028, "TLS_DTPMOD64"),
+    (1029, "TLS_DTPREL64"),
+    (1030, "TLS_TPREL64"),
+    (1031, "TLSDESC"),
+    (1032, "IRELATIVE"),
+    (1041, "AUTH_RELATIVE"),
+];
+
+const ARM_RELOCATIONS: [(u32, &str); 67] = [
+    (0, "NONE"),
+    (1, "PC24"),
+    (2, "ABS32"),
+    (3, "REL32"),
+    (4, "LDR_PC_G0"),
+    (5, "ABS16"),
+    (6, "ABS12"),
+    (7, "THM_ABS5"),
+    (8, "ABS8"),
+    (9, "SBREL32"),
+    (10, "THM_CALL"),
+    (11, "THM_PC8"),
+    (12, "BREL_ADJ"),
+    (13, "TLS_DESC"),
+    (14, "THM_SWI8"),
+    (15, "XPC25"),
+    (16, "THM_XPC22"),
+    (17, "TLS_DTPMOD32"),
+    (18, "TLS_DTPOFF32"),
+    (19, "TLS_TPOFF32"),
+    (20, "COPY"),
+    (21, "GLOB_DAT"),
+    (22, "JUMP_SLOT"),
+    (23, "RELATIVE"),
+    (24, "GOTOFF32"),
+    (25, "BASE_PREL"),
+    (26, "GOT_BREL"),
+    (27, "PLT32"),
+    (28, "CALL"),
+    (29, "JUMP24"),
+    (30, "THM_JUMP24"),
+    (31, "BASE_ABS"),
+    (38, "TARGET1"),
+    (39, "SBREL31"),
+    (40, "V4BX"),
+    (41, "TARGET2"),
+    (42, "PREL31"),
+    (43, "MOVW_ABS_NC"),
+    (44, "MOVT_ABS"),
+    (45, "MOVW_PREL_NC"),
+    (46, "MOVT_PREL"),
+    (47, "THM_MOVW_ABS_NC"),
+    (48, "THM_MOVT_ABS"),
+    (49, "THM_MOVW_PREL_NC"),
+    (50, "THM_MOVT_PREL"),
+    (51, "THM_JUMP19"),
+    (52, "THM_JUMP6"),
+    (53, "THM_ALU_PREL_11_0"),
+    (54, "THM_PC12"),
+    (55, "ABS32_NOI"),
+    (56, "REL32_NOI"),
+    (100, "GNU_VTENTRY"),
+    (101, "GNU_VTINHERIT"),
+    (102, "THM_JUMP11"),
+    (103, "THM_JUMP8"),
+    (104, "TLS_GD32"),
+    (105, "TLS_LDM32"),
+    (106, "TLS_LDO32"),
+    (107, "TLS_IE32"),
+    (108, "TLS_LE32"),
+    (109, "TLS_LDO12"),
+    (110, "TLS_LE12"),
+    (111, "TLS_IE12GP"),
+    (129, "THM_TLS_DESCSEQ16"),
+    (130, "THM_TLS_DESCSEQ32"),
+    (131, "THM_GOT_BREL12"),
+    (160, "IRELATIVE"),
+];
+
+const RISCV_RELOCATIONS: [(u32, &str); 61] = [
+    (0, "NONE"),
+    (1, "32"),
+    (2, "64"),
+    (3, "RELATIVE"),
+    (4, "COPY"),
+    (5, "JUMP_SLOT"),
+    (6, "TLS_DTPMOD32"),
+    (7, "TLS_DTPMOD64"),
+    (8, "TLS_DTPREL32"),
+    (9, "TLS_DTPREL64"),
+    (10, "TLS_TPREL32"),
+    (11, "TLS_TPREL64"),
+    (12, "TLSDESC"),
+    (16, "BRANCH"),
+    (17, "JAL"),
+    (18, "CALL"),
+    (19, "CALL_PLT"),
+    (20, "GOT_HI20"),
+    (21, "TLS_GOT_HI20"),
+    (22, "TLS_GD_HI20"),
+    (23, "PCREL_HI20"),
+    (24, "PCREL_LO12_I"),
+    (25, "PCREL_LO12_S"),
+    (26, "HI20"),
+    (27, "LO12_I"),
+    (28, "LO12_S"),
+    (29, "TPREL_HI20"),
+    (30, "TPREL_LO12_I"),
+    (31, "TPREL_LO12_S"),
+    (32, "TPREL_ADD"),
+    (33, "ADD8"),
+    (34, "ADD16"),
+    (35, "ADD32"),
+    (36, "ADD64"),
+    (37, "SUB8"),
+    (38, "SUB16"),
+    (39, "SUB32"),
+    (40, "SUB64"),
+    (41, "GOT32_PCREL"),
+    (43, "ALIGN"),
+    (44, "RVC_BRANCH"),
+    (45, "RVC_JUMP"),
+    (46, "RVC_LUI"),
+    (51, "RELAX"),
+    (52, "SUB6"),
+    (53, "SET6"),
+    (54, "SET8"),
+    (55, "SET16"),
+    (56, "SET32"),
+    (57, "32_PCREL"),
+    (58, "IRELATIVE"),
+    (59, "PLT32"),
+    (60, "SET_ULEB128"),
+    (61, "SUB_ULEB128"),
+    (62, "TLSDESC_HI20"),
+    (63, "TLSDESC_LOAD_LO12"),
+    (64, "TLSDESC_ADD_LO12"),
+    (65, "TLSDESC_CALL"),
+    (191, "VENDOR"),
+    (192, "CUSTOM192"),
+    (255, "CUSTOM255"),
+];
+
+fn lookup(names: &[(u32, &'static str)], r_type: u32) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|(value, _)| *value == r_type)
+        .map(|(_, name)| *name)
+}
+
+/// Name of a relocation type, e.g. `R_X86_64_JUMP_SLOT`, or its number for
+/// unsupported machines and unknown types
+pub(super) fn relocation_type_name(machine: &MachineType, r_type: u32) -> String {
+    let name = match machine {
+        MachineType::AmdX64 => lookup(&X86_64_RELOCATIONS, r_type).map(|n| ("X86_64", n)),
+        MachineType::X86 => lookup(&I386_RELOCATIONS, r_type).map(|n| ("386", n)),
+        MachineType::Arm64 => lookup(&AARCH64_RELOCATIONS, r_type).map(|n| ("AARCH64", n)),
+        MachineType::Arm => lookup(&ARM_RELOCATIONS, r_type).map(|n| ("ARM", n)),
+        MachineType::RiscV => lookup(&RISCV_RELOCATIONS, r_type).map(|n| ("RISCV", n)),
+        _ => None,
+    };
+    match name {
+        Some((prefix, name)) => format!("R_{prefix}_{name}"),
+        None => r_type.to_string(),
+    }
+}
+
+/// The relative relocation type implied by every entry of a RELR table
+pub(super) fn relative_relocation_type(machine: &MachineType) -> u32 {
+    match machine {
+        MachineType::AmdX64 | MachineType::X86 => 8,
+        MachineType::Arm64 => 1027,
+        MachineType::Arm => 23,
+        MachineType::RiscV => 3,
+        _ => 0,
+    }
+}
+
+/// The relocation type the dynamic linker uses to fill PLT slots
+pub(super) fn jump_slot_relocation_type(machine: &MachineType) -> Option<u32> {
+    match machine {
+        MachineType::AmdX64 | MachineType::X86 => Some(7),
+        MachineType::Arm64 => Some(1026),
+        MachineType::Arm => Some(22),
+        MachineType::RiscV => Some(5),
+        _ => None,
+    }
+}
+
+/// The relocation type used for GOT entries of symbols bound at load time
+pub(super) fn glob_dat_relocation_type(machine: &MachineType) -> Option<u32> {
+    match machine {
+        MachineType::AmdX64 | MachineType::X86 => Some(6),
+        MachineType::Arm64 => Some(1025),
+        MachineType::Arm => Some(21),
+        _ => None,
+    }
+}
@@ -0,0 +1,247 @@
+use super::{ElfError, ElfSectionType, Result, SectionHeader};
+use crate::blob::Blob;
+
+// Group flags of the Android packed relocation format (APS2)
+const RELOCATION_GROUPED_BY_INFO_FLAG: i64 = 1;
+const RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG: i64 = 2;
+const RELOCATION_GROUPED_BY_ADDEND_FLAG: i64 = 4;
+const RELOCATION_GROUP_HAS_ADDEND_FLAG: i64 = 8;
+
+pub struct Relocation {
+    // Index of the relocation section this entry belongs to
+    pub(super) section: usize,
+    // Location to apply the relocation to
+    pub(super) offset: u64,
+    // Machine specific relocation type
+    pub(super) r_type: u32,
+    // Symbol table index, 0 if there is no symbol
+    pub(super) symbol: u32,
+    // Explicit addend, only present in RELA style relocations
+    pub(super) addend: Option<i64>,
+}
+
+impl Relocation {
+    fn from_info(section: usize, offset: u64, info: u64, addend: Option<i64>, is_64: bool) -> Self {
+        let (symbol, r_type) = if is_64 {
+            ((info >> 32) as u32, (info & 0xffffffff) as u32)
+        } else {
+            ((info >> 8) as u32, (info & 0xff) as u32)
+        };
+        Self {
+            section,
+            offset,
+            r_type,
+            symbol,
+            addend,
+        }
+    }
+}
+
+/// Decode all relocations of a REL, RELA, RELR or Android packed relocation section
+pub(super) fn parse_relocation_section(
+    blob: &Blob,
+    section_idx: usize,
+    section: &SectionHeader,
+    is_64: bool,
+    relative_type: u32,
+) -> Result<Vec<Relocation>> {
+    match section.section_type {
+        ElfSectionType::Rel => parse_rel(blob, section_idx, section, is_64, false),
+        ElfSectionType::Rela => parse_rel(blob, section_idx, section, is_64, true),
+        ElfSectionType::Relr | ElfSectionType::AndroidRelr => {
+            parse_relr(blob, section_idx, section, is_64, relative_type)
+        }
+        ElfSectionType::AndroidRel => {
+            parse_android_packed(blob, section_idx, section, is_64, false)
+        }
+        ElfSectionType::AndroidRela => {
+            parse_android_packed(blob, section_idx, section, is_64, true)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// End of the section content, which has to lie within the file
+fn section_end(blob: &Blob, section: &SectionHeader) -> Result<usize> {
+    (section.offset as usize)
+        .checked_add(section.size as usize)
+        .filter(|end| *end <= blob.len())
+        .ok_or(ElfError::InternalError)
+}
+
+fn parse_rel(
+    blob: &Blob,
+    section_idx: usize,
+    section: &SectionHeader,
+    is_64: bool,
+    has_addend: bool,
+) -> Result<Vec<Relocation>> {
+    let word_size = if is_64 { 8 } else { 4 };
+    let ent_size = match section.ent_size as usize {
+        0 if has_addend => 3 * word_size,
+        0 => 2 * word_size,
+        ent_size => ent_size,
+    };
+    let end = section_end(blob, section)?;
+    let mut idx = section.offset as usize;
+    let mut relocations = Vec::with_capacity(section.size as usize / ent_size);
+    while idx + ent_size <= end {
+        let (offset, info, addend) = if is_64 {
+            let addend = if has_addend {
+                Some(blob.get_u64(idx + 16)? as i64)
+            } else {
+                None
+            };
+            (blob.get_u64(idx)?, blob.get_u64(idx + 8)?, addend)
+        } else {
+            let addend = if has_addend {
+                Some(blob.get_u32(idx + 8)? as i32 as i64)
+            } else {
+                None
+            };
+            (
+                blob.get_u32(idx)? as u64,
+                blob.get_u32(idx + 4)? as u64,
+                addend,
+            )
+        };
+        relocations.push(Relocation::from_info(
+            section_idx,
+            offset,
+            info,
+            addend,
+            is_64,
+        ));
+        idx += ent_size;
+    }
+    Ok(relocations)
+}
+
+/// Expand a RELR table: an even entry is an address to relocate, an odd entry
+/// is a bitmap of the following word sized slots
+fn parse_relr(
+    blob: &Blob,
+    section_idx: usize,
+    section: &SectionHeader,
+    is_64: bool,
+    relative_type: u32,
+) -> Result<Vec<Relocation>> {
+    let word_size: u64 = if is_64 { 8 } else { 4 };
+    let bits = word_size * 8;
+    let mut relocations = Vec::new();
+    let mut base = 0u64;
+    let end = section_end(blob, section)?;
+    let mut idx = section.offset as usize;
+    while idx + word_size as usize <= end {
+        let entry = if is_64 {
+            blob.get_u64(idx)?
+        } else {
+            blob.get_u32(idx)? as u64
+        };
+        if entry & 1 == 0 {
+            relocations.push(Relocation {
+                section: section_idx,
+                offset: entry,
+                r_type: relative_type,
+                symbol: 0,
+                addend: None,
+            });
+            base = entry.wrapping_add(word_size);
+        } else {
+            for bit in 1..bits {
+                if (entry >> bit) & 1 != 0 {
+                    relocations.push(Relocation {
+                        section: section_idx,
+                        offset: base.wrapping_add((bit - 1) * word_size),
+                        r_type: relative_type,
+                        symbol: 0,
+                        addend: None,
+                    });
+                }
+            }
+            base = base.wrapping_add((bits - 1) * word_size);
+        }
+        idx += word_size as usize;
+    }
+    Ok(relocations)
+}
+
+/// Expand the Android packed relocation format, a stream of SLEB128 values
+/// starting with the magic "APS2"
+fn parse_android_packed(
+    blob: &Blob,
+    section_idx: usize,
+    section: &SectionHeader,
+    is_64: bool,
+    is_rela: bool,
+) -> Result<Vec<Relocation>> {
+    let mut idx = section.offset as usize;
+    if blob.get_slice(idx, 4)? != b"APS2" {
+        return Err(ElfError::InternalError);
+    }
+    idx += 4;
+    let mut next = || -> Result<i64> {
+        let (value, len) = blob.get_sleb128(idx)?;
+        idx += len;
+        Ok(value)
+    };
+
+    let mut remaining = next()?;
+    // Grouped relocations take no space in the stream, but each one patches a word of the image
+    let word_size = if is_64 { 8 } else { 4 };
+    if remaining > (blob.len() / word_size) as i64 {
+        return Err(ElfError::InternalError);
+    }
+    let mut offset = next()? as u64;
+    let mut addend = 0i64;
+    let mut relocations = Vec::new();
+    while remaining > 0 {
+        let group_size = next()?;
+        if group_size <= 0 || group_size > remaining {
+            return Err(ElfError::InternalError);
+        }
+        remaining -= group_size;
+        let group_flags = next()?;
+        let grouped_by_info = group_flags & RELOCATION_GROUPED_BY_INFO_FLAG != 0;
+        let grouped_by_offset_delta = group_flags & RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG != 0;
+        let grouped_by_addend = group_flags & RELOCATION_GROUPED_BY_ADDEND_FLAG != 0;
+        let group_has_addend = group_flags & RELOCATION_GROUP_HAS_ADDEND_FLAG != 0;
+
+        let group_offset_delta = if grouped_by_offset_delta {
+            next()? as u64
+        } else {
+            0
+        };
+        let group_info = if grouped_by_info { next()? as u64 } else { 0 };
+        if grouped_by_addend && group_has_addend {
+            addend = addend.wrapping_add(next()?);
+        }
+        if !group_has_addend {
+            addend = 0;
+        }
+
+        for _ in 0..group_size {
+            offset = offset.wrapping_add(if grouped_by_offset_delta {
+                group_offset_delta
+            } else {
+                next()? as u64
+            });
+            let info = if grouped_by_info {
+                group_info
+            } else {
+                next()? as u64
+            };
+            if group_has_addend && !grouped_by_addend {
+                addend = addend.wrapping_add(next()?);
+            }
+            relocations.push(Relocation::from_info(
+                section_idx,
+                offset,
+                info,
+                if is_rela { Some(addend) } else { None },
+                is_64,
+            ));
+        }
+    }
+    Ok(relocations)
+}
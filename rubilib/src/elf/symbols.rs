@@ -25,6 +25,104 @@ pub enum SymbolType {
     Unknown = 0xf,
 }
 
+/// Common interface of ELF32 and ELF64 symbol table entries
+pub trait Symbol: Send + Sync {
+    /// Absolute offset of the symbol name in the binary
+    fn name(&self) -> Option<usize>;
+    /// Index of the section the symbol is defined in, 0 for undefined symbols
+    fn section_index(&self) -> u16;
+    /// Table row of the symbol, optionally with demangled name
+    fn to_vec(&self, blob: &Blob, demangle: bool) -> Result<Vec<String>>;
+}
+
+/// Parse the symbol table entry at `idx` using the layout of the file's ELF class
+pub(super) fn new_symbol(
+    blob: &Blob,
+    idx: usize,
+    string_table_offset: usize,
+    is_64: bool,
+) -> Result<Box<dyn Symbol>> {
+    if is_64 {
+        Ok(Box::new(Symbol64::new(blob, idx, string_table_offset)?))
+    } else {
+        Ok(Box::new(Symbol32::new(blob, idx, string_table_offset)?))
+    }
+}
+
+fn symbol_name(blob: &Blob, idx: usize, string_table_offset: usize) -> Result<Option<usize>> {
+    let name_addr = string_table_offset + (blob.get_u32(idx)? as usize);
+    Ok(if name_addr == 0 {
+        None
+    } else {
+        Some(name_addr)
+    })
+}
+
+fn symbol_display_name(blob: &Blob, name: Option<usize>, demangle: bool) -> Result<String> {
+    let name = blob.get_cname(name)?;
+    Ok(if demangle {
+        crate::demangle::demangle(&name)
+    } else {
+        name
+    })
+}
+
+pub struct Symbol32 {
+    // Symbol name, index in string tbl
+    name: Option<usize>,
+    symbol_type: SymbolType,
+    binding: SymbolBinding,
+    // No defined meaning, 0
+    other: u8,
+    // Associated section index
+    index: u16,
+    // Value of the symbol
+    value: u32,
+    // Associated symbol size
+    size: u32,
+}
+
+impl Symbol32 {
+    pub(super) fn new(blob: &Blob, idx: usize, string_table_offset: usize) -> Result<Self> {
+        let name = symbol_name(blob, idx, string_table_offset)?;
+        // Lower 4 bits: symbol type, upper 4 bits: symbol binding
+        let info = blob.get_u8(idx + 12)?;
+
+        Ok(Self {
+            name,
+            symbol_type: SymbolType::from_repr(info & 0xf).unwrap_or(SymbolType::Unknown),
+            binding: SymbolBinding::from_repr(info >> 4).unwrap_or(SymbolBinding::Unknown),
+            other: blob.get_u8(idx + 13)?,
+            index: blob.get_u16(idx + 14)?,
+            value: blob.get_u32(idx + 4)?,
+            size: blob.get_u32(idx + 8)?,
+        })
+    }
+}
+
+impl Symbol for Symbol32 {
+    fn name(&self) -> Option<usize> {
+        self.name
+    }
+
+    fn section_index(&self) -> u16 {
+        self.index
+    }
+
+    fn to_vec(&self, blob: &Blob, demangle: bool) -> Result<Vec<String>> {
+        let mut v = Vec::with_capacity(7);
+        v.push(format!("{:?}", self.symbol_type));
+        v.push(format!("{:?}", self.binding));
+        v.push(format!("0x{:08x}", self.other));
+        v.push(format!("0x{:08x}", self.value));
+        v.push(format!("0x{:08x}", self.size));
+        v.push(format!("0x{:04x}", self.index));
+        v.push(symbol_display_name(blob, self.name, demangle)?);
+
+        Ok(v)
+    }
+}
+
 pub struct Symbol64 {
     // Symbol name, index in string tbl
     name: Option<usize>,
@@ -42,12 +140,7 @@ pub struct Symbol64 {
 
 impl Symbol64 {
     pub(super) fn new(blob: &Blob, idx: usize, string_table_offset: usize) -> Result<Self> {
-        let name_addr = string_table_offset + (blob.get_u32(idx)? as usize);
-        let name = if name_addr == 0 {
-            None
-        } else {
-            Some(name_addr)
-        };
+        let name = symbol_name(blob, idx, string_table_offset)?;
         // Lower 4 bits: symbol type, upper 4 bits: symbol binding
         let info = blob.get_u8(idx + 4)?;
 
@@ -61,8 +154,18 @@ impl Symbol64 {
             size: blob.get_u64(idx + 16)?,
         })
     }
+}
+
+impl Symbol for Symbol64 {
+    fn name(&self) -> Option<usize> {
+        self.name
+    }
+
+    fn section_index(&self) -> u16 {
+        self.index
+    }
 
-    pub(super) fn to_vec(&self, blob: &Blob) -> Result<Vec<String>> {
+    fn to_vec(&self, blob: &Blob, demangle: bool) -> Result<Vec<String>> {
         let mut v = Vec::with_capacity(7);
         v.push(format!("{:?}", self.symbol_type));
         v.push(format!("{:?}", self.binding));
@@ -70,7 +173,7 @@ impl Symbol64 {
         v.push(format!("0x{:016x}", self.value));
         v.push(format!("0x{:016x}", self.size));
         v.push(format!("0x{:04x}", self.index));
-        v.push(blob.get_cname(self.name)?);
+        v.push(symbol_display_name(blob, self.name, demangle)?);
 
         Ok(v)
     }
@@ -0,0 +1,444 @@
+use super::*;
+
+const SHF_ALLOC: u64 = 0x2;
+const PT_LOAD: u32 = 1;
+
+/// Section of a hand built ELF file, the size defaults to the length of the data
+struct TestSection {
+    name: &'static str,
+    section_type: u32,
+    flags: u64,
+    addr: u64,
+    data: Vec<u8>,
+    size: Option<u64>,
+    link: u32,
+    ent_size: u64,
+}
+
+fn section(name: &'static str, section_type: ElfSectionType, data: Vec<u8>) -> TestSection {
+    TestSection {
+        name,
+        section_type: section_type as u32,
+        flags: 0,
+        addr: 0,
+        data,
+        size: None,
+        link: 0,
+        ent_size: 0,
+    }
+}
+
+/// Segment of a hand built ELF file as type, file offset, address, file size and memory size
+type TestSegment = (u32, u64, u64, u64, u64);
+
+/// Little endian x86-64 ELF file with the section data behind the program headers,
+/// followed by the section header string table and the section headers
+fn build_elf(sections: Vec<TestSection>, segments: &[TestSegment]) -> ElfBinary {
+    build_elf_for(MachineType::AmdX64, sections, segments)
+}
+
+fn build_elf_for(
+    machine: MachineType,
+    sections: Vec<TestSection>,
+    segments: &[TestSegment],
+) -> ElfBinary {
+    ElfBinary::new(Blob::new(build_elf_data(machine, sections, segments)).unwrap()).unwrap()
+}
+
+fn build_elf_data(
+    machine: MachineType,
+    sections: Vec<TestSection>,
+    segments: &[TestSegment],
+) -> Vec<u8> {
+    let phoff = 64;
+    let mut data = vec![0u8; phoff + 56 * segments.len()];
+    data[..16].copy_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    data[16..18].copy_from_slice(&3u16.to_le_bytes());
+    data[18..20].copy_from_slice(&(machine as u16).to_le_bytes());
+    data[20..24].copy_from_slice(&1u32.to_le_bytes());
+    data[32..40].copy_from_slice(&(phoff as u64).to_le_bytes());
+    data[52..54].copy_from_slice(&64u16.to_le_bytes());
+    data[54..56].copy_from_slice(&56u16.to_le_bytes());
+    data[56..58].copy_from_slice(&(segments.len() as u16).to_le_bytes());
+    for (idx, (p_type, offset, vaddr, file_size, mem_size)) in segments.iter().enumerate() {
+        let entry = phoff + 56 * idx;
+        data[entry..entry + 4].copy_from_slice(&p_type.to_le_bytes());
+        data[entry + 8..entry + 16].copy_from_slice(&offset.to_le_bytes());
+        data[entry + 16..entry + 24].copy_from_slice(&vaddr.to_le_bytes());
+        data[entry + 24..entry + 32].copy_from_slice(&vaddr.to_le_bytes());
+        data[entry + 32..entry + 40].copy_from_slice(&file_size.to_le_bytes());
+        data[entry + 40..entry + 48].copy_from_slice(&mem_size.to_le_bytes());
+    }
+
+    let mut names = vec![0u8];
+    let mut headers = vec![0u8; 64];
+    let mut add_header = |data: &mut Vec<u8>, section: &TestSection, offset: usize| {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(&(names.len() as u32).to_le_bytes());
+        names.extend_from_slice(section.name.as_bytes());
+        names.push(0);
+        header[4..8].copy_from_slice(&section.section_type.to_le_bytes());
+        header[8..16].copy_from_slice(&section.flags.to_le_bytes());
+        header[16..24].copy_from_slice(&section.addr.to_le_bytes());
+        header[24..32].copy_from_slice(&(offset as u64).to_le_bytes());
+        let size = section.size.unwrap_or(section.data.len() as u64);
+        header[32..40].copy_from_slice(&size.to_le_bytes());
+        header[40..44].copy_from_slice(&section.link.to_le_bytes());
+        header[48..56].copy_from_slice(&1u64.to_le_bytes());
+        header[56..64].copy_from_slice(&section.ent_size.to_le_bytes());
+        headers.extend_from_slice(&header);
+        data.extend_from_slice(&section.data);
+        data.resize((data.len() + 7) & !7, 0);
+    };
+    for section in &sections {
+        let offset = data.len();
+        add_header(&mut data, section, offset);
+    }
+    let names_offset = data.len();
+    let names_section = TestSection {
+        name: ".shstrtab",
+        section_type: ElfSectionType::StrTab as u32,
+        flags: 0,
+        addr: 0,
+        data: Vec::new(),
+        size: None,
+        link: 0,
+        ent_size: 0,
+    };
+    add_header(&mut data, &names_section, names_offset);
+    // The string table is complete only now, patch in its content and size
+    let names_header = headers.len() - 64;
+    headers[names_header + 32..names_header + 40]
+        .copy_from_slice(&(names.len() as u64).to_le_bytes());
+    data.extend_from_slice(&names);
+    data.resize((data.len() + 7) & !7, 0);
+
+    let shoff = data.len();
+    data.extend_from_slice(&headers);
+    data[40..48].copy_from_slice(&(shoff as u64).to_le_bytes());
+    data[58..60].copy_from_slice(&64u16.to_le_bytes());
+    data[60..62].copy_from_slice(&(sections.len() as u16 + 2).to_le_bytes());
+    data[62..64].copy_from_slice(&(sections.len() as u16 + 1).to_le_bytes());
+    data
+}
+
+#[test]
+fn section_with_overflowing_size_is_not_in_segment() {
+    let mut text = section(".text", ElfSectionType::ProgBits, vec![0x90; 16]);
+    text.flags = SHF_ALLOC;
+    text.addr = 0x1000;
+    let mut bogus = section(".bogus", ElfSectionType::ProgBits, vec![0; 16]);
+    bogus.flags = SHF_ALLOC;
+    bogus.addr = 0x1010;
+    bogus.size = Some(u64::MAX - 8);
+    let mut elf = build_elf(vec![text, bogus], &[(PT_LOAD, 0, 0, 0x1000, 0x2000)]);
+    let mapping = elf.section_to_segment_table().unwrap();
+    assert_eq!(mapping.rows[0].content[2], ".text");
+}
+
+fn sleb128(mut value: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn android_packed(values: &[i64]) -> Vec<u8> {
+    let mut data = b"APS2".to_vec();
+    for value in values {
+        data.extend(sleb128(*value));
+    }
+    data
+}
+
+fn relocation_offsets(table: &Table) -> Vec<String> {
+    table
+        .rows
+        .iter()
+        .map(|row| row.content[2].clone())
+        .collect()
+}
+
+#[test]
+fn relr_addresses_and_bitmaps_are_expanded() {
+    let mut relr = Vec::new();
+    // Address 0x1000, then a bitmap for the 1st and 3rd word behind it
+    relr.extend_from_slice(&0x1000u64.to_le_bytes());
+    relr.extend_from_slice(&0b1011u64.to_le_bytes());
+    let mut elf = build_elf(vec![section(".relr.dyn", ElfSectionType::Relr, relr)], &[]);
+    let table = elf.relocations_table().unwrap();
+    assert_eq!(
+        relocation_offsets(&table),
+        [
+            "0x0000000000001000",
+            "0x0000000000001008",
+            "0x0000000000001018"
+        ]
+    );
+    assert!(table
+        .rows
+        .iter()
+        .all(|row| row.content[3] == "R_X86_64_RELATIVE"));
+}
+
+#[test]
+fn android_packed_groups_are_expanded() {
+    // Count and start offset, then two relocations grouped by info and offset delta and
+    // one relocation with its own offset delta, info and addend
+    let stream = android_packed(&[3, 0x2000, 2, 3, 8, 8, 1, 8, 0x10, 6, 0x20]);
+    let mut elf = build_elf(
+        vec![section(".rela.dyn", ElfSectionType::AndroidRela, stream)],
+        &[],
+    );
+    let table = elf.relocations_table().unwrap();
+    assert_eq!(
+        relocation_offsets(&table),
+        [
+            "0x0000000000002008",
+            "0x0000000000002010",
+            "0x0000000000002020"
+        ]
+    );
+    let types: Vec<_> = table.rows.iter().map(|row| &row.content[3]).collect();
+    assert_eq!(
+        types,
+        [
+            "R_X86_64_RELATIVE",
+            "R_X86_64_RELATIVE",
+            "R_X86_64_GLOB_DAT"
+        ]
+    );
+    let addends: Vec<_> = table.rows.iter().map(|row| &row.content[5]).collect();
+    assert_eq!(addends, ["0x0", "0x0", "0x20"]);
+}
+
+#[test]
+fn android_packed_count_larger_than_file_is_rejected() {
+    let stream = android_packed(&[1 << 60, 0, 1 << 60, 3, 8, 8]);
+    let mut elf = build_elf(
+        vec![section(".rela.dyn", ElfSectionType::AndroidRela, stream)],
+        &[],
+    );
+    assert!(elf.relocations_table().is_err());
+}
+
+#[test]
+fn relocation_section_beyond_file_is_rejected() {
+    let mut rela = section(".rela.dyn", ElfSectionType::Rela, vec![0; 24]);
+    rela.size = Some(u64::MAX - 16);
+    let mut elf = build_elf(vec![rela], &[]);
+    assert!(elf.relocations_table().is_err());
+}
+
+fn core_file_note(words: &[u64], names: &[u8]) -> Vec<u8> {
+    let mut desc: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+    desc.extend_from_slice(names);
+    let mut note = Vec::new();
+    note.extend_from_slice(&5u32.to_le_bytes());
+    note.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    note.extend_from_slice(&0x46494c45u32.to_le_bytes());
+    note.extend_from_slice(b"CORE\0\0\0\0");
+    note.extend_from_slice(&desc);
+    note
+}
+
+#[test]
+fn core_file_note_lists_mapped_files() {
+    let note = core_file_note(&[1, 0x1000, 0x400000, 0x401000, 2], b"/bin/true\0");
+    let mut elf = build_elf(vec![section(".note", ElfSectionType::Note, note)], &[]);
+    let table = elf.notes_table().unwrap();
+    assert_eq!(
+        table.rows[0].content[4],
+        "0x400000-0x401000 @0x2000 /bin/true"
+    );
+}
+
+#[test]
+fn core_file_note_count_larger_than_descriptor_is_rejected() {
+    let note = core_file_note(&[1 << 40, u64::MAX, 0, 0, 2], b"a\0");
+    let mut elf = build_elf(vec![section(".note", ElfSectionType::Note, note)], &[]);
+    assert!(elf.notes_table().is_err());
+}
+
+#[test]
+fn version_symbols_beyond_file_are_rejected() {
+    let mut versym = section(".gnu.version", ElfSectionType::GnuVerSym, vec![0; 4]);
+    versym.size = Some(1 << 50);
+    let mut elf = build_elf(vec![versym], &[]);
+    assert!(elf.required_glibc().is_err());
+}
+
+fn dynamic_entries(entries: &[(u64, u64)]) -> Vec<u8> {
+    entries
+        .iter()
+        .flat_map(|(tag, value)| [tag.to_le_bytes(), value.to_le_bytes()])
+        .flatten()
+        .collect()
+}
+
+#[test]
+fn unresolvable_dynamic_string_shows_raw_value() {
+    let dynstr = section(".dynstr", ElfSectionType::StrTab, b"\0libc.so.6\0".to_vec());
+    let dynamic = section(
+        ".dynamic",
+        ElfSectionType::Dynamic,
+        dynamic_entries(&[(1, 1), (1, u64::MAX), (0, 0)]),
+    );
+    let mut elf = build_elf(vec![dynstr, dynamic], &[]);
+    let table = elf.dynamic_table().unwrap();
+    let values: Vec<_> = table.rows.iter().map(|row| &row.content[3]).collect();
+    assert_eq!(
+        values,
+        ["libc.so.6", "0xffffffffffffffff", "0x0000000000000000"]
+    );
+}
+
+#[test]
+fn dynamic_section_beyond_address_space_is_rejected() {
+    let mut dynamic = section(
+        ".dynamic",
+        ElfSectionType::Dynamic,
+        dynamic_entries(&[(0, 0)]),
+    );
+    dynamic.size = Some(u64::MAX);
+    let mut elf = build_elf(vec![dynamic], &[]);
+    assert!(elf.dynamic_table().is_err());
+}
+
+/// .dynstr, .dynsym and .rela.plt with a JUMP_SLOT relocation of the given type for
+/// `puts` and `exit` each, as sections 1 to 3
+fn jump_slots(r_type: u64, got_addresses: [u64; 2]) -> Vec<TestSection> {
+    let dynstr = section(
+        ".dynstr",
+        ElfSectionType::StrTab,
+        b"\0puts\0exit\0".to_vec(),
+    );
+    let mut symbols = vec![0u8; 24];
+    for name in [1u32, 6] {
+        let mut symbol = [0u8; 24];
+        symbol[..4].copy_from_slice(&name.to_le_bytes());
+        symbol[4] = 0x12;
+        symbols.extend_from_slice(&symbol);
+    }
+    let mut dynsym = section(".dynsym", ElfSectionType::DynSym, symbols);
+    dynsym.link = 1;
+    dynsym.ent_size = 24;
+    let mut relocations = Vec::new();
+    for (symbol, address) in got_addresses.iter().enumerate() {
+        for value in [*address, (symbol as u64 + 1) << 32 | r_type, 0] {
+            relocations.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    let mut rela_plt = section(".rela.plt", ElfSectionType::Rela, relocations);
+    rela_plt.link = 2;
+    vec![dynstr, dynsym, rela_plt]
+}
+
+fn plt_stubs(elf: &mut ElfBinary) -> Vec<(u64, u64, String)> {
+    elf.plt_entries()
+        .unwrap()
+        .into_iter()
+        .map(|entry| (entry.stub_address, entry.got_address, entry.symbol))
+        .collect()
+}
+
+#[test]
+fn x86_64_plt_stubs_are_resolved_through_their_jump() {
+    let mut code = vec![0u8; 48];
+    // PLT0, then `jmp *disp(%rip)` stubs to the GOT slots in reverse order
+    code[16..18].copy_from_slice(&[0xff, 0x25]);
+    code[18..22].copy_from_slice(&(0x3020u32 - 0x1016).to_le_bytes());
+    code[32..34].copy_from_slice(&[0xff, 0x25]);
+    code[34..38].copy_from_slice(&(0x3018u32 - 0x1026).to_le_bytes());
+    let mut plt = section(".plt", ElfSectionType::ProgBits, code);
+    plt.addr = 0x1000;
+    plt.ent_size = 16;
+    let mut sections = jump_slots(7, [0x3018, 0x3020]);
+    sections.push(plt);
+    let mut elf = build_elf(sections, &[]);
+    assert_eq!(
+        plt_stubs(&mut elf),
+        [
+            (0x1010, 0x3020, "exit".to_string()),
+            (0x1020, 0x3018, "puts".to_string())
+        ]
+    );
+}
+
+#[test]
+fn aarch64_bti_plt_stubs_are_24_bytes() {
+    let mut plt = section(".plt", ElfSectionType::ProgBits, vec![0; 32 + 2 * 24]);
+    plt.addr = 0x1000;
+    let dynamic = section(
+        ".dynamic",
+        ElfSectionType::Dynamic,
+        dynamic_entries(&[(0x70000001, 0), (0, 0)]),
+    );
+    let mut sections = jump_slots(1026, [0x3018, 0x3020]);
+    sections.extend([plt, dynamic]);
+    let mut elf = build_elf_for(MachineType::Arm64, sections, &[]);
+    assert_eq!(
+        plt_stubs(&mut elf),
+        [
+            (0x1020, 0x3018, "puts".to_string()),
+            (0x1038, 0x3020, "exit".to_string())
+        ]
+    );
+}
+
+#[test]
+fn broken_note_keeps_the_notes_before_it() {
+    let mut notes = Vec::new();
+    for value in [4u32, 4, 3] {
+        notes.extend_from_slice(&value.to_le_bytes());
+    }
+    notes.extend_from_slice(b"GNU\0\xde\xad\xbe\xef");
+    // Name running out of the file
+    for value in [u32::MAX, 0, 1] {
+        notes.extend_from_slice(&value.to_le_bytes());
+    }
+    let mut elf = build_elf(vec![section(".note", ElfSectionType::Note, notes)], &[]);
+    assert_eq!(elf.build_id().unwrap().as_deref(), Some("deadbeef"));
+    assert_eq!(elf.notes_table().unwrap().rows.len(), 1);
+}
+
+#[test]
+fn broken_version_symbols_leave_the_version_column_empty() {
+    let mut versym = section(".gnu.version", ElfSectionType::GnuVerSym, vec![0; 6]);
+    versym.size = Some(1 << 50);
+    let mut sections = jump_slots(7, [0x3018, 0x3020]);
+    sections.push(versym);
+    let mut elf = build_elf(sections, &[]);
+    let table = elf.dyn_symbols_table(false).unwrap();
+    let names: Vec<_> = table.rows.iter().map(|row| &row.content[7]).collect();
+    assert_eq!(names, ["*empty*", "puts", "exit"]);
+    assert!(table.rows.iter().all(|row| row.content[8].is_empty()));
+}
+
+#[test]
+fn overlay_follows_the_section_headers() {
+    let mut data = build_elf_data(
+        MachineType::AmdX64,
+        vec![
+            section(".text", ElfSectionType::ProgBits, vec![0x90; 16]),
+            // Occupies no file space
+            TestSection {
+                size: Some(0x100000),
+                ..section(".bss", ElfSectionType::NoBits, Vec::new())
+            },
+        ],
+        &[(PT_LOAD, 0, 0x400000, 0x80, 0x80)],
+    );
+    let image_end = data.len();
+    data.extend_from_slice(b"\x1f\x8b\x08\x00 compressed");
+    let mut elf = ElfBinary::new(Blob::new(data).unwrap()).unwrap();
+    let overlay = elf.overlay().unwrap().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (image_end, 15));
+    assert_eq!(overlay.file_type, "gzip data");
+}
@@ -0,0 +1,222 @@
+use super::{ElfError, Result, SectionHeader};
+use crate::blob::Blob;
+
+const VER_FLG_BASE: u16 = 0x1;
+const VER_FLG_WEAK: u16 = 0x2;
+const VER_FLG_INFO: u16 = 0x4;
+
+// Bit of a .gnu.version entry that marks a symbol version as hidden
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// Entry of the version definition section .gnu.version_d
+pub struct VersionDefinition {
+    pub(super) index: u16,
+    pub(super) flags: u16,
+    // The defined version followed by the versions it inherits from
+    pub(super) names: Vec<String>,
+}
+
+/// Version of a library needed, from the version requirements section .gnu.version_r
+pub struct VersionNeed {
+    pub(super) file: String,
+    pub(super) index: u16,
+    pub(super) flags: u16,
+    pub(super) name: String,
+}
+
+fn flags_as_string(flags: u16) -> String {
+    let mut names = Vec::new();
+    if flags & VER_FLG_BASE != 0 {
+        names.push("BASE".to_string());
+    }
+    if flags & VER_FLG_WEAK != 0 {
+        names.push("WEAK".to_string());
+    }
+    if flags & VER_FLG_INFO != 0 {
+        names.push("INFO".to_string());
+    }
+    let rest = flags & !(VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO);
+    if rest != 0 {
+        names.push(format!("0x{rest:x}"));
+    }
+    names.join(" ")
+}
+
+/// Version indices of all dynamic symbols
+pub(super) fn parse_version_symbols(blob: &Blob, section: &SectionHeader) -> Result<Vec<u16>> {
+    let mut idx = section.offset as usize;
+    let end = idx
+        .checked_add(section.size as usize)
+        .filter(|end| *end <= blob.len())
+        .ok_or(ElfError::InternalError)?;
+    let mut versions = Vec::with_capacity(section.size as usize / 2);
+    while idx + 2 <= end {
+        versions.push(blob.get_u16(idx)?);
+        idx += 2;
+    }
+    Ok(versions)
+}
+
+/// Offset of the next entry in a chain of version entries
+fn add_offset(offset: usize, next: u32) -> Result<usize> {
+    offset
+        .checked_add(next as usize)
+        .ok_or(ElfError::InternalError)
+}
+
+fn string_at(blob: &Blob, string_table_offset: usize, name: u32) -> Result<String> {
+    let offset = string_table_offset
+        .checked_add(name as usize)
+        .ok_or(ElfError::InternalError)?;
+    Ok(blob.get_cname(Some(offset))?)
+}
+
+pub(super) fn parse_version_definitions(
+    blob: &Blob,
+    section: &SectionHeader,
+    string_table_offset: usize,
+) -> Result<Vec<VersionDefinition>> {
+    let mut definitions = Vec::new();
+    let mut idx = section.offset as usize;
+    // sh_info holds the number of entries
+    for _ in 0..section.info {
+        let flags = blob.get_u16(idx + 2)?;
+        let index = blob.get_u16(idx + 4)?;
+        let count = blob.get_u16(idx + 6)?;
+        let mut aux = add_offset(idx, blob.get_u32(idx + 12)?)?;
+        let mut names = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            names.push(string_at(blob, string_table_offset, blob.get_u32(aux)?)?);
+            let next = blob.get_u32(aux + 4)?;
+            if next == 0 {
+                break;
+            }
+            aux = add_offset(aux, next)?;
+        }
+        definitions.push(VersionDefinition {
+            index,
+            flags,
+            names,
+        });
+        let next = blob.get_u32(idx + 16)?;
+        if next == 0 {
+            break;
+        }
+        idx = add_offset(idx, next)?;
+    }
+    Ok(definitions)
+}
+
+pub(super) fn parse_version_needs(
+    blob: &Blob,
+    section: &SectionHeader,
+    string_table_offset: usize,
+) -> Result<Vec<VersionNeed>> {
+    let mut needs = Vec::new();
+    let mut idx = section.offset as usize;
+    // sh_info holds the number of entries
+    for _ in 0..section.info {
+        let count = blob.get_u16(idx + 2)?;
+        let file = string_at(blob, string_table_offset, blob.get_u32(idx + 4)?)?;
+        let mut aux = add_offset(idx, blob.get_u32(idx + 8)?)?;
+        for _ in 0..count {
+            let flags = blob.get_u16(aux + 4)?;
+            let index = blob.get_u16(aux + 6)?;
+            let name = string_at(blob, string_table_offset, blob.get_u32(aux + 8)?)?;
+            needs.push(VersionNeed {
+                file: file.clone(),
+                index,
+                flags,
+                name,
+            });
+            let next = blob.get_u32(aux + 12)?;
+            if next == 0 {
+                break;
+            }
+            aux = add_offset(aux, next)?;
+        }
+        let next = blob.get_u32(idx + 12)?;
+        if next == 0 {
+            break;
+        }
+        idx = add_offset(idx, next)?;
+    }
+    Ok(needs)
+}
+
+/// Version suffix of a dynamic symbol: `@VER` for references and hidden
+/// definitions, `@@VER` for the default version of a definition
+pub(super) fn version_suffix(
+    version: u16,
+    definitions: &[VersionDefinition],
+    needs: &[VersionNeed],
+) -> String {
+    let hidden = version & VERSYM_HIDDEN != 0;
+    let index = version & !VERSYM_HIDDEN;
+    // 0 and 1 are the local and global base versions
+    if index < 2 {
+        return String::new();
+    }
+    if let Some(need) = needs.iter().find(|need| need.index == index) {
+        return if hidden {
+            format!("@{} (hidden)", need.name)
+        } else {
+            format!("@{}", need.name)
+        };
+    }
+    if let Some(name) = definitions
+        .iter()
+        .find(|definition| definition.index == index)
+        .and_then(|definition| definition.names.first())
+    {
+        return if hidden {
+            format!("@{name} (hidden)")
+        } else {
+            format!("@@{name}")
+        };
+    }
+    format!("@<{index}>")
+}
+
+/// Highest GLIBC_x.y version needed, the minimum glibc the binary runs with
+pub(super) fn required_glibc(needs: &[VersionNeed]) -> Option<String> {
+    needs
+        .iter()
+        .filter_map(|need| {
+            let version = need.name.strip_prefix("GLIBC_")?;
+            let numbers = version
+                .split('.')
+                .map(|n| n.parse::<u32>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            Some((numbers, version))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, version)| version.to_string())
+}
+
+impl VersionDefinition {
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.index.to_string(),
+            flags_as_string(self.flags),
+            self.names.first().cloned().unwrap_or_default(),
+            self.names
+                .iter()
+                .skip(1)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" "),
+        ]
+    }
+}
+
+impl VersionNeed {
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.name.clone(),
+            self.index.to_string(),
+            flags_as_string(self.flags),
+        ]
+    }
+}
@@ -23,7 +23,11 @@ impl HexBinary {
     }
 
     pub fn as_hex_table(&self, bytes_per_line: u32) -> Result<Table> {
-        prepare_hex_table(&self.blob, bytes_per_line)
+        prepare_hex_table(&self.blob, 0, self.blob.len(), bytes_per_line)
+    }
+
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
     }
 
     pub fn header_info(&self) -> Vec<(String, String)> {
@@ -31,18 +35,30 @@ impl HexBinary {
     }
 }
 
-fn prepare_hex_table(blob: &Blob, bytes_per_line: u32) -> Result<Table> {
-    let bytes_per_line = bytes_per_line as usize;
+/// Hex dump of `size` bytes starting at file `offset`
+pub fn hex_dump_table(
+    blob: &Blob,
+    offset: usize,
+    size: usize,
+    bytes_per_line: u32,
+) -> Result<Table> {
+    // Fail early instead of dumping a truncated range
+    blob.get_slice(offset, size)?;
+    prepare_hex_table(blob, offset, size, bytes_per_line)
+}
+
+fn prepare_hex_table(blob: &Blob, start: usize, size: usize, bytes_per_line: u32) -> Result<Table> {
+    let bytes_per_line = bytes_per_line.max(1) as usize;
     let mut rows = Vec::new();
-    let mut offset = 0;
-    let max = blob.len();
+    let mut offset = start;
+    let max = blob.len().min(start.saturating_add(size));
     while offset < max {
         let mut first = true;
         let mut bytes = String::with_capacity(bytes_per_line * 3 - 1);
         let mut ascii_bytes = String::with_capacity(bytes_per_line);
         for idx in offset..max.min(offset + bytes_per_line) {
             let b = blob.get_u8(idx)?;
-            if b >= 32 && b < 128 {
+            if (32..128).contains(&b) {
                 write!(ascii_bytes, "{}", b as char)?;
             } else {
                 write!(ascii_bytes, ".")?;
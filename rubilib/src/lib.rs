@@ -1,6 +1,11 @@
+pub mod archive;
 pub mod binary;
 pub mod blob;
+pub mod coff;
+pub mod demangle;
 pub mod elf;
 pub mod hex;
+pub mod macho;
+pub mod overlay;
 pub mod pe;
 pub mod table;
@@ -0,0 +1,141 @@
+use super::{flags_as_string, get_be_u32, Result};
+use crate::blob::Blob;
+
+// Blob magics of the embedded signature, stored big endian
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade0c02;
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade0cc0;
+const CSMAGIC_BLOBWRAPPER: u32 = 0xfade0b01;
+
+// Slot types of the super blob index
+const CSSLOT_CODEDIRECTORY: u32 = 0;
+const CSSLOT_ENTITLEMENTS: u32 = 5;
+const CSSLOT_SIGNATURESLOT: u32 = 0x10000;
+
+// First version of the code directory with a team id
+const CS_SUPPORTSTEAMID: u32 = 0x20200;
+
+// Header of a CMS blob wrapper without any signature data
+const EMPTY_BLOB_WRAPPER_SIZE: u32 = 8;
+
+const CODE_DIRECTORY_FLAG_NAMES: [(u32, &str); 12] = [
+    (0x1, "VALID"),
+    (0x2, "ADHOC"),
+    (0x4, "GET_TASK_ALLOW"),
+    (0x8, "INSTALLER"),
+    (0x10, "FORCED_LV"),
+    (0x20, "INVALID_ALLOWED"),
+    (0x100, "HARD"),
+    (0x200, "KILL"),
+    (0x400, "CHECK_EXPIRATION"),
+    (0x800, "RESTRICT"),
+    (0x10000, "RUNTIME"),
+    (0x20000, "LINKER_SIGNED"),
+];
+
+const HASH_TYPE_NAMES: [&str; 5] = ["none", "SHA-1", "SHA-256", "SHA-256 truncated", "SHA-384"];
+
+/// Content of the code directory and signature of an embedded code signature
+pub(super) struct CodeSignature {
+    identifier: String,
+    team_id: Option<String>,
+    flags: u32,
+    hash_type: u8,
+    code_slots: u32,
+    // The signature contains a CMS signature and not only hashes
+    cms_signed: bool,
+    has_entitlements: bool,
+}
+
+impl CodeSignature {
+    pub(super) fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}, {}",
+            if self.cms_signed { "signed" } else { "ad-hoc" },
+            self.identifier
+        );
+        if let Some(team_id) = &self.team_id {
+            summary.push_str(&format!(", team {team_id}"));
+        }
+        summary.push_str(&format!(
+            ", {} hashes of {} pages",
+            HASH_TYPE_NAMES
+                .get(self.hash_type as usize)
+                .unwrap_or(&"unknown"),
+            self.code_slots
+        ));
+        if self.flags != 0 {
+            summary.push_str(&format!(
+                ", flags {}",
+                flags_as_string(self.flags, &CODE_DIRECTORY_FLAG_NAMES)
+            ));
+        }
+        if self.has_entitlements {
+            summary.push_str(", entitlements");
+        }
+        summary
+    }
+}
+
+fn parse_code_directory(blob: &Blob, offset: usize) -> Result<CodeSignature> {
+    let version = get_be_u32(blob, offset + 8)?;
+    let flags = get_be_u32(blob, offset + 12)?;
+    let identifier_offset = get_be_u32(blob, offset + 20)? as usize;
+    let code_slots = get_be_u32(blob, offset + 28)?;
+    let hash_type = blob.get_u8(offset + 37)?;
+    let identifier = blob
+        .get_cstr(offset + identifier_offset)?
+        .to_string_lossy()
+        .to_string();
+    let team_id = if version >= CS_SUPPORTSTEAMID {
+        match get_be_u32(blob, offset + 48)? as usize {
+            0 => None,
+            team_offset => Some(
+                blob.get_cstr(offset + team_offset)?
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        }
+    } else {
+        None
+    };
+    Ok(CodeSignature {
+        identifier,
+        team_id,
+        flags,
+        hash_type,
+        code_slots,
+        cms_signed: false,
+        has_entitlements: false,
+    })
+}
+
+/// Parse the super blob of an LC_CODE_SIGNATURE at file `offset`
+pub(super) fn parse_code_signature(blob: &Blob, offset: usize) -> Result<Option<CodeSignature>> {
+    if get_be_u32(blob, offset)? != CSMAGIC_EMBEDDED_SIGNATURE {
+        return Ok(None);
+    }
+    let count = get_be_u32(blob, offset + 8)? as usize;
+    let mut directory = None;
+    let mut cms_signed = false;
+    let mut has_entitlements = false;
+    for idx in 0..count {
+        let slot = get_be_u32(blob, offset + 12 + 8 * idx)?;
+        let blob_offset = offset + get_be_u32(blob, offset + 16 + 8 * idx)? as usize;
+        let magic = get_be_u32(blob, blob_offset)?;
+        match slot {
+            CSSLOT_CODEDIRECTORY if magic == CSMAGIC_CODEDIRECTORY => {
+                directory = Some(parse_code_directory(blob, blob_offset)?);
+            }
+            CSSLOT_ENTITLEMENTS => has_entitlements = true,
+            CSSLOT_SIGNATURESLOT if magic == CSMAGIC_BLOBWRAPPER => {
+                cms_signed = get_be_u32(blob, blob_offset + 4)? > EMPTY_BLOB_WRAPPER_SIZE;
+            }
+            _ => {}
+        }
+    }
+    Ok(directory.map(|directory| CodeSignature {
+        cms_signed,
+        has_entitlements,
+        ..directory
+    }))
+}
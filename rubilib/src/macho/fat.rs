@@ -0,0 +1,77 @@
+use super::{cpu_name, get_be_u32, get_be_u64, MachOError, Result};
+use crate::blob::Blob;
+
+// Universal binaries are always stored big endian
+const FAT_MAGIC: u32 = 0xcafebabe;
+const FAT_MAGIC_64: u32 = 0xcafebabf;
+
+/// One architecture slice of a universal binary
+pub(super) struct FatArch {
+    cputype: u32,
+    cpusubtype: u32,
+    // File offset and size of the embedded Mach-O image
+    pub(super) offset: u64,
+    pub(super) size: u64,
+    // Alignment as power of two
+    align: u32,
+}
+
+impl FatArch {
+    pub(super) fn name(&self) -> String {
+        cpu_name(self.cputype, self.cpusubtype)
+    }
+
+    pub(super) fn to_vec(&self, selected: bool) -> Vec<String> {
+        vec![
+            self.name(),
+            format!("0x{:08x}", self.cpusubtype),
+            format!("0x{:08x}", self.offset),
+            self.size.to_string(),
+            format!("2^{}", self.align),
+            if selected { "yes" } else { "" }.to_string(),
+        ]
+    }
+}
+
+/// Check for the universal binary magic
+pub(super) fn is_fat(blob: &Blob) -> Result<bool> {
+    let magic = get_be_u32(blob, 0)?;
+    Ok(magic == FAT_MAGIC || magic == FAT_MAGIC_64)
+}
+
+/// Read the architecture table following the fat header
+pub(super) fn parse_fat_header(blob: &Blob) -> Result<Vec<FatArch>> {
+    let is_64 = match get_be_u32(blob, 0)? {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return Err(MachOError::NoMachOBinary),
+    };
+    let count = get_be_u32(blob, 4)? as usize;
+    let mut slices = Vec::new();
+    let mut offset = 8;
+    for _ in 0..count {
+        let slice = if is_64 {
+            let slice = FatArch {
+                cputype: get_be_u32(blob, offset)?,
+                cpusubtype: get_be_u32(blob, offset + 4)?,
+                offset: get_be_u64(blob, offset + 8)?,
+                size: get_be_u64(blob, offset + 16)?,
+                align: get_be_u32(blob, offset + 24)?,
+            };
+            offset += 32;
+            slice
+        } else {
+            let slice = FatArch {
+                cputype: get_be_u32(blob, offset)?,
+                cpusubtype: get_be_u32(blob, offset + 4)?,
+                offset: get_be_u32(blob, offset + 8)? as u64,
+                size: get_be_u32(blob, offset + 12)? as u64,
+                align: get_be_u32(blob, offset + 16)?,
+            };
+            offset += 20;
+            slice
+        };
+        slices.push(slice);
+    }
+    Ok(slices)
+}
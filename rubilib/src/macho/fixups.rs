@@ -0,0 +1,448 @@
+use super::load_commands::Segment;
+use super::symbols::library_name;
+use super::{MachOError, Result};
+use crate::blob::Blob;
+
+// Pointer formats of dyld chained fixups
+const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+const DYLD_CHAINED_PTR_64: u16 = 2;
+const DYLD_CHAINED_PTR_32: u16 = 3;
+const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+
+// Formats of the import table
+const DYLD_CHAINED_IMPORT: u32 = 1;
+const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+// Page has no fixups
+const DYLD_CHAINED_PTR_START_NONE: u16 = 0xffff;
+// Page has several chains, the start value indexes the list of chain starts
+const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+const DYLD_CHAINED_PTR_START_LAST: u16 = 0x8000;
+
+const ARM64E_KEY_NAMES: [&str; 4] = ["IA", "IB", "DA", "DB"];
+
+/// A symbol bound by the dynamic linker
+pub(super) struct ChainedImport {
+    // Library ordinal, negative values are special lookups
+    library_ordinal: i32,
+    weak: bool,
+    name: String,
+    addend: i64,
+}
+
+/// Signing parameters of arm64e authenticated pointers
+struct PointerAuth {
+    key: u8,
+    diversity: u16,
+    address_diversity: bool,
+}
+
+enum FixupTarget {
+    // Pointer to an address of the image, from the preferred load address
+    Rebase(u64),
+    // Pointer to an imported symbol, index into the import table
+    Bind { import: u32, addend: i64 },
+}
+
+pub(super) struct Fixup {
+    segment: String,
+    // File offset of the pointer relative to the start of the image
+    pub(super) offset: u64,
+    address: u64,
+    target: FixupTarget,
+    auth: Option<PointerAuth>,
+}
+
+pub(super) struct ChainedFixups {
+    pub(super) imports: Vec<ChainedImport>,
+    pub(super) fixups: Vec<Fixup>,
+}
+
+impl ChainedFixups {
+    pub(super) fn fixup_to_vec(&self, fixup: &Fixup, libraries: &[String]) -> Vec<String> {
+        let (kind, target, library, addend) = match &fixup.target {
+            FixupTarget::Rebase(target) => (
+                "rebase",
+                format!("0x{target:016x}"),
+                String::new(),
+                String::new(),
+            ),
+            FixupTarget::Bind { import, addend } => match self.imports.get(*import as usize) {
+                Some(symbol) => (
+                    if symbol.weak { "weak bind" } else { "bind" },
+                    symbol.name.clone(),
+                    library_name(symbol.library_ordinal, libraries),
+                    symbol.addend.wrapping_add(*addend).to_string(),
+                ),
+                None => (
+                    "bind",
+                    format!("invalid import #{import}"),
+                    String::new(),
+                    addend.to_string(),
+                ),
+            },
+        };
+        vec![
+            fixup.segment.clone(),
+            format!("0x{:016x}", fixup.address),
+            match &fixup.auth {
+                Some(_) => format!("auth {kind}"),
+                None => kind.to_string(),
+            },
+            target,
+            library,
+            addend,
+            match &fixup.auth {
+                Some(auth) => format!(
+                    "key {}, diversity 0x{:04x}{}",
+                    ARM64E_KEY_NAMES[auth.key as usize & 3],
+                    auth.diversity,
+                    if auth.address_diversity {
+                        ", address"
+                    } else {
+                        ""
+                    }
+                ),
+                None => String::new(),
+            },
+        ]
+    }
+}
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// Offset within the image, crafted chains must not overflow
+fn add_offset(offset: u64, delta: u64) -> Result<u64> {
+    offset.checked_add(delta).ok_or(MachOError::InternalError)
+}
+
+fn bits(value: u64, start: u32, count: u32) -> u64 {
+    (value >> start) & ((1 << count) - 1)
+}
+
+/// Decode one pointer of a chain, returns the fixup target, authentication and distance to the next pointer
+fn decode_pointer(
+    value: u64,
+    format: u16,
+    image_base: u64,
+) -> Option<(FixupTarget, Option<PointerAuth>, u64)> {
+    let decoded = match format {
+        DYLD_CHAINED_PTR_ARM64E
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => {
+            let next = bits(value, 51, 11) * 8;
+            let is_bind = bits(value, 62, 1) != 0;
+            let is_auth = bits(value, 63, 1) != 0;
+            let ordinal_bits = if format == DYLD_CHAINED_PTR_ARM64E_USERLAND24 {
+                24
+            } else {
+                16
+            };
+            let auth = is_auth.then(|| PointerAuth {
+                diversity: bits(value, 32, 16) as u16,
+                address_diversity: bits(value, 48, 1) != 0,
+                key: bits(value, 49, 2) as u8,
+            });
+            let target = match (is_bind, is_auth) {
+                (true, true) => FixupTarget::Bind {
+                    import: bits(value, 0, ordinal_bits) as u32,
+                    addend: 0,
+                },
+                (true, false) => FixupTarget::Bind {
+                    import: bits(value, 0, ordinal_bits) as u32,
+                    addend: sign_extend(bits(value, 32, 19), 19),
+                },
+                (false, true) => FixupTarget::Rebase(image_base.wrapping_add(bits(value, 0, 32))),
+                (false, false) => {
+                    let target = bits(value, 0, 43) | bits(value, 43, 8) << 56;
+                    // Only the original arm64e format stores virtual addresses
+                    FixupTarget::Rebase(if format == DYLD_CHAINED_PTR_ARM64E {
+                        target
+                    } else {
+                        image_base.wrapping_add(target)
+                    })
+                }
+            };
+            (target, auth, next)
+        }
+        DYLD_CHAINED_PTR_64 | DYLD_CHAINED_PTR_64_OFFSET => {
+            let next = bits(value, 51, 12) * 4;
+            let target = if bits(value, 63, 1) != 0 {
+                FixupTarget::Bind {
+                    import: bits(value, 0, 24) as u32,
+                    addend: bits(value, 24, 8) as i64,
+                }
+            } else {
+                let target = bits(value, 0, 36) | bits(value, 36, 8) << 56;
+                FixupTarget::Rebase(if format == DYLD_CHAINED_PTR_64 {
+                    target
+                } else {
+                    image_base.wrapping_add(target)
+                })
+            };
+            (target, None, next)
+        }
+        DYLD_CHAINED_PTR_32 => {
+            let next = bits(value, 26, 5) * 4;
+            let target = if bits(value, 31, 1) != 0 {
+                FixupTarget::Bind {
+                    import: bits(value, 0, 20) as u32,
+                    addend: bits(value, 20, 6) as i64,
+                }
+            } else {
+                FixupTarget::Rebase(bits(value, 0, 26))
+            };
+            (target, None, next)
+        }
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+fn parse_imports(
+    blob: &Blob,
+    offset: usize,
+    count: u32,
+    format: u32,
+    symbols_offset: usize,
+) -> Result<Vec<ChainedImport>> {
+    let mut imports = Vec::new();
+    let mut entry = offset;
+    for _ in 0..count {
+        let (library_ordinal, weak, name_offset, addend, size) = match format {
+            DYLD_CHAINED_IMPORT_ADDEND64 => {
+                let value = blob.get_u64(entry)?;
+                (
+                    sign_extend(bits(value, 0, 16), 16) as i32,
+                    bits(value, 16, 1) != 0,
+                    bits(value, 32, 32) as usize,
+                    blob.get_u64(entry + 8)? as i64,
+                    16,
+                )
+            }
+            _ => {
+                let value = blob.get_u32(entry)? as u64;
+                let addend = if format == DYLD_CHAINED_IMPORT_ADDEND {
+                    blob.get_u32(entry + 4)? as i32 as i64
+                } else {
+                    0
+                };
+                (
+                    sign_extend(bits(value, 0, 8), 8) as i32,
+                    bits(value, 8, 1) != 0,
+                    bits(value, 9, 23) as usize,
+                    addend,
+                    if format == DYLD_CHAINED_IMPORT { 4 } else { 8 },
+                )
+            }
+        };
+        imports.push(ChainedImport {
+            library_ordinal,
+            weak,
+            name: blob
+                .get_cstr(symbols_offset + name_offset)?
+                .to_string_lossy()
+                .to_string(),
+            addend,
+        });
+        entry += size;
+    }
+    Ok(imports)
+}
+
+/// Follow the chain of pointers starting at `offset`, each pointer encodes the distance to the next one
+fn walk_chain(
+    blob: &Blob,
+    base: usize,
+    segment: &Segment,
+    offset: u64,
+    format: u16,
+    image_base: u64,
+    fixups: &mut Vec<Fixup>,
+) -> Result<()> {
+    let mut offset = offset;
+    loop {
+        let position = add_offset(base as u64, offset)? as usize;
+        let value = if format == DYLD_CHAINED_PTR_32 {
+            blob.get_u32(position)? as u64
+        } else {
+            blob.get_u64(position)?
+        };
+        let Some((target, auth, next)) = decode_pointer(value, format, image_base) else {
+            return Ok(());
+        };
+        fixups.push(Fixup {
+            segment: segment.name.clone(),
+            offset,
+            address: segment
+                .vm_address
+                .wrapping_add(offset)
+                .wrapping_sub(segment.file_offset),
+            target,
+            auth,
+        });
+        if next == 0 {
+            return Ok(());
+        }
+        offset = add_offset(offset, next)?;
+    }
+}
+
+/// Decode the LC_DYLD_CHAINED_FIXUPS data at `offset` of an image starting at `base`
+pub(super) fn parse_chained_fixups(
+    blob: &Blob,
+    base: usize,
+    offset: usize,
+    segments: &[&Segment],
+    image_base: u64,
+) -> Result<ChainedFixups> {
+    let starts_offset = offset + blob.get_u32(offset + 4)? as usize;
+    let imports_offset = offset + blob.get_u32(offset + 8)? as usize;
+    let symbols_offset = offset + blob.get_u32(offset + 12)? as usize;
+    let imports_count = blob.get_u32(offset + 16)?;
+    let imports_format = blob.get_u32(offset + 20)?;
+    let imports = parse_imports(
+        blob,
+        imports_offset,
+        imports_count,
+        imports_format,
+        symbols_offset,
+    )?;
+
+    let mut fixups = Vec::new();
+    let segment_count = blob.get_u32(starts_offset)? as usize;
+    for (idx, segment) in segments.iter().enumerate().take(segment_count) {
+        let info_offset = blob.get_u32(starts_offset + 4 + 4 * idx)? as usize;
+        if info_offset == 0 {
+            continue;
+        }
+        let info = starts_offset + info_offset;
+        let page_size = blob.get_u16(info + 4)? as u64;
+        let format = blob.get_u16(info + 6)?;
+        let page_count = blob.get_u16(info + 20)? as usize;
+        let page_starts = info + 22;
+        for page in 0..page_count {
+            let start = blob.get_u16(page_starts + 2 * page)?;
+            if start == DYLD_CHAINED_PTR_START_NONE {
+                continue;
+            }
+            let page_offset = add_offset(segment.file_offset, page as u64 * page_size)?;
+            if start & DYLD_CHAINED_PTR_START_MULTI != 0 && format == DYLD_CHAINED_PTR_32 {
+                let mut chain = (start & !DYLD_CHAINED_PTR_START_MULTI) as usize;
+                loop {
+                    let chain_start = blob.get_u16(page_starts + 2 * chain)?;
+                    walk_chain(
+                        blob,
+                        base,
+                        segment,
+                        add_offset(
+                            page_offset,
+                            (chain_start & !DYLD_CHAINED_PTR_START_LAST) as u64,
+                        )?,
+                        format,
+                        image_base,
+                        &mut fixups,
+                    )?;
+                    if chain_start & DYLD_CHAINED_PTR_START_LAST != 0 {
+                        break;
+                    }
+                    chain += 1;
+                }
+            } else {
+                walk_chain(
+                    blob,
+                    base,
+                    segment,
+                    add_offset(page_offset, start as u64)?,
+                    format,
+                    image_base,
+                    &mut fixups,
+                )?;
+            }
+        }
+    }
+    Ok(ChainedFixups { imports, fixups })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE_BASE: u64 = 0x100000000;
+
+    fn rebase(value: u64, format: u16) -> (u64, Option<PointerAuth>, u64) {
+        match decode_pointer(value, format, IMAGE_BASE) {
+            Some((FixupTarget::Rebase(target), auth, next)) => (target, auth, next),
+            _ => panic!("no rebase"),
+        }
+    }
+
+    fn bind(value: u64, format: u16) -> (u32, i64, Option<PointerAuth>, u64) {
+        match decode_pointer(value, format, IMAGE_BASE) {
+            Some((FixupTarget::Bind { import, addend }, auth, next)) => {
+                (import, addend, auth, next)
+            }
+            _ => panic!("no bind"),
+        }
+    }
+
+    #[test]
+    fn pointer_64_targets_and_strides() {
+        // Virtual address with high byte, next pointer 3 strides of 4 bytes ahead
+        let (target, auth, next) = rebase(0x4000 | 0x12 << 36 | 3 << 51, DYLD_CHAINED_PTR_64);
+        assert_eq!((target, next), (0x1200_0000_0000_4000, 12));
+        assert!(auth.is_none());
+        let (target, _, _) = rebase(0x4000, DYLD_CHAINED_PTR_64_OFFSET);
+        assert_eq!(target, IMAGE_BASE + 0x4000);
+        let (import, addend, _, next) = bind(1 << 63 | 5 | 3 << 24, DYLD_CHAINED_PTR_64);
+        assert_eq!((import, addend, next), (5, 3, 0));
+    }
+
+    #[test]
+    fn pointer_arm64e_authentication() {
+        let value = 1 << 63 | 0x2000 | 0x1234 << 32 | 1 << 48 | 2 << 49 | 1 << 51;
+        let (target, auth, next) = rebase(value, DYLD_CHAINED_PTR_ARM64E);
+        assert_eq!((target, next), (IMAGE_BASE + 0x2000, 8));
+        let auth = auth.unwrap();
+        assert_eq!(
+            (auth.key, auth.diversity, auth.address_diversity),
+            (2, 0x1234, true)
+        );
+
+        // Plain rebases of arm64e hold virtual addresses, userland ones offsets
+        let (target, _, _) = rebase(0x4000, DYLD_CHAINED_PTR_ARM64E);
+        assert_eq!(target, 0x4000);
+        let (target, _, _) = rebase(0x4000, DYLD_CHAINED_PTR_ARM64E_USERLAND);
+        assert_eq!(target, IMAGE_BASE + 0x4000);
+    }
+
+    #[test]
+    fn pointer_arm64e_binds() {
+        // Addend of -4 in 19 bits
+        let value = 1 << 62 | 7 | 0x7fffc << 32;
+        let (import, addend, auth, _) = bind(value, DYLD_CHAINED_PTR_ARM64E);
+        assert_eq!((import, addend), (7, -4));
+        assert!(auth.is_none());
+        let (import, addend, auth, _) = bind(
+            1 << 63 | 1 << 62 | 0x123456,
+            DYLD_CHAINED_PTR_ARM64E_USERLAND24,
+        );
+        assert_eq!((import, addend), (0x123456, 0));
+        assert!(auth.is_some());
+    }
+
+    #[test]
+    fn pointer_32_targets_and_strides() {
+        let (target, _, next) = rebase(0x3000 | 2 << 26, DYLD_CHAINED_PTR_32);
+        assert_eq!((target, next), (0x3000, 8));
+        let (import, addend, _, _) = bind(1 << 31 | 9 | 4 << 20, DYLD_CHAINED_PTR_32);
+        assert_eq!((import, addend), (9, 4));
+        assert!(decode_pointer(0, 0x7f, IMAGE_BASE).is_none());
+    }
+}
@@ -0,0 +1,666 @@
+use super::{flags_as_string, format_version, Result};
+use crate::blob::{Blob, BlobError};
+
+pub(super) const LC_SEGMENT: u32 = 0x1;
+pub(super) const LC_SYMTAB: u32 = 0x2;
+pub(super) const LC_DYSYMTAB: u32 = 0xb;
+pub(super) const LC_LOAD_DYLIB: u32 = 0xc;
+pub(super) const LC_ID_DYLIB: u32 = 0xd;
+pub(super) const LC_LOAD_DYLINKER: u32 = 0xe;
+pub(super) const LC_ID_DYLINKER: u32 = 0xf;
+pub(super) const LC_LOAD_WEAK_DYLIB: u32 = 0x80000018;
+pub(super) const LC_SEGMENT_64: u32 = 0x19;
+pub(super) const LC_UUID: u32 = 0x1b;
+pub(super) const LC_RPATH: u32 = 0x8000001c;
+pub(super) const LC_CODE_SIGNATURE: u32 = 0x1d;
+pub(super) const LC_SEGMENT_SPLIT_INFO: u32 = 0x1e;
+pub(super) const LC_REEXPORT_DYLIB: u32 = 0x8000001f;
+pub(super) const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+pub(super) const LC_ENCRYPTION_INFO: u32 = 0x21;
+pub(super) const LC_LOAD_UPWARD_DYLIB: u32 = 0x80000023;
+pub(super) const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+pub(super) const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+pub(super) const LC_FUNCTION_STARTS: u32 = 0x26;
+pub(super) const LC_MAIN: u32 = 0x80000028;
+pub(super) const LC_DATA_IN_CODE: u32 = 0x29;
+pub(super) const LC_SOURCE_VERSION: u32 = 0x2a;
+pub(super) const LC_DYLIB_CODE_SIGN_DRS: u32 = 0x2b;
+pub(super) const LC_ENCRYPTION_INFO_64: u32 = 0x2c;
+pub(super) const LC_LINKER_OPTIMIZATION_HINT: u32 = 0x2e;
+pub(super) const LC_VERSION_MIN_TVOS: u32 = 0x2f;
+pub(super) const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+pub(super) const LC_BUILD_VERSION: u32 = 0x32;
+pub(super) const LC_DYLD_EXPORTS_TRIE: u32 = 0x80000033;
+pub(super) const LC_DYLD_CHAINED_FIXUPS: u32 = 0x80000034;
+pub(super) const LC_ATOM_INFO: u32 = 0x36;
+
+const COMMAND_NAMES: [(u32, &str); 55] = [
+    (LC_SEGMENT, "LC_SEGMENT"),
+    (LC_SYMTAB, "LC_SYMTAB"),
+    (0x3, "LC_SYMSEG"),
+    (0x4, "LC_THREAD"),
+    (0x5, "LC_UNIXTHREAD"),
+    (0x6, "LC_LOADFVMLIB"),
+    (0x7, "LC_IDFVMLIB"),
+    (0x8, "LC_IDENT"),
+    (0x9, "LC_FVMFILE"),
+    (0xa, "LC_PREPAGE"),
+    (LC_DYSYMTAB, "LC_DYSYMTAB"),
+    (LC_LOAD_DYLIB, "LC_LOAD_DYLIB"),
+    (LC_ID_DYLIB, "LC_ID_DYLIB"),
+    (LC_LOAD_DYLINKER, "LC_LOAD_DYLINKER"),
+    (LC_ID_DYLINKER, "LC_ID_DYLINKER"),
+    (0x10, "LC_PREBOUND_DYLIB"),
+    (0x11, "LC_ROUTINES"),
+    (0x12, "LC_SUB_FRAMEWORK"),
+    (0x13, "LC_SUB_UMBRELLA"),
+    (0x14, "LC_SUB_CLIENT"),
+    (0x15, "LC_SUB_LIBRARY"),
+    (0x16, "LC_TWOLEVEL_HINTS"),
+    (0x17, "LC_PREBIND_CKSUM"),
+    (LC_LOAD_WEAK_DYLIB, "LC_LOAD_WEAK_DYLIB"),
+    (LC_SEGMENT_64, "LC_SEGMENT_64"),
+    (0x1a, "LC_ROUTINES_64"),
+    (LC_UUID, "LC_UUID"),
+    (LC_RPATH, "LC_RPATH"),
+    (LC_CODE_SIGNATURE, "LC_CODE_SIGNATURE"),
+    (LC_SEGMENT_SPLIT_INFO, "LC_SEGMENT_SPLIT_INFO"),
+    (LC_REEXPORT_DYLIB, "LC_REEXPORT_DYLIB"),
+    (LC_LAZY_LOAD_DYLIB, "LC_LAZY_LOAD_DYLIB"),
+    (LC_ENCRYPTION_INFO, "LC_ENCRYPTION_INFO"),
+    (0x22, "LC_DYLD_INFO"),
+    (0x80000022, "LC_DYLD_INFO_ONLY"),
+    (LC_LOAD_UPWARD_DYLIB, "LC_LOAD_UPWARD_DYLIB"),
+    (LC_VERSION_MIN_MACOSX, "LC_VERSION_MIN_MACOSX"),
+    (LC_VERSION_MIN_IPHONEOS, "LC_VERSION_MIN_IPHONEOS"),
+    (LC_FUNCTION_STARTS, "LC_FUNCTION_STARTS"),
+    (0x27, "LC_DYLD_ENVIRONMENT"),
+    (LC_MAIN, "LC_MAIN"),
+    (LC_DATA_IN_CODE, "LC_DATA_IN_CODE"),
+    (LC_SOURCE_VERSION, "LC_SOURCE_VERSION"),
+    (LC_DYLIB_CODE_SIGN_DRS, "LC_DYLIB_CODE_SIGN_DRS"),
+    (LC_ENCRYPTION_INFO_64, "LC_ENCRYPTION_INFO_64"),
+    (0x2d, "LC_LINKER_OPTION"),
+    (LC_LINKER_OPTIMIZATION_HINT, "LC_LINKER_OPTIMIZATION_HINT"),
+    (LC_VERSION_MIN_TVOS, "LC_VERSION_MIN_TVOS"),
+    (LC_VERSION_MIN_WATCHOS, "LC_VERSION_MIN_WATCHOS"),
+    (0x31, "LC_NOTE"),
+    (LC_BUILD_VERSION, "LC_BUILD_VERSION"),
+    (LC_DYLD_EXPORTS_TRIE, "LC_DYLD_EXPORTS_TRIE"),
+    (LC_DYLD_CHAINED_FIXUPS, "LC_DYLD_CHAINED_FIXUPS"),
+    (0x80000035, "LC_FILESET_ENTRY"),
+    (LC_ATOM_INFO, "LC_ATOM_INFO"),
+];
+
+const PLATFORM_NAMES: [&str; 13] = [
+    "unknown",
+    "macOS",
+    "iOS",
+    "tvOS",
+    "watchOS",
+    "bridgeOS",
+    "Mac Catalyst",
+    "iOS simulator",
+    "tvOS simulator",
+    "watchOS simulator",
+    "DriverKit",
+    "visionOS",
+    "visionOS simulator",
+];
+
+const TOOL_NAMES: [&str; 5] = ["unknown", "clang", "swift", "ld", "lld"];
+
+const SECTION_TYPE_NAMES: [&str; 23] = [
+    "REGULAR",
+    "ZEROFILL",
+    "CSTRING_LITERALS",
+    "4BYTE_LITERALS",
+    "8BYTE_LITERALS",
+    "LITERAL_POINTERS",
+    "NON_LAZY_SYMBOL_POINTERS",
+    "LAZY_SYMBOL_POINTERS",
+    "SYMBOL_STUBS",
+    "MOD_INIT_FUNC_POINTERS",
+    "MOD_TERM_FUNC_POINTERS",
+    "COALESCED",
+    "GB_ZEROFILL",
+    "INTERPOSING",
+    "16BYTE_LITERALS",
+    "DTRACE_DOF",
+    "LAZY_DYLIB_SYMBOL_POINTERS",
+    "THREAD_LOCAL_REGULAR",
+    "THREAD_LOCAL_ZEROFILL",
+    "THREAD_LOCAL_VARIABLES",
+    "THREAD_LOCAL_VARIABLE_POINTERS",
+    "THREAD_LOCAL_INIT_FUNCTION_POINTERS",
+    "INIT_FUNC_OFFSETS",
+];
+
+const SECTION_ATTRIBUTE_NAMES: [(u32, &str); 10] = [
+    (0x80000000, "PURE_INSTRUCTIONS"),
+    (0x40000000, "NO_TOC"),
+    (0x20000000, "STRIP_STATIC_SYMS"),
+    (0x10000000, "NO_DEAD_STRIP"),
+    (0x08000000, "LIVE_SUPPORT"),
+    (0x04000000, "SELF_MODIFYING_CODE"),
+    (0x02000000, "DEBUG"),
+    (0x00000400, "SOME_INSTRUCTIONS"),
+    (0x00000200, "EXT_RELOC"),
+    (0x00000100, "LOC_RELOC"),
+];
+
+const SEGMENT_FLAG_NAMES: [(u32, &str); 5] = [
+    (0x1, "HIGHVM"),
+    (0x2, "FVMLIB"),
+    (0x4, "NORELOC"),
+    (0x8, "PROTECTED_VERSION_1"),
+    (0x10, "READ_ONLY"),
+];
+
+// Zero fill sections occupy no space in the file
+const S_ZEROFILL: u32 = 0x1;
+const S_GB_ZEROFILL: u32 = 0xc;
+const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+
+fn command_name(cmd: u32) -> String {
+    match COMMAND_NAMES.iter().find(|(value, _)| *value == cmd) {
+        Some((_, name)) => name.to_string(),
+        None => format!("0x{cmd:08x}"),
+    }
+}
+
+fn protection_as_string(protection: u32) -> String {
+    format!(
+        "{}{}{}",
+        if protection & 1 != 0 { 'r' } else { '-' },
+        if protection & 2 != 0 { 'w' } else { '-' },
+        if protection & 4 != 0 { 'x' } else { '-' }
+    )
+}
+
+/// Segment and section names are padded to 16 bytes and not always terminated
+fn fixed_name(blob: &Blob, offset: usize) -> Result<String> {
+    let bytes = blob.get_slice(offset, 16)?;
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    Ok(String::from_utf8_lossy(&bytes[..len]).to_string())
+}
+
+/// Strings of load commands are stored behind the command structure
+fn lc_str(blob: &Blob, command_offset: usize, size: u32) -> Result<String> {
+    let offset = blob.get_u32(command_offset + 8)? as usize;
+    if offset >= size as usize {
+        return Ok(String::new());
+    }
+    Ok(blob
+        .get_cstr(command_offset + offset)?
+        .to_string_lossy()
+        .to_string())
+}
+
+pub(super) struct Section {
+    pub(super) name: String,
+    pub(super) segment_name: String,
+    pub(super) address: u64,
+    pub(super) size: u64,
+    // File offset relative to the start of the image
+    pub(super) offset: u32,
+    align: u32,
+    relocation_offset: u32,
+    number_of_relocations: u32,
+    flags: u32,
+}
+
+impl Section {
+    fn new(blob: &Blob, offset: usize, is_64: bool) -> Result<Self> {
+        let name = fixed_name(blob, offset)?;
+        let segment_name = fixed_name(blob, offset + 16)?;
+        let (address, size, rest) = if is_64 {
+            (
+                blob.get_u64(offset + 32)?,
+                blob.get_u64(offset + 40)?,
+                offset + 48,
+            )
+        } else {
+            (
+                blob.get_u32(offset + 32)? as u64,
+                blob.get_u32(offset + 36)? as u64,
+                offset + 40,
+            )
+        };
+        Ok(Self {
+            name,
+            segment_name,
+            address,
+            size,
+            offset: blob.get_u32(rest)?,
+            align: blob.get_u32(rest + 4)?,
+            relocation_offset: blob.get_u32(rest + 8)?,
+            number_of_relocations: blob.get_u32(rest + 12)?,
+            flags: blob.get_u32(rest + 16)?,
+        })
+    }
+
+    pub(super) fn section_type(&self) -> u32 {
+        self.flags & 0xff
+    }
+
+    /// Zero fill sections have no data in the file
+    pub(super) fn has_data(&self) -> bool {
+        !matches!(
+            self.section_type(),
+            S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL
+        ) && self.offset != 0
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.segment_name.clone(),
+            self.name.clone(),
+            format!("0x{:016x}", self.address),
+            format!("0x{:x}", self.size),
+            format!("0x{:08x}", self.offset),
+            format!("2^{}", self.align),
+            if self.number_of_relocations > 0 {
+                format!(
+                    "{} at 0x{:08x}",
+                    self.number_of_relocations, self.relocation_offset
+                )
+            } else {
+                String::new()
+            },
+            match SECTION_TYPE_NAMES.get(self.section_type() as usize) {
+                Some(name) => name.to_string(),
+                None => format!("0x{:02x}", self.section_type()),
+            },
+            flags_as_string(self.flags & 0xffffff00, &SECTION_ATTRIBUTE_NAMES),
+        ]
+    }
+}
+
+pub(super) struct Segment {
+    pub(super) name: String,
+    pub(super) vm_address: u64,
+    pub(super) vm_size: u64,
+    // File range relative to the start of the image
+    pub(super) file_offset: u64,
+    pub(super) file_size: u64,
+    max_protection: u32,
+    init_protection: u32,
+    flags: u32,
+    pub(super) sections: Vec<Section>,
+}
+
+impl Segment {
+    fn new(blob: &Blob, offset: usize, is_64: bool) -> Result<Self> {
+        let name = fixed_name(blob, offset + 8)?;
+        let (vm_address, vm_size, file_offset, file_size, rest) = if is_64 {
+            (
+                blob.get_u64(offset + 24)?,
+                blob.get_u64(offset + 32)?,
+                blob.get_u64(offset + 40)?,
+                blob.get_u64(offset + 48)?,
+                offset + 56,
+            )
+        } else {
+            (
+                blob.get_u32(offset + 24)? as u64,
+                blob.get_u32(offset + 28)? as u64,
+                blob.get_u32(offset + 32)? as u64,
+                blob.get_u32(offset + 36)? as u64,
+                offset + 40,
+            )
+        };
+        let number_of_sections = blob.get_u32(rest + 8)?;
+        let section_size = if is_64 { 80 } else { 68 };
+        let mut sections = Vec::new();
+        let mut section_offset = rest + 16;
+        for _ in 0..number_of_sections {
+            sections.push(Section::new(blob, section_offset, is_64)?);
+            section_offset += section_size;
+        }
+        Ok(Self {
+            name,
+            vm_address,
+            vm_size,
+            file_offset,
+            file_size,
+            max_protection: blob.get_u32(rest)?,
+            init_protection: blob.get_u32(rest + 4)?,
+            flags: blob.get_u32(rest + 12)?,
+            sections,
+        })
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            format!("0x{:016x}", self.vm_address),
+            format!("0x{:x}", self.vm_size),
+            format!("0x{:08x}", self.file_offset),
+            format!("0x{:x}", self.file_size),
+            protection_as_string(self.max_protection),
+            protection_as_string(self.init_protection),
+            self.sections.len().to_string(),
+            flags_as_string(self.flags, &SEGMENT_FLAG_NAMES),
+        ]
+    }
+}
+
+/// Location of the symbol and string table
+pub(super) struct Symtab {
+    pub(super) symbol_offset: u32,
+    pub(super) number_of_symbols: u32,
+    pub(super) string_offset: u32,
+    pub(super) string_size: u32,
+}
+
+/// Grouping of the symbol table used by the dynamic linker
+pub(super) struct Dysymtab {
+    local_index: u32,
+    local_count: u32,
+    external_index: u32,
+    external_count: u32,
+    undefined_index: u32,
+    undefined_count: u32,
+    indirect_offset: u32,
+    indirect_count: u32,
+}
+
+pub(super) struct Dylib {
+    pub(super) name: String,
+    timestamp: u32,
+    current_version: u32,
+    compatibility_version: u32,
+}
+
+impl Dylib {
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            format_version(self.current_version),
+            format_version(self.compatibility_version),
+            self.timestamp.to_string(),
+        ]
+    }
+}
+
+pub(super) struct BuildVersion {
+    platform: u32,
+    pub(super) min_os: u32,
+    pub(super) sdk: u32,
+    // Tool and its version used to build the image
+    tools: Vec<(u32, u32)>,
+}
+
+impl BuildVersion {
+    pub(super) fn platform_name(&self) -> String {
+        match PLATFORM_NAMES.get(self.platform as usize) {
+            Some(name) => name.to_string(),
+            None => format!("platform {}", self.platform),
+        }
+    }
+
+    pub(super) fn tools_as_string(&self) -> String {
+        self.tools
+            .iter()
+            .map(|(tool, version)| {
+                let name = match TOOL_NAMES.get(*tool as usize) {
+                    Some(name) => name.to_string(),
+                    None => format!("tool {tool}"),
+                };
+                format!("{name} {}", format_version(*version))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub(super) enum Command {
+    Segment(Segment),
+    Symtab(Symtab),
+    Dysymtab(Dysymtab),
+    Dylib(Dylib),
+    // Dynamic linker or run path
+    Path(String),
+    Uuid([u8; 16]),
+    BuildVersion(BuildVersion),
+    // Minimum OS version and SDK of the older LC_VERSION_MIN_* commands
+    VersionMin { version: u32, sdk: u32 },
+    SourceVersion(u64),
+    Main { entry_offset: u64, stack_size: u64 },
+    Encryption { offset: u32, size: u32, id: u32 },
+    // Data in the __LINKEDIT segment like code signature or chained fixups
+    LinkEditData { offset: u32, size: u32 },
+    Other,
+}
+
+pub(super) struct LoadCommand {
+    // Absolute file offset of the command
+    pub(super) offset: usize,
+    pub(super) cmd: u32,
+    pub(super) size: u32,
+    pub(super) command: Command,
+}
+
+impl LoadCommand {
+    fn new(blob: &Blob, offset: usize, is_64: bool) -> Result<Self> {
+        let cmd = blob.get_u32(offset)?;
+        let size = blob.get_u32(offset + 4)?;
+        let command = match cmd {
+            LC_SEGMENT | LC_SEGMENT_64 => Command::Segment(Segment::new(blob, offset, is_64)?),
+            LC_SYMTAB => Command::Symtab(Symtab {
+                symbol_offset: blob.get_u32(offset + 8)?,
+                number_of_symbols: blob.get_u32(offset + 12)?,
+                string_offset: blob.get_u32(offset + 16)?,
+                string_size: blob.get_u32(offset + 20)?,
+            }),
+            LC_DYSYMTAB => Command::Dysymtab(Dysymtab {
+                local_index: blob.get_u32(offset + 8)?,
+                local_count: blob.get_u32(offset + 12)?,
+                external_index: blob.get_u32(offset + 16)?,
+                external_count: blob.get_u32(offset + 20)?,
+                undefined_index: blob.get_u32(offset + 24)?,
+                undefined_count: blob.get_u32(offset + 28)?,
+                indirect_offset: blob.get_u32(offset + 56)?,
+                indirect_count: blob.get_u32(offset + 60)?,
+            }),
+            LC_LOAD_DYLIB | LC_ID_DYLIB | LC_LOAD_WEAK_DYLIB | LC_REEXPORT_DYLIB
+            | LC_LAZY_LOAD_DYLIB | LC_LOAD_UPWARD_DYLIB => Command::Dylib(Dylib {
+                name: lc_str(blob, offset, size)?,
+                timestamp: blob.get_u32(offset + 12)?,
+                current_version: blob.get_u32(offset + 16)?,
+                compatibility_version: blob.get_u32(offset + 20)?,
+            }),
+            LC_LOAD_DYLINKER | LC_ID_DYLINKER | LC_RPATH => {
+                Command::Path(lc_str(blob, offset, size)?)
+            }
+            LC_UUID => Command::Uuid(
+                blob.get_slice(offset + 8, 16)?
+                    .try_into()
+                    .map_err(|_| BlobError::InvalidSliceSize)?,
+            ),
+            LC_BUILD_VERSION => {
+                let number_of_tools = blob.get_u32(offset + 20)?;
+                let mut tools = Vec::new();
+                for idx in 0..number_of_tools as usize {
+                    tools.push((
+                        blob.get_u32(offset + 24 + 8 * idx)?,
+                        blob.get_u32(offset + 28 + 8 * idx)?,
+                    ));
+                }
+                Command::BuildVersion(BuildVersion {
+                    platform: blob.get_u32(offset + 8)?,
+                    min_os: blob.get_u32(offset + 12)?,
+                    sdk: blob.get_u32(offset + 16)?,
+                    tools,
+                })
+            }
+            LC_VERSION_MIN_MACOSX
+            | LC_VERSION_MIN_IPHONEOS
+            | LC_VERSION_MIN_TVOS
+            | LC_VERSION_MIN_WATCHOS => Command::VersionMin {
+                version: blob.get_u32(offset + 8)?,
+                sdk: blob.get_u32(offset + 12)?,
+            },
+            LC_SOURCE_VERSION => Command::SourceVersion(blob.get_u64(offset + 8)?),
+            LC_MAIN => Command::Main {
+                entry_offset: blob.get_u64(offset + 8)?,
+                stack_size: blob.get_u64(offset + 16)?,
+            },
+            LC_ENCRYPTION_INFO | LC_ENCRYPTION_INFO_64 => Command::Encryption {
+                offset: blob.get_u32(offset + 8)?,
+                size: blob.get_u32(offset + 12)?,
+                id: blob.get_u32(offset + 16)?,
+            },
+            LC_CODE_SIGNATURE
+            | LC_SEGMENT_SPLIT_INFO
+            | LC_FUNCTION_STARTS
+            | LC_DATA_IN_CODE
+            | LC_DYLIB_CODE_SIGN_DRS
+            | LC_LINKER_OPTIMIZATION_HINT
+            | LC_DYLD_EXPORTS_TRIE
+            | LC_DYLD_CHAINED_FIXUPS
+            | LC_ATOM_INFO => Command::LinkEditData {
+                offset: blob.get_u32(offset + 8)?,
+                size: blob.get_u32(offset + 12)?,
+            },
+            _ => Command::Other,
+        };
+        Ok(Self {
+            offset,
+            cmd,
+            size,
+            command,
+        })
+    }
+
+    pub(super) fn name(&self) -> String {
+        command_name(self.cmd)
+    }
+
+    pub(super) fn details(&self) -> String {
+        match &self.command {
+            Command::Segment(segment) => format!(
+                "{} vm 0x{:x}-0x{:x}, file 0x{:x}-0x{:x}, {}, {} sections",
+                segment.name,
+                segment.vm_address,
+                segment.vm_address.saturating_add(segment.vm_size),
+                segment.file_offset,
+                segment.file_offset.saturating_add(segment.file_size),
+                protection_as_string(segment.init_protection),
+                segment.sections.len()
+            ),
+            Command::Symtab(symtab) => format!(
+                "{} symbols at 0x{:x}, {} bytes of strings at 0x{:x}",
+                symtab.number_of_symbols,
+                symtab.symbol_offset,
+                symtab.string_size,
+                symtab.string_offset
+            ),
+            Command::Dysymtab(dysymtab) => format!(
+                "local {}+{}, external {}+{}, undefined {}+{}, {} indirect symbols at 0x{:x}",
+                dysymtab.local_index,
+                dysymtab.local_count,
+                dysymtab.external_index,
+                dysymtab.external_count,
+                dysymtab.undefined_index,
+                dysymtab.undefined_count,
+                dysymtab.indirect_count,
+                dysymtab.indirect_offset
+            ),
+            Command::Dylib(dylib) => format!(
+                "{} (current {}, compatibility {})",
+                dylib.name,
+                format_version(dylib.current_version),
+                format_version(dylib.compatibility_version)
+            ),
+            Command::Path(path) => path.clone(),
+            Command::Uuid(uuid) => format_uuid(uuid),
+            Command::BuildVersion(build_version) => format!(
+                "{} {}, SDK {}, {}",
+                build_version.platform_name(),
+                format_version(build_version.min_os),
+                format_version(build_version.sdk),
+                build_version.tools_as_string()
+            ),
+            Command::VersionMin { version, sdk } => {
+                format!("{}, SDK {}", format_version(*version), format_version(*sdk))
+            }
+            Command::SourceVersion(version) => format_source_version(*version),
+            Command::Main {
+                entry_offset,
+                stack_size,
+            } => format!("entry offset 0x{entry_offset:x}, stack size {stack_size}"),
+            Command::Encryption { offset, size, id } => format!(
+                "{} bytes at 0x{:x}, {}",
+                size,
+                offset,
+                if *id == 0 {
+                    "not encrypted"
+                } else {
+                    "encrypted"
+                }
+            ),
+            Command::LinkEditData { offset, size } => {
+                format!("{size} bytes at 0x{offset:x}")
+            }
+            Command::Other => String::new(),
+        }
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            format!("0x{:08x}", self.offset),
+            self.name(),
+            self.size.to_string(),
+            self.details(),
+        ]
+    }
+}
+
+pub(super) fn format_uuid(uuid: &[u8; 16]) -> String {
+    let hex: String = uuid.iter().map(|b| format!("{b:02X}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Source versions are packed as A.B.C.D.E in 24.10.10.10.10 bits
+fn format_source_version(version: u64) -> String {
+    let mut parts = vec![version >> 40];
+    for shift in [30, 20, 10, 0] {
+        parts.push((version >> shift) & 0x3ff);
+    }
+    parts
+        .iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Parse all load commands following the Mach-O header at `offset`
+pub(super) fn parse_load_commands(
+    blob: &Blob,
+    offset: usize,
+    count: u32,
+    is_64: bool,
+) -> Result<Vec<LoadCommand>> {
+    let mut commands = Vec::new();
+    let mut offset = offset;
+    for _ in 0..count {
+        let command = LoadCommand::new(blob, offset, is_64)?;
+        // Commands are at least 8 bytes, stop at a corrupt size instead of looping
+        if command.size < 8 {
+            commands.push(command);
+            break;
+        }
+        offset += command.size as usize;
+        commands.push(command);
+    }
+    Ok(commands)
+}
@@ -0,0 +1,790 @@
+use crate::blob::{BinaryType, Blob, BlobError};
+use crate::table::{Row, RowAction, Table, TableType};
+use thiserror::Error;
+
+type Result<T> = std::result::Result<T, MachOError>;
+
+mod codesign;
+mod fat;
+mod fixups;
+mod load_commands;
+mod symbols;
+#[cfg(test)]
+mod tests;
+
+use codesign::CodeSignature;
+use fat::FatArch;
+use fixups::ChainedFixups;
+use load_commands::{Command, LoadCommand, Section, Segment};
+use symbols::Nlist;
+
+#[derive(Error, Debug, Clone)]
+pub enum MachOError {
+    #[error("no mach-o binary")]
+    NoMachOBinary,
+    #[error("invalid binary")]
+    InternalError,
+    #[error("no such architecture in universal binary")]
+    NoSuchSlice,
+    #[error("binary corrupted")]
+    BlobCorrupted(#[from] BlobError),
+}
+
+// Undefined symbols are looked up in the library given by their ordinal
+const MH_TWOLEVEL: u32 = 0x80;
+
+const FILE_TYPE_NAMES: [&str; 13] = [
+    "unknown",
+    "Object file",
+    "Executable",
+    "Fixed VM shared library",
+    "Core dump",
+    "Preloaded executable",
+    "Dynamic library",
+    "Dynamic linker",
+    "Bundle",
+    "Dynamic library stub",
+    "Debug symbols",
+    "Kernel extension",
+    "File set",
+];
+
+const HEADER_FLAG_NAMES: [(u32, &str); 29] = [
+    (0x1, "NOUNDEFS"),
+    (0x2, "INCRLINK"),
+    (0x4, "DYLDLINK"),
+    (0x8, "BINDATLOAD"),
+    (0x10, "PREBOUND"),
+    (0x20, "SPLIT_SEGS"),
+    (0x40, "LAZY_INIT"),
+    (MH_TWOLEVEL, "TWOLEVEL"),
+    (0x100, "FORCE_FLAT"),
+    (0x200, "NOMULTIDEFS"),
+    (0x400, "NOFIXPREBINDING"),
+    (0x800, "PREBINDABLE"),
+    (0x1000, "ALLMODSBOUND"),
+    (0x2000, "SUBSECTIONS_VIA_SYMBOLS"),
+    (0x4000, "CANONICAL"),
+    (0x8000, "WEAK_DEFINES"),
+    (0x10000, "BINDS_TO_WEAK"),
+    (0x20000, "ALLOW_STACK_EXECUTION"),
+    (0x40000, "ROOT_SAFE"),
+    (0x80000, "SETUID_SAFE"),
+    (0x100000, "NO_REEXPORTED_DYLIBS"),
+    (0x200000, "PIE"),
+    (0x400000, "DEAD_STRIPPABLE_DYLIB"),
+    (0x800000, "HAS_TLV_DESCRIPTORS"),
+    (0x1000000, "NO_HEAP_EXECUTION"),
+    (0x2000000, "APP_EXTENSION_SAFE"),
+    (0x4000000, "NLIST_OUTOFSYNC_WITH_DYLDINFO"),
+    (0x8000000, "SIM_SUPPORT"),
+    (0x80000000, "DYLIB_IN_CACHE"),
+];
+
+// CPU types with the 64 bit ABI flag
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_X86_64: u32 = 0x01000007;
+const CPU_TYPE_ARM: u32 = 12;
+const CPU_TYPE_ARM64: u32 = 0x0100000c;
+const CPU_TYPE_ARM64_32: u32 = 0x0200000c;
+const CPU_TYPE_POWERPC: u32 = 18;
+const CPU_TYPE_POWERPC64: u32 = 0x01000012;
+
+// The upper bits of the subtype hold capabilities like pointer authentication
+const CPU_SUBTYPE_MASK: u32 = 0x00ffffff;
+
+/// Name of the architecture given by CPU type and subtype
+pub(super) fn cpu_name(cputype: u32, cpusubtype: u32) -> String {
+    let subtype = cpusubtype & CPU_SUBTYPE_MASK;
+    match (cputype, subtype) {
+        (CPU_TYPE_X86, _) => "x86".to_string(),
+        (CPU_TYPE_X86_64, 8) => "x86-64h".to_string(),
+        (CPU_TYPE_X86_64, _) => "x86-64".to_string(),
+        (CPU_TYPE_ARM, 5) => "ARMv4T".to_string(),
+        (CPU_TYPE_ARM, 6) => "ARMv6".to_string(),
+        (CPU_TYPE_ARM, 9) => "ARMv7".to_string(),
+        (CPU_TYPE_ARM, 11) => "ARMv7s".to_string(),
+        (CPU_TYPE_ARM, 12) => "ARMv7k".to_string(),
+        (CPU_TYPE_ARM, 14) => "ARMv6-M".to_string(),
+        (CPU_TYPE_ARM, 15) => "ARMv7-M".to_string(),
+        (CPU_TYPE_ARM, 16) => "ARMv7E-M".to_string(),
+        (CPU_TYPE_ARM, _) => "ARM".to_string(),
+        (CPU_TYPE_ARM64, 2) => "ARM64e".to_string(),
+        (CPU_TYPE_ARM64, _) => "ARM64".to_string(),
+        (CPU_TYPE_ARM64_32, _) => "ARM64_32".to_string(),
+        (CPU_TYPE_POWERPC, _) => "PowerPC".to_string(),
+        (CPU_TYPE_POWERPC64, _) => "PowerPC 64".to_string(),
+        _ => format!("CPU type 0x{cputype:x}"),
+    }
+}
+
+/// Dylib and OS versions are packed as X.Y.Z in 16.8.8 bits
+pub(super) fn format_version(version: u32) -> String {
+    format!(
+        "{}.{}.{}",
+        version >> 16,
+        (version >> 8) & 0xff,
+        version & 0xff
+    )
+}
+
+fn flags_as_string(value: u32, names: &[(u32, &str)]) -> String {
+    let mut flags = Vec::new();
+    let mut rest = value;
+    for (flag, name) in names {
+        if value & flag != 0 {
+            flags.push(name.to_string());
+            rest &= !flag;
+        }
+    }
+    if rest != 0 {
+        flags.push(format!("0x{rest:x}"));
+    }
+    flags.join(" ")
+}
+
+/// Fat headers and code signatures are big endian regardless of the image
+fn get_be_u32(blob: &Blob, offset: usize) -> Result<u32> {
+    Ok(u32::from_be_bytes(
+        blob.get_slice(offset, 4)?
+            .try_into()
+            .map_err(|_| BlobError::InvalidSliceSize)?,
+    ))
+}
+
+fn get_be_u64(blob: &Blob, offset: usize) -> Result<u64> {
+    Ok(u64::from_be_bytes(
+        blob.get_slice(offset, 8)?
+            .try_into()
+            .map_err(|_| BlobError::InvalidSliceSize)?,
+    ))
+}
+
+/// The header at the start of each Mach-O image
+pub struct MachHeader {
+    is_64: bool,
+    lsb: bool,
+    cputype: u32,
+    cpusubtype: u32,
+    filetype: u32,
+    number_of_commands: u32,
+    size_of_commands: u32,
+    flags: u32,
+}
+
+impl MachHeader {
+    /// Parse the header at `offset` and switch `blob` to the byte order of the image
+    pub fn new(blob: &mut Blob, offset: usize) -> Result<Self> {
+        let (lsb, is_64) = match blob.get_slice(offset, 4)? {
+            [0xce, 0xfa, 0xed, 0xfe] => (true, false),
+            [0xcf, 0xfa, 0xed, 0xfe] => (true, true),
+            [0xfe, 0xed, 0xfa, 0xce] => (false, false),
+            [0xfe, 0xed, 0xfa, 0xcf] => (false, true),
+            _ => return Err(MachOError::NoMachOBinary),
+        };
+        blob.lsb = lsb;
+        Ok(Self {
+            is_64,
+            lsb,
+            cputype: blob.get_u32(offset + 4)?,
+            cpusubtype: blob.get_u32(offset + 8)?,
+            filetype: blob.get_u32(offset + 12)?,
+            number_of_commands: blob.get_u32(offset + 16)?,
+            size_of_commands: blob.get_u32(offset + 20)?,
+            flags: blob.get_u32(offset + 24)?,
+        })
+    }
+
+    pub fn is_64(&self) -> bool {
+        self.is_64
+    }
+
+    fn size(&self) -> usize {
+        if self.is_64 {
+            32
+        } else {
+            28
+        }
+    }
+
+    pub fn architecture(&self) -> String {
+        cpu_name(self.cputype, self.cpusubtype)
+    }
+
+    pub fn info(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "Architecture".to_string(),
+                format!(
+                    "{} (0x{:x}, subtype 0x{:x})",
+                    self.architecture(),
+                    self.cputype,
+                    self.cpusubtype
+                ),
+            ),
+            (
+                "File type".to_string(),
+                match FILE_TYPE_NAMES.get(self.filetype as usize) {
+                    Some(name) => name.to_string(),
+                    None => format!("0x{:x}", self.filetype),
+                },
+            ),
+            (
+                "Load commands".to_string(),
+                format!(
+                    "{}, {} bytes",
+                    self.number_of_commands, self.size_of_commands
+                ),
+            ),
+            (
+                "Flags".to_string(),
+                flags_as_string(self.flags, &HEADER_FLAG_NAMES),
+            ),
+        ]
+    }
+}
+
+pub struct MachOBinary {
+    blob: Blob,
+    // Architectures of a universal binary, empty for a single image
+    slices: Vec<FatArch>,
+    slice: usize,
+    // File data up to the end of the selected slice of a universal binary, so reads of the
+    // image stop at its end
+    image: Option<Blob>,
+    // File offset of the selected image, all offsets of the image are relative to it
+    base: usize,
+    header: MachHeader,
+    load_commands: Vec<LoadCommand>,
+    symbols: Vec<Nlist>,
+    chained_fixups: Option<ChainedFixups>,
+}
+
+/// Header of a slice of a universal binary and the file data up to the end of the slice
+fn load_slice(blob: &Blob, slice: &FatArch) -> Result<(Blob, MachHeader)> {
+    let end = slice.offset.saturating_add(slice.size);
+    let mut image = blob.truncated(usize::try_from(end).unwrap_or(usize::MAX));
+    let header = MachHeader::new(&mut image, slice.offset as usize)?;
+    Ok((image, header))
+}
+
+impl MachOBinary {
+    pub fn new(blob: Blob) -> Result<Self> {
+        if !matches!(blob.bin_type, BinaryType::MachO) {
+            return Err(MachOError::NoMachOBinary);
+        }
+        let mut blob = blob;
+        let mut slices = Vec::new();
+        let (slice, image, header) = if fat::is_fat(&blob)? {
+            slices = fat::parse_fat_header(&blob)?;
+            // Start with the first slice holding a valid image, broken slices only fail once
+            // they are selected
+            let mut selected = Err(MachOError::NoSuchSlice);
+            for (idx, slice) in slices.iter().enumerate() {
+                match load_slice(&blob, slice) {
+                    Ok((image, header)) => {
+                        selected = Ok((idx, Some(image), header));
+                        break;
+                    }
+                    Err(e) if idx == 0 => selected = Err(e),
+                    Err(_) => {}
+                }
+            }
+            selected?
+        } else {
+            (0, None, MachHeader::new(&mut blob, 0)?)
+        };
+        let base = slices.get(slice).map_or(0, |slice| slice.offset as usize);
+        Ok(Self {
+            blob,
+            slices,
+            slice,
+            image,
+            base,
+            header,
+            load_commands: Vec::new(),
+            symbols: Vec::new(),
+            chained_fixups: None,
+        })
+    }
+
+    /// The selected image of a universal binary, the whole file otherwise
+    fn image(&self) -> &Blob {
+        self.image.as_ref().unwrap_or(&self.blob)
+    }
+
+    pub fn ident(&self) -> String {
+        format!(
+            "Mach-O {} bit {} endian",
+            if self.header.is_64 { 64 } else { 32 },
+            if self.header.lsb { "little" } else { "big" }
+        )
+    }
+
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    /// Architectures of a universal binary, empty if the file contains a single image
+    pub fn slices(&self) -> Vec<String> {
+        self.slices.iter().map(|slice| slice.name()).collect()
+    }
+
+    pub fn selected_slice(&self) -> usize {
+        self.slice
+    }
+
+    /// Analyze the image of architecture `idx` of a universal binary
+    pub fn select_slice(&mut self, idx: usize) -> Result<()> {
+        if self.slices.is_empty() && idx == 0 {
+            return Ok(());
+        }
+        let slice = self.slices.get(idx).ok_or(MachOError::NoSuchSlice)?;
+        let (image, header) = load_slice(&self.blob, slice)?;
+        self.base = slice.offset as usize;
+        self.image = Some(image);
+        self.header = header;
+        self.slice = idx;
+        self.load_commands.clear();
+        self.symbols.clear();
+        self.chained_fixups = None;
+        Ok(())
+    }
+
+    pub fn header_info(&mut self) -> Vec<(String, String)> {
+        let mut info = vec![("Ident".to_string(), self.ident())];
+        if let Some(slice) = self.slices.get(self.slice) {
+            info.push(("Universal binary".to_string(), self.slices().join(", ")));
+            info.push((
+                "Selected architecture".to_string(),
+                format!(
+                    "{}, {} bytes at offset 0x{:x}",
+                    slice.name(),
+                    slice.size,
+                    slice.offset
+                ),
+            ));
+        }
+        info.extend(self.header.info());
+        if self.get_load_commands().is_ok() {
+            let image_base = self.image_base();
+            for command in &self.load_commands {
+                match &command.command {
+                    Command::Uuid(_) => info.push(("UUID".to_string(), command.details())),
+                    Command::BuildVersion(build_version) => {
+                        info.push((
+                            "Platform".to_string(),
+                            format!(
+                                "{} {}",
+                                build_version.platform_name(),
+                                format_version(build_version.min_os)
+                            ),
+                        ));
+                        info.push(("SDK".to_string(), format_version(build_version.sdk)));
+                        info.push(("Build tools".to_string(), build_version.tools_as_string()));
+                    }
+                    Command::VersionMin { version, sdk } => {
+                        let platform = match command.cmd {
+                            load_commands::LC_VERSION_MIN_IPHONEOS => "iOS",
+                            load_commands::LC_VERSION_MIN_TVOS => "tvOS",
+                            load_commands::LC_VERSION_MIN_WATCHOS => "watchOS",
+                            _ => "macOS",
+                        };
+                        info.push((
+                            "Platform".to_string(),
+                            format!("{platform} {}", format_version(*version)),
+                        ));
+                        info.push(("SDK".to_string(), format_version(*sdk)));
+                    }
+                    Command::Main { entry_offset, .. } => info.push((
+                        "Entry point".to_string(),
+                        format!(
+                            "0x{:x} (file offset 0x{entry_offset:x})",
+                            image_base.wrapping_add(*entry_offset)
+                        ),
+                    )),
+                    Command::Dylib(_) if command.cmd == load_commands::LC_ID_DYLIB => {
+                        info.push(("Install name".to_string(), command.details()))
+                    }
+                    Command::Path(path) if command.cmd == load_commands::LC_LOAD_DYLINKER => {
+                        info.push(("Dynamic linker".to_string(), path.clone()))
+                    }
+                    Command::Path(path) if command.cmd == load_commands::LC_RPATH => {
+                        info.push(("Run path".to_string(), path.clone()))
+                    }
+                    Command::SourceVersion(_) => {
+                        info.push(("Source version".to_string(), command.details()))
+                    }
+                    Command::Encryption { .. } => {
+                        info.push(("Encryption".to_string(), command.details()))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        match self.code_signature() {
+            Ok(Some(signature)) => info.push(("Code signature".to_string(), signature.summary())),
+            Ok(None) => {}
+            Err(e) => info.push(("Code signature".to_string(), e.to_string())),
+        }
+        info
+    }
+
+    pub fn load_commands_table(&mut self) -> Result<Table> {
+        self.get_load_commands()?;
+        let headers = ["Nr.", "Offset", "Command", "Size", "Details"];
+        let mut rows = Vec::with_capacity(self.load_commands.len());
+        for (idx, command) in self.load_commands.iter().enumerate() {
+            let mut v = command.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::View {
+                    offset: command.offset,
+                    size: command.size as usize,
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachOLoadCommands, &headers, rows))
+    }
+
+    pub fn segments_table(&mut self) -> Result<Table> {
+        self.get_load_commands()?;
+        let headers = [
+            "Nr.",
+            "Name",
+            "VM Address",
+            "VM Size",
+            "File Offset",
+            "File Size",
+            "Max Prot",
+            "Init Prot",
+            "Sections",
+            "Flags",
+        ];
+        let segments = self.segments();
+        let mut rows = Vec::with_capacity(segments.len());
+        for (idx, segment) in segments.iter().enumerate() {
+            let mut v = segment.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: if segment.file_size > 0 {
+                    RowAction::View {
+                        offset: self.base + segment.file_offset as usize,
+                        size: segment.file_size as usize,
+                    }
+                } else {
+                    RowAction::None
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachOSegments, &headers, rows))
+    }
+
+    pub fn sections_table(&mut self) -> Result<Table> {
+        self.get_load_commands()?;
+        let headers = [
+            "Nr.",
+            "Segment",
+            "Section",
+            "Address",
+            "Size",
+            "Offset",
+            "Align",
+            "Relocations",
+            "Type",
+            "Attributes",
+        ];
+        let sections = self.sections();
+        let mut rows = Vec::with_capacity(sections.len());
+        // Sections are numbered from 1 as in the n_sect field of symbols
+        for (idx, section) in sections.iter().enumerate() {
+            let mut v = section.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push((idx + 1).to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: if section.has_data() {
+                    RowAction::View {
+                        offset: self.base + section.offset as usize,
+                        size: section.size as usize,
+                    }
+                } else {
+                    RowAction::None
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachOSections, &headers, rows))
+    }
+
+    pub fn symbols_table(&mut self, demangle: bool) -> Result<Table> {
+        self.get_symbols()?;
+        let headers = [
+            "Nr.",
+            "Type",
+            "Scope",
+            "Section",
+            "Description",
+            "Value",
+            "Library",
+            "Name",
+        ];
+        let section_names: Vec<String> = self
+            .sections()
+            .iter()
+            .map(|section| format!("{},{}", section.segment_name, section.name))
+            .collect();
+        let libraries = self.libraries();
+        let two_level = self.header.flags & MH_TWOLEVEL != 0;
+        let mut rows = Vec::with_capacity(self.symbols.len());
+        for (idx, symbol) in self.symbols.iter().enumerate() {
+            let mut v = symbol.to_vec(
+                self.image(),
+                demangle,
+                &section_names,
+                two_level.then_some(libraries.as_slice()),
+            )?;
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::MachOSymbols, &headers, rows))
+    }
+
+    /// Libraries the image depends on and its own install name
+    pub fn dylibs_table(&mut self) -> Result<Table> {
+        self.get_load_commands()?;
+        let headers = [
+            "Nr.",
+            "Command",
+            "Name",
+            "Current Version",
+            "Compatibility Version",
+            "Time Stamp",
+        ];
+        let mut rows = Vec::new();
+        for command in &self.load_commands {
+            let Command::Dylib(dylib) = &command.command else {
+                continue;
+            };
+            let mut v = dylib.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 2);
+            content.push(rows.len().to_string());
+            content.push(command.name());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::View {
+                    offset: command.offset,
+                    size: command.size as usize,
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachODylibs, &headers, rows))
+    }
+
+    /// Rebases and binds of the dyld chained fixups
+    pub fn fixups_table(&mut self) -> Result<Table> {
+        self.get_chained_fixups()?;
+        let headers = [
+            "Nr.",
+            "Segment",
+            "Address",
+            "Kind",
+            "Target",
+            "Library",
+            "Addend",
+            "Authentication",
+        ];
+        let Some(chained_fixups) = &self.chained_fixups else {
+            return Ok(Table::new(TableType::MachOFixups, &headers, Vec::new()));
+        };
+        let libraries = self.libraries();
+        let pointer_size = if self.header.is_64 { 8 } else { 4 };
+        let mut rows = Vec::with_capacity(chained_fixups.fixups.len());
+        for (idx, fixup) in chained_fixups.fixups.iter().enumerate() {
+            let mut v = chained_fixups.fixup_to_vec(fixup, &libraries);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::View {
+                    offset: self.base + fixup.offset as usize,
+                    size: pointer_size,
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachOFixups, &headers, rows))
+    }
+
+    /// Architecture slices of a universal binary
+    pub fn architectures_table(&self) -> Result<Table> {
+        let headers = [
+            "Nr.",
+            "Architecture",
+            "Subtype",
+            "Offset",
+            "Size",
+            "Align",
+            "Selected",
+        ];
+        let mut rows = Vec::with_capacity(self.slices.len());
+        for (idx, slice) in self.slices.iter().enumerate() {
+            let mut v = slice.to_vec(idx == self.slice);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(MachOError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::View {
+                    offset: slice.offset as usize,
+                    size: slice.size as usize,
+                },
+            });
+        }
+        Ok(Table::new(TableType::MachOArchitectures, &headers, rows))
+    }
+
+    fn get_load_commands(&mut self) -> Result<()> {
+        if self.load_commands.is_empty() {
+            self.load_commands = load_commands::parse_load_commands(
+                self.image(),
+                self.base + self.header.size(),
+                self.header.number_of_commands,
+                self.header.is_64,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn get_symbols(&mut self) -> Result<()> {
+        self.get_load_commands()?;
+        if self.symbols.is_empty() {
+            let symtab = self
+                .load_commands
+                .iter()
+                .find_map(|command| match &command.command {
+                    Command::Symtab(symtab) => Some(symtab),
+                    _ => None,
+                });
+            if let Some(symtab) = symtab {
+                self.symbols = symbols::parse_symbols(
+                    self.image(),
+                    self.base + symtab.symbol_offset as usize,
+                    symtab.number_of_symbols,
+                    self.base + symtab.string_offset as usize,
+                    self.header.is_64,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_chained_fixups(&mut self) -> Result<()> {
+        self.get_load_commands()?;
+        if self.chained_fixups.is_none() {
+            if let Some((offset, _)) = self.link_edit_data(load_commands::LC_DYLD_CHAINED_FIXUPS) {
+                self.chained_fixups = Some(fixups::parse_chained_fixups(
+                    self.image(),
+                    self.base,
+                    self.base + offset as usize,
+                    &self.segments(),
+                    self.image_base(),
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn code_signature(&mut self) -> Result<Option<CodeSignature>> {
+        self.get_load_commands()?;
+        match self.link_edit_data(load_commands::LC_CODE_SIGNATURE) {
+            Some((offset, _)) => {
+                codesign::parse_code_signature(self.image(), self.base + offset as usize)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Location of the __LINKEDIT data referenced by the first command `cmd`
+    fn link_edit_data(&self, cmd: u32) -> Option<(u32, u32)> {
+        self.load_commands
+            .iter()
+            .find_map(|command| match &command.command {
+                Command::LinkEditData { offset, size } if command.cmd == cmd => {
+                    Some((*offset, *size))
+                }
+                _ => None,
+            })
+    }
+
+    fn segments(&self) -> Vec<&Segment> {
+        self.load_commands
+            .iter()
+            .filter_map(|command| match &command.command {
+                Command::Segment(segment) => Some(segment),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn sections(&self) -> Vec<&Section> {
+        self.segments()
+            .into_iter()
+            .flat_map(|segment| segment.sections.iter())
+            .collect()
+    }
+
+    /// Names of the dependent libraries in the order of their library ordinals
+    fn libraries(&self) -> Vec<String> {
+        self.load_commands
+            .iter()
+            .filter_map(|command| match &command.command {
+                Command::Dylib(dylib) if command.cmd != load_commands::LC_ID_DYLIB => {
+                    Some(dylib.name.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Preferred load address, the address of the segment mapping the header
+    fn image_base(&self) -> u64 {
+        self.segments()
+            .iter()
+            .find(|segment| segment.file_offset == 0 && segment.file_size > 0)
+            .map_or(0, |segment| segment.vm_address)
+    }
+}
@@ -0,0 +1,224 @@
+use super::Result;
+use crate::blob::Blob;
+
+// Masks of the n_type field
+const N_STAB: u8 = 0xe0;
+const N_PEXT: u8 = 0x10;
+const N_TYPE: u8 = 0x0e;
+const N_EXT: u8 = 0x01;
+
+const N_UNDF: u8 = 0x0;
+const N_ABS: u8 = 0x2;
+const N_SECT: u8 = 0xe;
+const N_PBUD: u8 = 0xc;
+const N_INDR: u8 = 0xa;
+
+// Flags of the n_desc field
+const N_WEAK_REF: u16 = 0x40;
+const N_WEAK_DEF: u16 = 0x80;
+
+// Special library ordinals of two level namespace symbols
+const SELF_LIBRARY_ORDINAL: u8 = 0x0;
+const DYNAMIC_LOOKUP_ORDINAL: u8 = 0xfe;
+const EXECUTABLE_ORDINAL: u8 = 0xff;
+
+const STAB_NAMES: [(u8, &str); 30] = [
+    (0x20, "GSYM"),
+    (0x22, "FNAME"),
+    (0x24, "FUN"),
+    (0x26, "STSYM"),
+    (0x28, "LCSYM"),
+    (0x2e, "BNSYM"),
+    (0x32, "AST"),
+    (0x3c, "OPT"),
+    (0x40, "RSYM"),
+    (0x44, "SLINE"),
+    (0x4e, "ENSYM"),
+    (0x60, "SSYM"),
+    (0x64, "SO"),
+    (0x66, "OSO"),
+    (0x80, "LSYM"),
+    (0x82, "BINCL"),
+    (0x84, "SOL"),
+    (0x86, "PARAMS"),
+    (0x88, "VERSION"),
+    (0x8a, "OLEVEL"),
+    (0xa0, "PSYM"),
+    (0xa2, "EINCL"),
+    (0xa4, "ENTRY"),
+    (0xc0, "LBRAC"),
+    (0xc2, "EXCL"),
+    (0xe0, "RBRAC"),
+    (0xe2, "BCOMM"),
+    (0xe4, "ECOMM"),
+    (0xe8, "ECOML"),
+    (0xfe, "LENG"),
+];
+
+/// An nlist entry of the symbol table
+pub(super) struct Nlist {
+    // Absolute offset of the symbol name in the binary
+    name: Option<usize>,
+    symbol_type: u8,
+    // Section number starting at 1, 0 if the symbol is in no section
+    section: u8,
+    description: u16,
+    value: u64,
+}
+
+impl Nlist {
+    pub(super) fn new(
+        blob: &Blob,
+        offset: usize,
+        string_table_offset: usize,
+        is_64: bool,
+    ) -> Result<Self> {
+        let name_index = blob.get_u32(offset)? as usize;
+        Ok(Self {
+            name: if name_index == 0 {
+                None
+            } else {
+                Some(string_table_offset + name_index)
+            },
+            symbol_type: blob.get_u8(offset + 4)?,
+            section: blob.get_u8(offset + 5)?,
+            description: blob.get_u16(offset + 6)?,
+            value: if is_64 {
+                blob.get_u64(offset + 8)?
+            } else {
+                blob.get_u32(offset + 8)? as u64
+            },
+        })
+    }
+
+    pub(super) fn is_debug(&self) -> bool {
+        self.symbol_type & N_STAB != 0
+    }
+
+    pub(super) fn is_undefined(&self) -> bool {
+        !self.is_debug() && self.symbol_type & N_TYPE == N_UNDF
+    }
+
+    pub(super) fn name(&self, blob: &Blob) -> Result<String> {
+        Ok(blob.get_cname(self.name)?)
+    }
+
+    fn type_as_string(&self) -> String {
+        if self.is_debug() {
+            return match STAB_NAMES
+                .iter()
+                .find(|(value, _)| *value == self.symbol_type)
+            {
+                Some((_, name)) => format!("STAB {name}"),
+                None => format!("STAB 0x{:02x}", self.symbol_type),
+            };
+        }
+        let mut kind = match self.symbol_type & N_TYPE {
+            N_UNDF if self.value != 0 => "COMMON".to_string(),
+            N_UNDF => "UNDEF".to_string(),
+            N_ABS => "ABS".to_string(),
+            N_SECT => "SECT".to_string(),
+            N_PBUD => "PBUD".to_string(),
+            N_INDR => "INDR".to_string(),
+            other => format!("0x{other:x}"),
+        };
+        if self.description & N_WEAK_REF != 0 && self.is_undefined() {
+            kind.push_str(" WEAK_REF");
+        }
+        if self.description & N_WEAK_DEF != 0 && !self.is_undefined() {
+            kind.push_str(" WEAK_DEF");
+        }
+        kind
+    }
+
+    fn scope_as_string(&self) -> String {
+        if self.is_debug() {
+            String::new()
+        } else if self.symbol_type & N_EXT != 0 {
+            "external".to_string()
+        } else if self.symbol_type & N_PEXT != 0 {
+            "private external".to_string()
+        } else {
+            "local".to_string()
+        }
+    }
+
+    /// Library an undefined symbol is bound to in a two level namespace image
+    pub(super) fn library_ordinal(&self) -> Option<u8> {
+        if self.is_undefined() && self.symbol_type & N_EXT != 0 {
+            Some((self.description >> 8) as u8)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn to_vec(
+        &self,
+        blob: &Blob,
+        demangle: bool,
+        section_names: &[String],
+        // Dependent libraries if the image uses a two level namespace
+        libraries: Option<&[String]>,
+    ) -> Result<Vec<String>> {
+        let name = self.name(blob)?;
+        Ok(vec![
+            self.type_as_string(),
+            self.scope_as_string(),
+            match self.section {
+                0 => String::new(),
+                section => match section_names.get(section as usize - 1) {
+                    Some(name) if !self.is_debug() => name.clone(),
+                    _ => section.to_string(),
+                },
+            },
+            format!("0x{:04x}", self.description),
+            format!("0x{:016x}", self.value),
+            match (self.library_ordinal(), libraries) {
+                (Some(ordinal), Some(libraries)) => library_name(ordinal as i32, libraries),
+                _ => String::new(),
+            },
+            if demangle {
+                crate::demangle::demangle(&name)
+            } else {
+                name
+            },
+        ])
+    }
+}
+
+/// Name of the library a bound symbol is looked up in, ordinals count from 1
+pub(super) fn library_name(ordinal: i32, libraries: &[String]) -> String {
+    match ordinal {
+        ordinal if ordinal == SELF_LIBRARY_ORDINAL as i32 => "self".to_string(),
+        -1 => "main executable".to_string(),
+        -2 => "flat lookup".to_string(),
+        -3 => "weak lookup".to_string(),
+        ordinal if ordinal == DYNAMIC_LOOKUP_ORDINAL as i32 => "dynamic lookup".to_string(),
+        ordinal if ordinal == EXECUTABLE_ORDINAL as i32 => "main executable".to_string(),
+        ordinal => match libraries.get(ordinal as usize - 1) {
+            Some(library) => library.clone(),
+            None => format!("library #{ordinal}"),
+        },
+    }
+}
+
+/// Parse `count` nlist entries starting at `offset`
+pub(super) fn parse_symbols(
+    blob: &Blob,
+    offset: usize,
+    count: u32,
+    string_table_offset: usize,
+    is_64: bool,
+) -> Result<Vec<Nlist>> {
+    let entry_size = if is_64 { 16 } else { 12 };
+    let mut symbols = Vec::new();
+    for idx in 0..count as usize {
+        symbols.push(Nlist::new(
+            blob,
+            offset + idx * entry_size,
+            string_table_offset,
+            is_64,
+        )?);
+    }
+    Ok(symbols)
+}
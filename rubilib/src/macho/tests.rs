@@ -0,0 +1,218 @@
+use super::*;
+
+// Data of the load commands starts at this file offset
+const DATA_OFFSET: usize = 0x100;
+const IMAGE_BASE: u64 = 0x100000000;
+
+/// Little endian 64 bit arm64 executable with the given load commands, `data` is placed at
+/// DATA_OFFSET
+fn build_macho(commands: &[Vec<u8>], number_of_commands: u32, data: &[u8]) -> MachOBinary {
+    let file = build_macho_data(commands, number_of_commands, data);
+    MachOBinary::new(Blob::new(file).unwrap()).unwrap()
+}
+
+fn build_macho_data(commands: &[Vec<u8>], number_of_commands: u32, data: &[u8]) -> Vec<u8> {
+    let mut file = Vec::new();
+    for value in [0xfeedfacf, 0x0100000c, 0, 2, number_of_commands] {
+        file.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    let size_of_commands: usize = commands.iter().map(|command| command.len()).sum();
+    file.extend_from_slice(&(size_of_commands as u32).to_le_bytes());
+    file.extend_from_slice(&[0; 8]);
+    for command in commands {
+        file.extend_from_slice(command);
+    }
+    if !data.is_empty() {
+        assert!(file.len() <= DATA_OFFSET);
+        file.resize(DATA_OFFSET, 0);
+        file.extend_from_slice(data);
+    }
+    file
+}
+
+fn command(cmd: u32, fields: &[u32]) -> Vec<u8> {
+    let mut command = cmd.to_le_bytes().to_vec();
+    command.extend_from_slice(&(8 + 4 * fields.len() as u32).to_le_bytes());
+    for field in fields {
+        command.extend_from_slice(&field.to_le_bytes());
+    }
+    command
+}
+
+fn segment(
+    name: &str,
+    vm_address: u64,
+    file_offset: u64,
+    file_size: u64,
+    sections: u32,
+) -> Vec<u8> {
+    let mut command = load_commands::LC_SEGMENT_64.to_le_bytes().to_vec();
+    command.extend_from_slice(&72u32.to_le_bytes());
+    let mut segment_name = [0u8; 16];
+    segment_name[..name.len()].copy_from_slice(name.as_bytes());
+    command.extend_from_slice(&segment_name);
+    for value in [vm_address, 0x4000, file_offset, file_size] {
+        command.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [7, 3, sections, 0] {
+        command.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    command
+}
+
+/// Chained fixups of one page of __DATA with the given page starts and one import of
+/// `_printf` from the first library
+fn chained_fixups(imports_count: u32, page_starts: &[u16]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in [0, 0x20, 0x50, 0x58, imports_count, 1, 0, 0] {
+        data.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    // Start of __TEXT has no fixups, __DATA starts at offset 0xc
+    for value in [2, 0, 0xc] {
+        data.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    data.extend_from_slice(&(22 + 2 * page_starts.len() as u32).to_le_bytes());
+    data.extend_from_slice(&0x4000u16.to_le_bytes());
+    data.extend_from_slice(&6u16.to_le_bytes());
+    data.extend_from_slice(&0x4000u64.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&(page_starts.len() as u16).to_le_bytes());
+    for start in page_starts {
+        data.extend_from_slice(&start.to_le_bytes());
+    }
+    data.resize(0x50, 0);
+    data.extend_from_slice(&(1u32 | 1 << 9).to_le_bytes());
+    data.resize(0x58, 0);
+    data.extend_from_slice(b"\0_printf\0");
+    data
+}
+
+fn fixups_binary(data_offset: u64, imports_count: u32, page_starts: &[u16]) -> MachOBinary {
+    let fixups = chained_fixups(imports_count, page_starts);
+    let mut data = fixups.clone();
+    data.resize(0x100, 0);
+    // A rebase to IMAGE_BASE + 0x3f00 pointing 8 bytes ahead, then a bind of import 0
+    data.extend_from_slice(&(0x3f00u64 | 2 << 51).to_le_bytes());
+    data.extend_from_slice(&(1u64 << 63).to_le_bytes());
+    build_macho(
+        &[
+            segment("__TEXT", IMAGE_BASE, 0, 0x100, 0),
+            segment("__DATA", IMAGE_BASE + 0x4000, data_offset, 0x10, 0),
+            command(
+                load_commands::LC_DYLD_CHAINED_FIXUPS,
+                &[DATA_OFFSET as u32, fixups.len() as u32],
+            ),
+        ],
+        3,
+        &data,
+    )
+}
+
+#[test]
+fn chained_fixups_are_followed() {
+    let mut macho = fixups_binary(0x200, 1, &[0]);
+    let table = macho.fixups_table().unwrap();
+    let rows: Vec<_> = table.rows.iter().map(|row| &row.content[1..7]).collect();
+    assert_eq!(
+        rows,
+        [
+            [
+                "__DATA",
+                "0x0000000100004000",
+                "rebase",
+                "0x0000000100003f00",
+                "",
+                ""
+            ],
+            [
+                "__DATA",
+                "0x0000000100004008",
+                "bind",
+                "_printf",
+                "library #1",
+                "0"
+            ]
+        ]
+    );
+}
+
+#[test]
+fn chained_fixups_page_beyond_address_space_is_rejected() {
+    let mut macho = fixups_binary(u64::MAX - 0x1000, 1, &[0xffff, 0]);
+    assert!(macho.fixups_table().is_err());
+}
+
+#[test]
+fn chained_fixups_import_count_larger_than_file_is_rejected() {
+    let mut macho = fixups_binary(0x200, u32::MAX, &[0]);
+    assert!(macho.fixups_table().is_err());
+}
+
+#[test]
+fn load_command_count_larger_than_file_is_handled() {
+    let mut macho = build_macho(&[], u32::MAX, &[0; 64]);
+    assert!(!macho.header_info().is_empty());
+    assert!(macho.load_commands_table().is_ok());
+}
+
+#[test]
+fn section_count_larger_than_file_is_rejected() {
+    let mut macho = build_macho(&[segment("__TEXT", 0, 0, 0, u32::MAX)], 1, &[]);
+    assert!(macho.segments_table().is_err());
+}
+
+#[test]
+fn tool_count_larger_than_file_is_rejected() {
+    let build_version = command(
+        load_commands::LC_BUILD_VERSION,
+        &[1, 0xe0000, 0xe0000, u32::MAX],
+    );
+    let mut macho = build_macho(&[build_version], 1, &[]);
+    assert!(macho.load_commands_table().is_err());
+}
+
+#[test]
+fn symbol_count_larger_than_file_is_rejected() {
+    let symtab = command(load_commands::LC_SYMTAB, &[0x40, u32::MAX, 0x40, 0]);
+    let mut macho = build_macho(&[symtab], 1, &[]);
+    assert!(macho.symbols_table(false).is_err());
+}
+
+/// Universal binary with a broken arm64 slice followed by an image with one symbol, whose
+/// symbol and string table are 0x100 bytes behind the start of the image
+fn universal_binary(image_size: u32) -> Vec<u8> {
+    let mut file = Vec::new();
+    for value in [0xcafebabe, 2, 0x0100000c, 0, 0x1000, 0x100, 12] {
+        file.extend_from_slice(&u32::to_be_bytes(value));
+    }
+    for value in [0x0100000c, 0, 0x2000, image_size, 12] {
+        file.extend_from_slice(&u32::to_be_bytes(value));
+    }
+    file.resize(0x2000, 0);
+    let symtab = command(load_commands::LC_SYMTAB, &[0x100, 1, 0x110, 8]);
+    file.extend(build_macho_data(&[symtab], 1, &[]));
+    file.resize(0x2100, 0);
+    file.extend_from_slice(&1u32.to_le_bytes());
+    file.extend_from_slice(&[1, 0, 0, 0]);
+    file.extend_from_slice(&0u64.to_le_bytes());
+    file.extend_from_slice(b"\0_main\0\0");
+    file
+}
+
+#[test]
+fn broken_slice_fails_only_when_selected() {
+    let mut macho = MachOBinary::new(Blob::new(universal_binary(0x200)).unwrap()).unwrap();
+    assert_eq!(macho.selected_slice(), 1);
+    assert_eq!(macho.symbols_table(false).unwrap().rows.len(), 1);
+    assert!(macho.select_slice(0).is_err());
+    assert_eq!(macho.selected_slice(), 1);
+    assert!(macho.select_slice(1).is_ok());
+}
+
+#[test]
+fn reads_stop_at_the_end_of_the_slice() {
+    // The symbol table is behind the 0x40 bytes of the image
+    let mut macho = MachOBinary::new(Blob::new(universal_binary(0x40)).unwrap()).unwrap();
+    assert!(macho.load_commands_table().is_ok());
+    assert!(macho.symbols_table(false).is_err());
+}
@@ -0,0 +1,114 @@
+//! Data appended behind the end of a binary image, e.g. installer payloads
+use crate::blob::{Blob, BlobError};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[cfg(test)]
+mod tests;
+
+// Signatures of common payloads, the position is relative to the start of the overlay
+const SIGNATURES: [(usize, &[u8], &str); 17] = [
+    (0, b"PK\x03\x04", "ZIP archive"),
+    (0, b"7z\xbc\xaf\x27\x1c", "7-Zip archive"),
+    (0, b"Rar!\x1a\x07", "RAR archive"),
+    (0, b"MSCF", "Microsoft cabinet"),
+    (0, b"\x1f\x8b", "gzip data"),
+    (0, b"\xfd7zXZ\x00", "xz data"),
+    (0, b"BZh", "bzip2 data"),
+    (0, b"MZ", "DOS/PE executable"),
+    (0, b"\x7fELF", "ELF binary"),
+    (0, b"%PDF", "PDF document"),
+    (0, b"\x89PNG", "PNG image"),
+    (0, b"SQLite format 3\x00", "SQLite database"),
+    (4, b"\xef\xbe\xad\xdeNullsoftInst", "NSIS installer data"),
+    (0, b"zlb\x1a", "Inno Setup data"),
+    (0, b"rDlPtS", "Inno Setup data"),
+    (0, b"idska32\x1a", "Inno Setup data"),
+    (0, b"\x30\x82", "DER encoded data"),
+];
+
+/// Bytes behind the end of the file image described by the headers
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Overlay {
+    pub offset: usize,
+    pub size: usize,
+    // Shannon entropy in bits per byte
+    pub entropy: f64,
+    pub file_type: String,
+}
+
+impl Overlay {
+    /// Overlay of `blob` behind `image_end`, if there is any
+    pub fn new(blob: &Blob, image_end: usize) -> Result<Option<Self>, BlobError> {
+        Self::between(blob, image_end, blob.len())
+    }
+
+    /// Overlay of `blob` from `image_end` up to `end`, when other data follows the overlay
+    pub fn between(blob: &Blob, image_end: usize, end: usize) -> Result<Option<Self>, BlobError> {
+        let end = end.min(blob.len());
+        if image_end >= end {
+            return Ok(None);
+        }
+        let size = end - image_end;
+        let data = blob.get_slice(image_end, size)?;
+        let entropy = entropy(data);
+        Ok(Some(Self {
+            offset: image_end,
+            size,
+            entropy,
+            file_type: guess_type(data, entropy),
+        }))
+    }
+}
+
+impl Display for Overlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        write!(
+            f,
+            "offset 0x{:x}, {} bytes, entropy {:.2}, {}",
+            self.offset, self.size, self.entropy, self.file_type
+        )
+    }
+}
+
+/// Shannon entropy in bits per byte, 8 for random data
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for byte in data {
+        counts[*byte as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            // Written as p * log2(1 / p) to avoid a negative zero for uniform data
+            let p = *count as f64 / len;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+fn guess_type(data: &[u8], entropy: f64) -> String {
+    for (position, signature, name) in SIGNATURES {
+        if data.get(position..position + signature.len()) == Some(signature) {
+            return name.to_string();
+        }
+    }
+    let kind = if data.iter().all(|byte| *byte == 0) {
+        "zero padding"
+    } else if data
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace())
+    {
+        "text"
+    } else if entropy > 7.5 {
+        "unknown, compressed or encrypted"
+    } else {
+        "unknown"
+    };
+    kind.to_string()
+}
@@ -0,0 +1,60 @@
+use super::*;
+
+fn blob(data: &[u8]) -> Blob {
+    Blob::new(data.to_vec()).unwrap()
+}
+
+#[test]
+fn overlay_covers_the_data_behind_the_image_end() {
+    let mut data = vec![0x41; 0x80];
+    data.extend_from_slice(b"PK\x03\x04 archive");
+    let blob = blob(&data);
+    let overlay = Overlay::new(&blob, 0x80).unwrap().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (0x80, 12));
+    assert_eq!(overlay.file_type, "ZIP archive");
+    assert!(Overlay::new(&blob, data.len()).unwrap().is_none());
+    assert!(Overlay::new(&blob, 0x1000).unwrap().is_none());
+}
+
+#[test]
+fn overlay_between_stops_at_the_end() {
+    let blob = blob(&[0x41; 0x100]);
+    let overlay = Overlay::between(&blob, 0x80, 0x90).unwrap().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (0x80, 0x10));
+    assert_eq!(overlay.file_type, "text");
+    // The end is limited to the file
+    let overlay = Overlay::between(&blob, 0x80, 0x1000).unwrap().unwrap();
+    assert_eq!(overlay.size, 0x80);
+    assert!(Overlay::between(&blob, 0x90, 0x80).unwrap().is_none());
+    assert_eq!(
+        overlay.to_string(),
+        "offset 0x80, 128 bytes, entropy 0.00, text"
+    );
+}
+
+#[test]
+fn entropy_is_measured_in_bits_per_byte() {
+    assert_eq!(entropy(&[]), 0.0);
+    assert_eq!(entropy(&[7; 64]), 0.0);
+    assert_eq!(entropy(&[0, 1, 0, 1]), 1.0);
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(entropy(&all_bytes), 8.0);
+}
+
+#[test]
+fn payload_types_are_guessed() {
+    let guess = |data: &[u8]| guess_type(data, entropy(data));
+    assert_eq!(guess(b"7z\xbc\xaf\x27\x1c\x00\x04"), "7-Zip archive");
+    assert_eq!(guess(b"MZ\x90\x00"), "DOS/PE executable");
+    // The NSIS signature follows the flags of the first header
+    assert_eq!(
+        guess(b"\x00\x00\x00\x00\xef\xbe\xad\xdeNullsoftInst"),
+        "NSIS installer data"
+    );
+    // Too short for the signature
+    assert_eq!(guess(b"\x00\x00\x00\x00\xef\xbe\xad\xde"), "unknown");
+    assert_eq!(guess(&[0; 512]), "zero padding");
+    assert_eq!(guess(b"key = value\r\n"), "text");
+    let all_bytes: Vec<u8> = (0..=255).rev().collect();
+    assert_eq!(guess(&all_bytes), "unknown, compressed or encrypted");
+}
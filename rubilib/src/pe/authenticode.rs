@@ -0,0 +1,607 @@
+use super::der::{
+    self, Element, CONTEXT_0, CONTEXT_1, GENERALIZED_TIME, INTEGER, OBJECT_IDENTIFIER,
+    OCTET_STRING, SEQUENCE, SET,
+};
+use super::{PeBinary, PeError, Result, CERTIFICATE_DIRECTORY};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+// WIN_CERTIFICATE types
+const WIN_CERT_TYPE_X509: u16 = 0x1;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x2;
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const OID_TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+const OID_RFC3161_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+const OID_NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: &str) -> Option<Self> {
+        match oid {
+            "1.2.840.113549.2.5" => Some(Self::Md5),
+            "1.3.14.3.2.26" => Some(Self::Sha1),
+            "2.16.840.1.101.3.4.2.1" => Some(Self::Sha256),
+            "2.16.840.1.101.3.4.2.2" => Some(Self::Sha384),
+            "2.16.840.1.101.3.4.2.3" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    /// Digest over the concatenation of `parts`, None if the algorithm is not supported
+    fn digest(&self, parts: &[&[u8]]) -> Option<Vec<u8>> {
+        fn hash<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+        match self {
+            Self::Md5 => None,
+            Self::Sha1 => Some(hash::<Sha1>(parts)),
+            Self::Sha256 => Some(hash::<Sha256>(parts)),
+            Self::Sha384 => Some(hash::<Sha384>(parts)),
+            Self::Sha512 => Some(hash::<Sha512>(parts)),
+        }
+    }
+}
+
+/// The fields of an X.509 certificate shown to the user
+pub struct Certificate {
+    pub(super) subject: String,
+    pub(super) issuer: String,
+    pub(super) serial: String,
+    pub(super) not_before: String,
+    pub(super) not_after: String,
+    pub(super) offset: usize,
+    pub(super) size: usize,
+    // Encoded issuer and serial number, used to find the certificate of a signer
+    raw_issuer: Vec<u8>,
+    raw_serial: Vec<u8>,
+}
+
+impl Certificate {
+    fn parse(certificate: &Element) -> Result<Self> {
+        let tbs = certificate.child(0, SEQUENCE)?;
+        let mut fields = tbs.children()?.into_iter();
+        let mut serial = fields.next().ok_or(PeError::MalformedSignature)?;
+        // The version is optional and explicitly tagged
+        if serial.tag == CONTEXT_0 {
+            serial = fields.next().ok_or(PeError::MalformedSignature)?;
+        }
+        let serial = serial.expect(INTEGER)?;
+        let _signature = fields.next().ok_or(PeError::MalformedSignature)?;
+        let issuer = fields.next().ok_or(PeError::MalformedSignature)?;
+        let validity = fields
+            .next()
+            .ok_or(PeError::MalformedSignature)?
+            .expect(SEQUENCE)?
+            .children()?;
+        let subject = fields.next().ok_or(PeError::MalformedSignature)?;
+        Ok(Self {
+            subject: der::name_to_string(&subject)?,
+            issuer: der::name_to_string(&issuer)?,
+            serial: serial.serial(),
+            not_before: validity
+                .first()
+                .ok_or(PeError::MalformedSignature)?
+                .time()?,
+            not_after: validity.get(1).ok_or(PeError::MalformedSignature)?.time()?,
+            offset: certificate.offset,
+            size: certificate.raw.len(),
+            raw_issuer: issuer.raw.to_vec(),
+            raw_serial: serial.content.to_vec(),
+        })
+    }
+
+    /// Common name of the subject, the full name if there is none
+    pub(super) fn common_name(&self) -> String {
+        self.subject
+            .split(", ")
+            .find_map(|part| part.strip_prefix("CN="))
+            .unwrap_or(&self.subject)
+            .to_string()
+    }
+}
+
+// Name and value of a property, with the file range to view where it applies
+type Property = (String, String, Option<(usize, usize)>);
+
+/// A time stamp of the signature, either a counter signature or an RFC 3161 token
+pub struct Timestamp {
+    pub(super) kind: &'static str,
+    pub(super) time: String,
+    pub(super) signer: Option<String>,
+}
+
+/// One Authenticode signature, the PKCS#7 SignedData of a WIN_CERTIFICATE or a nested signature
+pub struct Signature {
+    // Position of the WIN_CERTIFICATE entry, nested signatures share the entry of their parent
+    pub(super) entry_offset: usize,
+    pub(super) entry_size: usize,
+    pub(super) nested: bool,
+    pub(super) digest_algorithm: Option<DigestAlgorithm>,
+    pub(super) digest_algorithm_oid: String,
+    pub(super) signed_digest: Vec<u8>,
+    pub(super) computed_digest: Option<Vec<u8>>,
+    // Digest of the signed content compared to the messageDigest attribute
+    pub(super) message_digest_matches: Option<bool>,
+    pub(super) signer_issuer: String,
+    pub(super) signer_serial: String,
+    pub(super) signer: Option<usize>,
+    pub(super) signing_time: Option<String>,
+    pub(super) timestamps: Vec<Timestamp>,
+    pub(super) certificates: Vec<Certificate>,
+}
+
+impl Signature {
+    pub(super) fn digest_matches(&self) -> Option<bool> {
+        self.computed_digest
+            .as_ref()
+            .map(|digest| *digest == self.signed_digest)
+    }
+
+    pub(super) fn signer(&self) -> Option<&Certificate> {
+        self.signer.map(|idx| &self.certificates[idx])
+    }
+
+    /// Short verdict of the signer and the digest checks
+    pub(super) fn summary(&self) -> String {
+        let signer = match self.signer() {
+            Some(certificate) => certificate.common_name(),
+            None => "unknown signer".to_string(),
+        };
+        let verdict = match (self.digest_matches(), self.message_digest_matches) {
+            (Some(true), Some(false)) => "signed content modified",
+            (Some(true), _) => "image digest matches",
+            (Some(false), _) => "image digest MISMATCH, file was modified",
+            (None, _) => "digest algorithm not supported",
+        };
+        format!("signed by {signer}, {verdict}")
+    }
+
+    pub(super) fn properties(&self) -> Vec<Property> {
+        let entry = Some((self.entry_offset, self.entry_size));
+        let mut properties = vec![
+            (
+                "Type".to_string(),
+                if self.nested {
+                    "Nested PKCS#7 signature"
+                } else {
+                    "PKCS#7 signed data"
+                }
+                .to_string(),
+                entry,
+            ),
+            ("Summary".to_string(), self.summary(), entry),
+            (
+                "Digest algorithm".to_string(),
+                self.digest_algorithm
+                    .map(|algorithm| algorithm.name().to_string())
+                    .unwrap_or_else(|| self.digest_algorithm_oid.clone()),
+                None,
+            ),
+            (
+                "Signed image digest".to_string(),
+                der::hex_string(&self.signed_digest),
+                None,
+            ),
+        ];
+        if let Some(digest) = &self.computed_digest {
+            properties.push((
+                "Computed image digest".to_string(),
+                der::hex_string(digest),
+                None,
+            ));
+        }
+        let image_digest = match self.digest_matches() {
+            Some(true) => "matches",
+            Some(false) => "MISMATCH",
+            None => "not computed",
+        };
+        properties.push(("Image digest".to_string(), image_digest.to_string(), None));
+        let message_digest = match self.message_digest_matches {
+            Some(true) => "matches",
+            Some(false) => "MISMATCH",
+            None => "not present",
+        };
+        properties.push((
+            "Signed attributes digest".to_string(),
+            message_digest.to_string(),
+            None,
+        ));
+        // Only digests are checked, there are no trust anchors offline
+        properties.push((
+            "Certificate chain".to_string(),
+            "not validated".to_string(),
+            None,
+        ));
+        match self.signer() {
+            Some(signer) => {
+                let range = Some((signer.offset, signer.size));
+                properties.push(("Signer".to_string(), signer.subject.clone(), range));
+                properties.push(("Issuer".to_string(), signer.issuer.clone(), range));
+                properties.push(("Serial number".to_string(), signer.serial.clone(), range));
+                properties.push(("Valid from".to_string(), signer.not_before.clone(), range));
+                properties.push(("Valid to".to_string(), signer.not_after.clone(), range));
+            }
+            None => {
+                properties.push(("Issuer".to_string(), self.signer_issuer.clone(), None));
+                properties.push((
+                    "Serial number".to_string(),
+                    self.signer_serial.clone(),
+                    None,
+                ));
+            }
+        }
+        if let Some(time) = &self.signing_time {
+            properties.push(("Signing time".to_string(), time.clone(), None));
+        }
+        for timestamp in &self.timestamps {
+            let signer = timestamp
+                .signer
+                .as_ref()
+                .map(|signer| format!(" by {signer}"))
+                .unwrap_or_default();
+            properties.push((
+                timestamp.kind.to_string(),
+                format!("{}{signer}", timestamp.time),
+                None,
+            ));
+        }
+        for (idx, certificate) in self.certificates.iter().enumerate() {
+            properties.push((
+                format!("Certificate {}", idx + 1),
+                format!(
+                    "{} (issuer: {}, serial: {}, valid {} to {})",
+                    certificate.subject,
+                    certificate.issuer,
+                    certificate.serial,
+                    certificate.not_before,
+                    certificate.not_after
+                ),
+                Some((certificate.offset, certificate.size)),
+            ));
+        }
+        properties
+    }
+}
+
+/// The parts of a PKCS#7 SignedData structure used here
+struct SignedData<'a> {
+    content_type: String,
+    content: Element<'a>,
+    certificates: Vec<Certificate>,
+    signer_infos: Vec<SignerInfo<'a>>,
+}
+
+struct SignerInfo<'a> {
+    issuer: Element<'a>,
+    serial: Element<'a>,
+    authenticated_attributes: Vec<(String, Vec<Element<'a>>)>,
+    unauthenticated_attributes: Vec<(String, Vec<Element<'a>>)>,
+}
+
+impl<'a> SignerInfo<'a> {
+    fn parse(signer_info: &Element<'a>) -> Result<Self> {
+        let fields = signer_info.expect(SEQUENCE)?.children()?;
+        let issuer_and_serial = fields
+            .get(1)
+            .ok_or(PeError::MalformedSignature)?
+            .expect(SEQUENCE)?;
+        let mut authenticated_attributes = Vec::new();
+        let mut unauthenticated_attributes = Vec::new();
+        for field in &fields[2..] {
+            match field.tag {
+                CONTEXT_0 => authenticated_attributes = parse_attributes(field)?,
+                CONTEXT_1 => unauthenticated_attributes = parse_attributes(field)?,
+                _ => {}
+            }
+        }
+        Ok(Self {
+            issuer: issuer_and_serial.child(0, SEQUENCE)?,
+            serial: issuer_and_serial.child(1, INTEGER)?,
+            authenticated_attributes,
+            unauthenticated_attributes,
+        })
+    }
+
+    fn attribute(&self, oid: &str) -> Option<&Element<'a>> {
+        self.authenticated_attributes
+            .iter()
+            .find(|(attribute, _)| attribute == oid)
+            .and_then(|(_, values)| values.first())
+    }
+
+    /// Index of the certificate matching issuer and serial number
+    fn find_certificate(&self, certificates: &[Certificate]) -> Option<usize> {
+        certificates.iter().position(|certificate| {
+            certificate.raw_issuer == self.issuer.raw
+                && certificate.raw_serial == self.serial.content
+        })
+    }
+
+    fn signing_time(&self) -> Option<String> {
+        self.attribute(OID_SIGNING_TIME)
+            .and_then(|time| time.time().ok())
+    }
+}
+
+/// Attributes are a set of sequences of a type and a set of values
+fn parse_attributes<'a>(attributes: &Element<'a>) -> Result<Vec<(String, Vec<Element<'a>>)>> {
+    let mut result = Vec::new();
+    for attribute in attributes.children()? {
+        let oid = attribute.child(0, OBJECT_IDENTIFIER)?.oid()?;
+        let values = attribute.child(1, SET)?.children()?;
+        result.push((oid, values));
+    }
+    Ok(result)
+}
+
+/// Decode a ContentInfo that wraps a SignedData structure
+fn parse_signed_data<'a>(content_info: &Element<'a>) -> Result<SignedData<'a>> {
+    if content_info.child(0, OBJECT_IDENTIFIER)?.oid()? != OID_SIGNED_DATA {
+        return Err(PeError::MalformedSignature);
+    }
+    let signed_data = content_info.child(1, CONTEXT_0)?.child(0, SEQUENCE)?;
+    let fields = signed_data.children()?;
+    let content_info = fields
+        .get(2)
+        .ok_or(PeError::MalformedSignature)?
+        .expect(SEQUENCE)?;
+    let mut certificates = Vec::new();
+    let mut signer_infos = Vec::new();
+    for field in &fields[3..] {
+        match field.tag {
+            CONTEXT_0 => {
+                for certificate in field.children()? {
+                    // Skip attribute certificates and other choices
+                    if certificate.tag == SEQUENCE {
+                        certificates.push(Certificate::parse(&certificate)?);
+                    }
+                }
+            }
+            SET => {
+                for signer_info in field.children()? {
+                    signer_infos.push(SignerInfo::parse(&signer_info)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    // Authenticode embeds its content directly, time stamp tokens wrap it into an octet string
+    let content = *content_info
+        .child(1, CONTEXT_0)?
+        .children()?
+        .first()
+        .ok_or(PeError::MalformedSignature)?;
+    Ok(SignedData {
+        content_type: content_info.child(0, OBJECT_IDENTIFIER)?.oid()?,
+        content,
+        certificates,
+        signer_infos,
+    })
+}
+
+/// Time stamps found in the unauthenticated attributes of the signer
+fn parse_timestamps(signer_info: &SignerInfo, certificates: &[Certificate]) -> Vec<Timestamp> {
+    let mut timestamps = Vec::new();
+    for (oid, values) in &signer_info.unauthenticated_attributes {
+        for value in values {
+            let timestamp = match oid.as_str() {
+                OID_COUNTER_SIGNATURE => parse_counter_signature(value, certificates),
+                OID_RFC3161_TIMESTAMP => parse_timestamp_token(value),
+                _ => continue,
+            };
+            // A broken time stamp does not invalidate the signature itself
+            if let Ok(timestamp) = timestamp {
+                timestamps.push(timestamp);
+            }
+        }
+    }
+    timestamps
+}
+
+/// Legacy counter signature, a SignerInfo whose certificate is part of the outer signature
+fn parse_counter_signature(value: &Element, certificates: &[Certificate]) -> Result<Timestamp> {
+    let counter_signer = SignerInfo::parse(value)?;
+    Ok(Timestamp {
+        kind: "Counter signature",
+        time: counter_signer
+            .signing_time()
+            .ok_or(PeError::MalformedSignature)?,
+        signer: counter_signer
+            .find_certificate(certificates)
+            .map(|idx| certificates[idx].common_name()),
+    })
+}
+
+/// RFC 3161 time stamp token, a SignedData containing a TSTInfo
+fn parse_timestamp_token(value: &Element) -> Result<Timestamp> {
+    let token = parse_signed_data(value)?;
+    if token.content_type != OID_TST_INFO {
+        return Err(PeError::MalformedSignature);
+    }
+    let content = token.content.expect(OCTET_STRING)?;
+    let tst_info = Element::parse_tagged(
+        content.content,
+        content.offset + content.raw.len() - content.content.len(),
+        SEQUENCE,
+    )?;
+    // version, policy, messageImprint, serialNumber, genTime
+    let time = tst_info.child(4, GENERALIZED_TIME)?.time()?;
+    let signer = token
+        .signer_infos
+        .first()
+        .and_then(|signer_info| signer_info.find_certificate(&token.certificates))
+        .map(|idx| token.certificates[idx].common_name());
+    Ok(Timestamp {
+        kind: "Time stamp (RFC 3161)",
+        time,
+        signer,
+    })
+}
+
+/// Decode an Authenticode signature, the image digest is left to the caller. Also returns the
+/// nested signatures found within its unauthenticated attributes
+fn parse_signature<'a>(
+    content_info: &Element<'a>,
+    entry: (usize, usize),
+    nested: bool,
+) -> Result<(Signature, Vec<Element<'a>>)> {
+    let signed_data = parse_signed_data(content_info)?;
+    if signed_data.content_type != OID_SPC_INDIRECT_DATA {
+        return Err(PeError::MalformedSignature);
+    }
+    // SpcIndirectDataContent is the description of the image followed by its digest
+    let digest_info = signed_data.content.child(1, SEQUENCE)?;
+    let digest_algorithm_oid = digest_info
+        .child(0, SEQUENCE)?
+        .child(0, OBJECT_IDENTIFIER)?
+        .oid()?;
+    let digest_algorithm = DigestAlgorithm::from_oid(&digest_algorithm_oid);
+    let signed_digest = digest_info.child(1, OCTET_STRING)?.content.to_vec();
+
+    let signer_info = signed_data
+        .signer_infos
+        .first()
+        .ok_or(PeError::MalformedSignature)?;
+    // The signed attributes cover the content without its tag and length
+    let message_digest_matches = match (digest_algorithm, signer_info.attribute(OID_MESSAGE_DIGEST))
+    {
+        (Some(algorithm), Some(message_digest)) => algorithm
+            .digest(&[signed_data.content.content])
+            .map(|digest| digest == message_digest.content),
+        _ => None,
+    };
+
+    let nested_signatures = signer_info
+        .unauthenticated_attributes
+        .iter()
+        .filter(|(oid, _)| oid == OID_NESTED_SIGNATURE)
+        .flat_map(|(_, values)| values.iter().copied())
+        .collect();
+    let signature = Signature {
+        entry_offset: entry.0,
+        entry_size: entry.1,
+        nested,
+        digest_algorithm,
+        digest_algorithm_oid,
+        signed_digest,
+        computed_digest: None,
+        message_digest_matches,
+        signer_issuer: der::name_to_string(&signer_info.issuer)?,
+        signer_serial: signer_info.serial.serial(),
+        signer: signer_info.find_certificate(&signed_data.certificates),
+        signing_time: signer_info.signing_time(),
+        timestamps: parse_timestamps(signer_info, &signed_data.certificates),
+        certificates: signed_data.certificates,
+    };
+    Ok((signature, nested_signatures))
+}
+
+/// Signatures of the certificate table, sections have to be loaded
+pub(super) fn parse_signatures(binary: &PeBinary) -> Result<Vec<Signature>> {
+    let mut signatures = Vec::new();
+    // The certificate table is not mapped, its address is a file offset
+    let Some(directory) = binary.data_directory(CERTIFICATE_DIRECTORY) else {
+        return Ok(signatures);
+    };
+    let blob = &binary.blob;
+    // The image is hashed once per algorithm, whatever the number of signatures using it
+    let mut digests: Vec<(DigestAlgorithm, Option<Vec<u8>>)> = Vec::new();
+    let mut offset = directory.virtual_address as usize;
+    let end = offset + directory.size as usize;
+    while offset + 8 <= end {
+        let length = blob.get_u32(offset)? as usize;
+        let certificate_type = blob.get_u16(offset + 6)?;
+        if length < 8 || offset + length > end {
+            return Err(PeError::MalformedSignature);
+        }
+        match certificate_type {
+            WIN_CERT_TYPE_PKCS_SIGNED_DATA => {
+                let data = blob.get_slice(offset + 8, length - 8)?;
+                // Nested signatures are kept on a worklist, in the order they appear
+                let mut pending = vec![(Element::parse_tagged(data, offset + 8, SEQUENCE)?, false)];
+                while let Some((content_info, nested)) = pending.pop() {
+                    let (mut signature, nested_signatures) =
+                        parse_signature(&content_info, (offset, length), nested)?;
+                    if let Some(algorithm) = signature.digest_algorithm {
+                        let idx = match digests.iter().position(|(known, _)| *known == algorithm) {
+                            Some(idx) => idx,
+                            None => {
+                                digests.push((algorithm, image_digest(binary, algorithm)?));
+                                digests.len() - 1
+                            }
+                        };
+                        signature.computed_digest = digests[idx].1.clone();
+                    }
+                    signatures.push(signature);
+                    pending.extend(
+                        nested_signatures
+                            .into_iter()
+                            .rev()
+                            .map(|value| (value, true)),
+                    );
+                }
+            }
+            // Plain certificates carry no signature of the image
+            WIN_CERT_TYPE_X509 => {}
+            _ => return Err(PeError::MalformedSignature),
+        }
+        // Entries are aligned to 8 bytes
+        offset += (length + 7) & !7;
+    }
+    Ok(signatures)
+}
+
+/// Authenticode digest of the image, skipping the checksum, the certificate table entry
+/// of the data directories and the certificate table itself
+pub(super) fn image_digest(
+    binary: &PeBinary,
+    algorithm: DigestAlgorithm,
+) -> Result<Option<Vec<u8>>> {
+    let Some(directory) = binary.data_directory(CERTIFICATE_DIRECTORY) else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let optional_header = binary.dos_header.pe_offset as usize + 24;
+    let checksum = optional_header + 64;
+    let certificate_entry =
+        optional_header + if binary.is_64() { 112 } else { 96 } + 8 * CERTIFICATE_DIRECTORY;
+    let table_start = directory.virtual_address as usize;
+    let table_end = table_start + directory.size as usize;
+    if table_start < certificate_entry + 8 || table_end > blob.len() {
+        return Err(PeError::MalformedSignature);
+    }
+    let parts = [
+        blob.get_slice(0, checksum)?,
+        blob.get_slice(checksum + 4, certificate_entry - checksum - 4)?,
+        blob.get_slice(certificate_entry + 8, table_start - certificate_entry - 8)?,
+        blob.get_slice(table_end, blob.len() - table_end)?,
+    ];
+    Ok(algorithm.digest(&parts))
+}
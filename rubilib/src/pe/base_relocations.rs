@@ -0,0 +1,123 @@
+use super::{MachineType, PeBinary, Result};
+
+const BASE_RELOCATION_DIRECTORY: usize = 5;
+
+// Relocation types with the same meaning on all machines
+const IMAGE_REL_BASED_ABSOLUTE: u8 = 0;
+const IMAGE_REL_BASED_HIGHLOW: u8 = 3;
+const IMAGE_REL_BASED_HIGHADJ: u8 = 4;
+const IMAGE_REL_BASED_DIR64: u8 = 10;
+
+/// A location the loader patches if the image is not loaded at its preferred base
+pub struct BaseRelocation {
+    pub(super) page: u32,
+    pub(super) rva: u32,
+    pub(super) kind: u8,
+    // Parameter of HIGHADJ relocations, stored in the following entry
+    pub(super) parameter: Option<u16>,
+    // Address currently stored at the location
+    pub(super) target: Option<u64>,
+}
+
+impl BaseRelocation {
+    fn type_as_string(&self, machine: MachineType) -> String {
+        let name = match (self.kind, machine) {
+            (0, _) => "ABSOLUTE",
+            (1, _) => "HIGH",
+            (2, _) => "LOW",
+            (3, _) => "HIGHLOW",
+            (4, _) => "HIGHADJ",
+            (5, MachineType::Arm | MachineType::Thumb | MachineType::ArmNt) => "ARM_MOV32",
+            (5, MachineType::RiscV32 | MachineType::RiscV64 | MachineType::RiscV128) => {
+                "RISCV_HIGH20"
+            }
+            (5, _) => "MIPS_JMPADDR",
+            (7, MachineType::Arm | MachineType::Thumb | MachineType::ArmNt) => "THUMB_MOV32",
+            (7, MachineType::RiscV32 | MachineType::RiscV64 | MachineType::RiscV128) => {
+                "RISCV_LOW12I"
+            }
+            (8, MachineType::RiscV32 | MachineType::RiscV64 | MachineType::RiscV128) => {
+                "RISCV_LOW12S"
+            }
+            (8, MachineType::LoongArch32 | MachineType::LoongArch64) => "LOONGARCH_MARK_LA",
+            (9, _) => "MIPS_JMPADDR16",
+            (10, _) => "DIR64",
+            _ => "",
+        };
+        if name.is_empty() {
+            format!("unknown ({})", self.kind)
+        } else {
+            name.to_string()
+        }
+    }
+
+    pub(super) fn to_vec(&self, machine: MachineType) -> Vec<String> {
+        vec![
+            format!("0x{:08x}", self.page),
+            format!("0x{:08x}", self.rva),
+            self.type_as_string(machine),
+            match (self.target, self.parameter) {
+                (Some(target), _) => format!("0x{target:x}"),
+                (None, Some(parameter)) => format!("0x{parameter:04x}"),
+                (None, None) => String::new(),
+            },
+        ]
+    }
+}
+
+/// Decode the blocks of the base relocation table, sections have to be loaded
+pub(super) fn parse_base_relocations(binary: &PeBinary) -> Result<Vec<BaseRelocation>> {
+    let mut relocations = Vec::new();
+    let Some(directory) = binary.data_directory(BASE_RELOCATION_DIRECTORY) else {
+        return Ok(relocations);
+    };
+    let Some(start) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(relocations);
+    };
+    let blob = &binary.blob;
+    let end = start + directory.size as usize;
+    let mut block = start;
+    // Each block covers one 4k page and starts with the page RVA and the block size
+    while block + 8 <= end {
+        let page = blob.get_u32(block)?;
+        let block_size = blob.get_u32(block + 4)? as usize;
+        // A broken block ends the table, the relocations decoded so far are kept
+        if block_size < 8 || block + block_size > end {
+            break;
+        }
+        let mut entries = (block + 8..block + block_size).step_by(2);
+        while let Some(entry) = entries.next() {
+            let value = blob.get_u16(entry)?;
+            let kind = (value >> 12) as u8;
+            // Padding to align the next block
+            if kind == IMAGE_REL_BASED_ABSOLUTE {
+                continue;
+            }
+            let rva = page.wrapping_add((value & 0xfff) as u32);
+            let parameter = if kind == IMAGE_REL_BASED_HIGHADJ {
+                match entries.next() {
+                    Some(entry) => Some(blob.get_u16(entry)?),
+                    None => None,
+                }
+            } else {
+                None
+            };
+            let target = match (kind, binary.rva_to_offset(rva)?) {
+                (IMAGE_REL_BASED_HIGHLOW, Some(offset)) => {
+                    blob.get_u32(offset).ok().map(|target| target as u64)
+                }
+                (IMAGE_REL_BASED_DIR64, Some(offset)) => blob.get_u64(offset).ok(),
+                _ => None,
+            };
+            relocations.push(BaseRelocation {
+                page,
+                rva,
+                kind,
+                parameter,
+                target,
+            });
+        }
+        block += block_size;
+    }
+    Ok(relocations)
+}
@@ -0,0 +1,88 @@
+use super::{load_config, PeBinary, Result};
+
+// COFF characteristics
+const IMAGE_FILE_RELOCS_STRIPPED: u16 = 0x1;
+
+// DLL characteristics
+const IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA: u16 = 0x20;
+const IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE: u16 = 0x40;
+const IMAGE_DLLCHARACTERISTICS_NX_COMPAT: u16 = 0x100;
+const IMAGE_DLLCHARACTERISTICS_NO_SEH: u16 = 0x400;
+const IMAGE_DLLCHARACTERISTICS_GUARD_CF: u16 = 0x4000;
+
+// Load config guard flags
+const IMAGE_GUARD_CF_INSTRUMENTED: u32 = 0x100;
+
+/// Hardening properties derived from the COFF and optional headers and the load configuration
+pub(super) fn checksec(binary: &PeBinary) -> Result<Vec<(String, String)>> {
+    let is_64 = binary.is_64();
+    let characteristics = binary.coff_header.characteristics;
+    let dll_characteristics = binary
+        .optional_header
+        .as_ref()
+        .map(|header| header.dll_characteristics)
+        .unwrap_or_default();
+    let load_config = load_config::parse_load_config(binary)?;
+    let has = |flag: u16| dll_characteristics & flag != 0;
+
+    let mut info = Vec::new();
+
+    let aslr = match (
+        has(IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE),
+        characteristics & IMAGE_FILE_RELOCS_STRIPPED != 0,
+    ) {
+        (true, false) => "ASLR enabled",
+        (true, true) => "ASLR requested, but relocations stripped",
+        (false, _) => "No ASLR",
+    };
+    info.push(("ASLR".to_string(), aslr.to_string()));
+
+    let high_entropy_va = if !is_64 {
+        "n/a (32 bit)"
+    } else if has(IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA) {
+        "High entropy VA enabled"
+    } else {
+        "No high entropy VA"
+    };
+    info.push(("High entropy VA".to_string(), high_entropy_va.to_string()));
+
+    let dep = if has(IMAGE_DLLCHARACTERISTICS_NX_COMPAT) {
+        "DEP enabled"
+    } else {
+        "No DEP"
+    };
+    info.push(("DEP".to_string(), dep.to_string()));
+
+    let instrumented = load_config
+        .as_ref()
+        .is_some_and(|config| config.guard_flags & IMAGE_GUARD_CF_INSTRUMENTED != 0);
+    let cfg = match (has(IMAGE_DLLCHARACTERISTICS_GUARD_CF), instrumented) {
+        (true, true) => "CFG enabled",
+        (true, false) => "CFG flag set, but code not instrumented",
+        (false, _) => "No CFG",
+    };
+    info.push(("CFG".to_string(), cfg.to_string()));
+
+    // SafeSEH only exists for 32 bit images, 64 bit exception handling is table based
+    let safe_seh = if is_64 {
+        "n/a (64 bit)".to_string()
+    } else if has(IMAGE_DLLCHARACTERISTICS_NO_SEH) {
+        "No SEH".to_string()
+    } else {
+        match &load_config {
+            Some(config) if config.se_handler_count > 0 => {
+                format!("SafeSEH enabled ({} handlers)", config.se_handler_count)
+            }
+            _ => "No SafeSEH".to_string(),
+        }
+    };
+    info.push(("SafeSEH".to_string(), safe_seh));
+
+    let stack_cookie = match &load_config {
+        Some(config) if config.security_cookie != 0 => "Stack cookie found",
+        _ => "No stack cookie",
+    };
+    info.push(("Stack cookie (/GS)".to_string(), stack_cookie.to_string()));
+
+    Ok(info)
+}
@@ -0,0 +1,280 @@
+use super::der::hex_string;
+use super::{flags_as_string, format_timestamp, PeBinary, Result};
+use crate::blob::Blob;
+use std::fmt::{self, Display};
+use strum::FromRepr;
+
+const DEBUG_DIRECTORY: usize = 6;
+
+// Signatures of CodeView records, PDB 7.0 and PDB 2.0 format
+const CODEVIEW_RSDS: u32 = 0x53445352;
+const CODEVIEW_NB10: u32 = 0x3031424e;
+
+const EX_DLL_CHARACTERISTICS_NAMES: [(u32, &str); 8] = [
+    (0x1, "CET_COMPAT"),
+    (0x2, "CET_COMPAT_STRICT_MODE"),
+    (0x4, "CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE"),
+    (0x8, "CET_DYNAMIC_APIS_ALLOW_IN_PROC"),
+    (0x10, "CET_RESERVED_1"),
+    (0x20, "CET_RESERVED_2"),
+    (0x40, "FORWARD_CFI_COMPAT"),
+    (0x80, "HOTPATCH_COMPATIBLE"),
+];
+
+#[repr(u32)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum DebugType {
+    Unknown = 0,
+    Coff = 1,
+    CodeView = 2,
+    Fpo = 3,
+    Misc = 4,
+    Exception = 5,
+    Fixup = 6,
+    OmapToSrc = 7,
+    OmapFromSrc = 8,
+    Borland = 9,
+    Reserved10 = 10,
+    Clsid = 11,
+    VcFeature = 12,
+    Pogo = 13,
+    Iltcg = 14,
+    Mpx = 15,
+    Repro = 16,
+    EmbeddedPdb = 17,
+    PdbChecksum = 19,
+    ExDllCharacteristics = 20,
+}
+
+impl Display for DebugType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        match self {
+            DebugType::Unknown => write!(f, "Unknown"),
+            DebugType::Coff => write!(f, "COFF"),
+            DebugType::CodeView => write!(f, "CodeView"),
+            DebugType::Fpo => write!(f, "FPO"),
+            DebugType::Misc => write!(f, "Misc"),
+            DebugType::Exception => write!(f, "Exception"),
+            DebugType::Fixup => write!(f, "Fixup"),
+            DebugType::OmapToSrc => write!(f, "OMAP to source"),
+            DebugType::OmapFromSrc => write!(f, "OMAP from source"),
+            DebugType::Borland => write!(f, "Borland"),
+            DebugType::Reserved10 => write!(f, "Reserved"),
+            DebugType::Clsid => write!(f, "CLSID"),
+            DebugType::VcFeature => write!(f, "VC feature"),
+            DebugType::Pogo => write!(f, "POGO"),
+            DebugType::Iltcg => write!(f, "ILTCG"),
+            DebugType::Mpx => write!(f, "MPX"),
+            DebugType::Repro => write!(f, "Repro"),
+            DebugType::EmbeddedPdb => write!(f, "Embedded PDB"),
+            DebugType::PdbChecksum => write!(f, "PDB checksum"),
+            DebugType::ExDllCharacteristics => write!(f, "Extended DLL characteristics"),
+        }
+    }
+}
+
+/// Decoded payload of a debug directory entry
+pub enum DebugInfo {
+    // Link to the PDB file, the GUID and age identify it on a symbol server
+    CodeView {
+        guid: String,
+        age: u32,
+        path: String,
+    },
+    // Profile guided optimization data, the names of the contributing subsections
+    Pogo {
+        signature: String,
+        names: Vec<String>,
+    },
+    // Hash of the inputs of a deterministic build
+    Repro(Vec<u8>),
+    ExDllCharacteristics(u32),
+    // Number of objects built with the various compiler security features
+    VcFeature([u32; 5]),
+    PdbChecksum {
+        algorithm: String,
+        checksum: Vec<u8>,
+    },
+    None,
+}
+
+/// An entry of the debug directory
+pub struct DebugEntry {
+    pub(super) time_date_stamp: u32,
+    pub(super) version: (u16, u16),
+    pub(super) debug_type: u32,
+    pub(super) size_of_data: u32,
+    pub(super) address_of_raw_data: u32,
+    pub(super) pointer_to_raw_data: u32,
+    pub(super) info: DebugInfo,
+}
+
+impl DebugEntry {
+    fn type_as_string(&self) -> String {
+        match DebugType::from_repr(self.debug_type) {
+            Some(debug_type) => debug_type.to_string(),
+            None => format!("0x{:x}", self.debug_type),
+        }
+    }
+
+    fn details(&self) -> String {
+        match &self.info {
+            DebugInfo::CodeView { guid, age, path } => format!("{guid} age {age} {path}"),
+            DebugInfo::Pogo { signature, names } if signature.is_empty() => names.join(" "),
+            DebugInfo::Pogo { signature, names } => {
+                format!("{signature}: {}", names.join(" "))
+            }
+            DebugInfo::Repro(hash) if hash.is_empty() => "deterministic build".to_string(),
+            DebugInfo::Repro(hash) => hex_string(hash),
+            DebugInfo::ExDllCharacteristics(flags) => format!(
+                "0x{flags:08x} {}",
+                flags_as_string(*flags, &EX_DLL_CHARACTERISTICS_NAMES)
+            ),
+            DebugInfo::VcFeature(counts) => format!(
+                "Pre-VC++ 11.00: {}, C/C++: {}, /GS: {}, /sdl: {}, guardN: {}",
+                counts[0], counts[1], counts[2], counts[3], counts[4]
+            ),
+            DebugInfo::PdbChecksum {
+                algorithm,
+                checksum,
+            } => format!("{algorithm}: {}", hex_string(checksum)),
+            DebugInfo::None => String::new(),
+        }
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.type_as_string(),
+            if self.time_date_stamp == 0 {
+                "0".to_string()
+            } else {
+                format_timestamp(self.time_date_stamp)
+            },
+            format!("{}.{}", self.version.0, self.version.1),
+            format!("0x{:08x}", self.address_of_raw_data),
+            format!("0x{:08x}", self.pointer_to_raw_data),
+            self.size_of_data.to_string(),
+            self.details(),
+        ]
+    }
+}
+
+/// GUID in registry format, the first three fields are little endian
+fn guid_as_string(blob: &Blob, offset: usize) -> Result<String> {
+    let data4 = blob.get_slice(offset + 8, 8)?;
+    Ok(format!(
+        "{{{:08X}-{:04X}-{:04X}-{}-{}}}",
+        blob.get_u32(offset)?,
+        blob.get_u16(offset + 4)?,
+        blob.get_u16(offset + 6)?,
+        hex_string(&data4[..2]).to_uppercase(),
+        hex_string(&data4[2..]).to_uppercase()
+    ))
+}
+
+fn parse_info(blob: &Blob, debug_type: u32, offset: usize, size: usize) -> Result<DebugInfo> {
+    let info = match DebugType::from_repr(debug_type) {
+        Some(DebugType::CodeView) => match blob.get_u32(offset)? {
+            CODEVIEW_RSDS => DebugInfo::CodeView {
+                guid: guid_as_string(blob, offset + 4)?,
+                age: blob.get_u32(offset + 20)?,
+                path: blob.get_cname(Some(offset + 24))?,
+            },
+            // The old format has a time stamp instead of a GUID
+            CODEVIEW_NB10 => DebugInfo::CodeView {
+                guid: format!("{:08X}", blob.get_u32(offset + 8)?),
+                age: blob.get_u32(offset + 12)?,
+                path: blob.get_cname(Some(offset + 16))?,
+            },
+            _ => DebugInfo::None,
+        },
+        Some(DebugType::Pogo) => {
+            // Four character code like "LTCG" or "PGU", stored as little endian number
+            let signature = blob.get_u32(offset)?.to_be_bytes();
+            let signature = String::from_utf8_lossy(&signature)
+                .trim_matches('\0')
+                .to_string();
+            // Entries of RVA, size and a zero terminated name padded to four bytes
+            let mut names = Vec::new();
+            let mut idx = offset + 4;
+            while idx + 9 <= offset + size {
+                let name = blob.get_cstr(idx + 8)?;
+                idx += (8 + name.to_bytes().len() + 1 + 3) & !3;
+                names.push(name.to_string_lossy().to_string());
+            }
+            DebugInfo::Pogo { signature, names }
+        }
+        Some(DebugType::Repro) => {
+            if size >= 4 {
+                let len = (blob.get_u32(offset)? as usize).min(size - 4);
+                DebugInfo::Repro(blob.get_slice(offset + 4, len)?.to_vec())
+            } else {
+                DebugInfo::Repro(Vec::new())
+            }
+        }
+        Some(DebugType::ExDllCharacteristics) => {
+            DebugInfo::ExDllCharacteristics(blob.get_u32(offset)?)
+        }
+        Some(DebugType::VcFeature) => {
+            let mut counts = [0; 5];
+            for (idx, count) in counts.iter_mut().enumerate() {
+                *count = blob.get_u32(offset + 4 * idx)?;
+            }
+            DebugInfo::VcFeature(counts)
+        }
+        Some(DebugType::PdbChecksum) => {
+            let algorithm = blob.get_cstr(offset)?;
+            let start = offset + algorithm.to_bytes().len() + 1;
+            let algorithm = algorithm.to_string_lossy().to_string();
+            let len = (offset + size).saturating_sub(start);
+            DebugInfo::PdbChecksum {
+                algorithm,
+                checksum: blob.get_slice(start, len)?.to_vec(),
+            }
+        }
+        _ => DebugInfo::None,
+    };
+    Ok(info)
+}
+
+/// Decode the entries of the debug directory, sections have to be loaded
+pub(super) fn parse_debug_directory(binary: &PeBinary) -> Result<Vec<DebugEntry>> {
+    let mut entries = Vec::new();
+    let Some(directory) = binary.data_directory(DEBUG_DIRECTORY) else {
+        return Ok(entries);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(entries);
+    };
+    let blob = &binary.blob;
+    for idx in 0..directory.size as usize / 28 {
+        let entry = offset + 28 * idx;
+        let debug_type = blob.get_u32(entry + 12)?;
+        let size_of_data = blob.get_u32(entry + 16)?;
+        let pointer_to_raw_data = blob.get_u32(entry + 24)?;
+        // Payloads outside of the file are listed without details
+        let info = if pointer_to_raw_data != 0 && size_of_data != 0 {
+            parse_info(
+                blob,
+                debug_type,
+                pointer_to_raw_data as usize,
+                size_of_data as usize,
+            )
+            .unwrap_or(DebugInfo::None)
+        } else if debug_type == DebugType::Repro as u32 {
+            DebugInfo::Repro(Vec::new())
+        } else {
+            DebugInfo::None
+        };
+        entries.push(DebugEntry {
+            time_date_stamp: blob.get_u32(entry + 4)?,
+            version: (blob.get_u16(entry + 8)?, blob.get_u16(entry + 10)?),
+            debug_type,
+            size_of_data,
+            address_of_raw_data: blob.get_u32(entry + 20)?,
+            pointer_to_raw_data,
+            info,
+        });
+    }
+    Ok(entries)
+}
@@ -0,0 +1,232 @@
+use super::{PeError, Result};
+
+pub(super) const INTEGER: u8 = 0x02;
+pub(super) const OCTET_STRING: u8 = 0x04;
+pub(super) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(super) const UTF8_STRING: u8 = 0x0c;
+pub(super) const PRINTABLE_STRING: u8 = 0x13;
+pub(super) const T61_STRING: u8 = 0x14;
+pub(super) const IA5_STRING: u8 = 0x16;
+pub(super) const UTC_TIME: u8 = 0x17;
+pub(super) const GENERALIZED_TIME: u8 = 0x18;
+pub(super) const BMP_STRING: u8 = 0x1e;
+pub(super) const SEQUENCE: u8 = 0x30;
+pub(super) const SET: u8 = 0x31;
+// Constructed context specific tags [0] and [1]
+pub(super) const CONTEXT_0: u8 = 0xa0;
+pub(super) const CONTEXT_1: u8 = 0xa1;
+
+// Attribute types of distinguished names
+const NAME_ATTRIBUTES: [(&str, &str); 13] = [
+    ("2.5.4.3", "CN"),
+    ("2.5.4.5", "serialNumber"),
+    ("2.5.4.6", "C"),
+    ("2.5.4.7", "L"),
+    ("2.5.4.8", "ST"),
+    ("2.5.4.9", "street"),
+    ("2.5.4.10", "O"),
+    ("2.5.4.11", "OU"),
+    ("2.5.4.15", "businessCategory"),
+    ("1.2.840.113549.1.9.1", "emailAddress"),
+    ("1.3.6.1.4.1.311.60.2.1.1", "jurisdictionL"),
+    ("1.3.6.1.4.1.311.60.2.1.2", "jurisdictionST"),
+    ("1.3.6.1.4.1.311.60.2.1.3", "jurisdictionC"),
+];
+
+/// A DER encoded element, `offset` is the position of its tag in the file
+#[derive(Clone, Copy)]
+pub(super) struct Element<'a> {
+    pub(super) tag: u8,
+    pub(super) content: &'a [u8],
+    pub(super) raw: &'a [u8],
+    pub(super) offset: usize,
+}
+
+impl<'a> Element<'a> {
+    /// Parse the first element of `data`, which starts at file position `offset`
+    pub(super) fn parse(data: &'a [u8], offset: usize) -> Result<Self> {
+        let tag = *data.first().ok_or(PeError::MalformedSignature)?;
+        // Multi byte tags are not used by the structures we decode
+        if tag & 0x1f == 0x1f {
+            return Err(PeError::MalformedSignature);
+        }
+        let first = *data.get(1).ok_or(PeError::MalformedSignature)? as usize;
+        let (len, header) = if first < 0x80 {
+            (first, 2)
+        } else {
+            // Long form, the indefinite form 0x80 is not valid DER
+            let count = first & 0x7f;
+            if count == 0 || count > 4 {
+                return Err(PeError::MalformedSignature);
+            }
+            let bytes = data.get(2..2 + count).ok_or(PeError::MalformedSignature)?;
+            let len = bytes.iter().fold(0usize, |len, b| len << 8 | *b as usize);
+            (len, 2 + count)
+        };
+        let raw = data
+            .get(..header + len)
+            .ok_or(PeError::MalformedSignature)?;
+        Ok(Self {
+            tag,
+            content: &raw[header..],
+            raw,
+            offset,
+        })
+    }
+
+    /// Parse the element and check its tag
+    pub(super) fn parse_tagged(data: &'a [u8], offset: usize, tag: u8) -> Result<Self> {
+        Self::parse(data, offset)?.expect(tag)
+    }
+
+    pub(super) fn expect(self, tag: u8) -> Result<Self> {
+        if self.tag == tag {
+            Ok(self)
+        } else {
+            Err(PeError::MalformedSignature)
+        }
+    }
+
+    /// Elements contained in a constructed element
+    pub(super) fn children(&self) -> Result<Vec<Element<'a>>> {
+        let mut children = Vec::new();
+        let mut offset = self.offset + self.raw.len() - self.content.len();
+        let mut rest = self.content;
+        while !rest.is_empty() {
+            let child = Element::parse(rest, offset)?;
+            offset += child.raw.len();
+            rest = &rest[child.raw.len()..];
+            children.push(child);
+        }
+        Ok(children)
+    }
+
+    /// Child at position `idx`, which must carry `tag`
+    pub(super) fn child(&self, idx: usize, tag: u8) -> Result<Element<'a>> {
+        self.children()?
+            .get(idx)
+            .ok_or(PeError::MalformedSignature)?
+            .expect(tag)
+    }
+
+    /// Object identifier in dotted notation
+    pub(super) fn oid(&self) -> Result<String> {
+        if self.tag != OBJECT_IDENTIFIER || self.content.is_empty() {
+            return Err(PeError::MalformedSignature);
+        }
+        let mut arcs = Vec::new();
+        let mut value = 0u64;
+        for b in self.content {
+            value = value << 7 | (b & 0x7f) as u64;
+            if b & 0x80 == 0 {
+                // The first value encodes the first two arcs
+                if arcs.is_empty() {
+                    let first = (value / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(value - 40 * first);
+                } else {
+                    arcs.push(value);
+                }
+                value = 0;
+            }
+        }
+        Ok(arcs
+            .iter()
+            .map(|arc| arc.to_string())
+            .collect::<Vec<_>>()
+            .join("."))
+    }
+
+    /// Content bytes as hex string
+    pub(super) fn hex(&self) -> String {
+        hex_string(self.content)
+    }
+
+    /// Integer as hex string, without the leading zero byte of positive numbers
+    pub(super) fn serial(&self) -> String {
+        match self.content {
+            [0, rest @ ..] if !rest.is_empty() => hex_string(rest),
+            content => hex_string(content),
+        }
+    }
+
+    /// Text of the various ASN.1 string types
+    pub(super) fn string(&self) -> String {
+        match self.tag {
+            BMP_STRING => {
+                let chars: Vec<u16> = self
+                    .content
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16_lossy(&chars)
+            }
+            UTF8_STRING | PRINTABLE_STRING | T61_STRING | IA5_STRING => {
+                String::from_utf8_lossy(self.content).to_string()
+            }
+            _ => format!("#{}", self.hex()),
+        }
+    }
+
+    /// UTC or generalized time as "YYYY-MM-DD HH:MM:SS UTC"
+    pub(super) fn time(&self) -> Result<String> {
+        let text = std::str::from_utf8(self.content).map_err(|_| PeError::MalformedSignature)?;
+        let digits = text.trim_end_matches('Z');
+        let (year, rest) = match self.tag {
+            UTC_TIME => {
+                let year: u32 = digits
+                    .get(..2)
+                    .and_then(|y| y.parse().ok())
+                    .ok_or(PeError::MalformedSignature)?;
+                // Two digit years below 50 belong to this century
+                (
+                    if year < 50 { 2000 + year } else { 1900 + year },
+                    &digits[2..],
+                )
+            }
+            GENERALIZED_TIME => (
+                digits
+                    .get(..4)
+                    .and_then(|y| y.parse().ok())
+                    .ok_or(PeError::MalformedSignature)?,
+                &digits[4..],
+            ),
+            _ => return Err(PeError::MalformedSignature),
+        };
+        let field = |idx: usize| rest.get(2 * idx..2 * idx + 2).unwrap_or("00");
+        Ok(format!(
+            "{year:04}-{}-{} {}:{}:{} UTC",
+            field(0),
+            field(1),
+            field(2),
+            field(3),
+            field(4)
+        ))
+    }
+}
+
+pub(crate) fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Distinguished name like "C=US, O=Example, CN=Example Root"
+pub(super) fn name_to_string(name: &Element) -> Result<String> {
+    let mut parts = Vec::new();
+    for rdn in name.expect(SEQUENCE)?.children()? {
+        for attribute in rdn.expect(SET)?.children()? {
+            let oid = attribute.child(0, OBJECT_IDENTIFIER)?.oid()?;
+            let value = attribute
+                .children()?
+                .get(1)
+                .ok_or(PeError::MalformedSignature)?
+                .string();
+            let key = NAME_ATTRIBUTES
+                .iter()
+                .find(|(attribute_oid, _)| *attribute_oid == oid)
+                .map(|(_, key)| key.to_string())
+                .unwrap_or(oid);
+            parts.push(format!("{key}={value}"));
+        }
+    }
+    Ok(parts.join(", "))
+}
@@ -0,0 +1,957 @@
+use super::der::hex_string;
+use super::{flags_as_string, PeBinary, PeError, Result};
+use crate::blob::Blob;
+use sha1::{Digest, Sha1};
+
+const CLI_HEADER_DIRECTORY: usize = 14;
+
+// "BSJB" at the start of the metadata root
+const METADATA_SIGNATURE: u32 = 0x424a5342;
+
+const CLI_FLAGS_NAMES: [(u32, &str); 7] = [
+    (0x1, "ILONLY"),
+    (0x2, "32BITREQUIRED"),
+    (0x4, "IL_LIBRARY"),
+    (0x8, "STRONGNAMESIGNED"),
+    (0x10, "NATIVE_ENTRYPOINT"),
+    (0x10000, "TRACKDEBUGDATA"),
+    (0x20000, "32BITPREFERRED"),
+];
+
+// Metadata tables, the number is the table id and the upper byte of a token
+const MODULE: usize = 0x00;
+const TYPE_REF: usize = 0x01;
+const TYPE_DEF: usize = 0x02;
+const FIELD: usize = 0x04;
+const METHOD_DEF: usize = 0x06;
+const PARAM: usize = 0x08;
+const INTERFACE_IMPL: usize = 0x09;
+const MEMBER_REF: usize = 0x0a;
+const DECL_SECURITY: usize = 0x0e;
+const STAND_ALONE_SIG: usize = 0x11;
+const EVENT: usize = 0x14;
+const PROPERTY: usize = 0x17;
+const MODULE_REF: usize = 0x1a;
+const TYPE_SPEC: usize = 0x1b;
+const ASSEMBLY: usize = 0x20;
+const ASSEMBLY_REF: usize = 0x23;
+const FILE: usize = 0x26;
+const EXPORTED_TYPE: usize = 0x27;
+const MANIFEST_RESOURCE: usize = 0x28;
+const GENERIC_PARAM: usize = 0x2a;
+const METHOD_SPEC: usize = 0x2b;
+const GENERIC_PARAM_CONSTRAINT: usize = 0x2c;
+// Placeholder for unused tags of coded indices, this table never has rows
+const UNUSED: usize = 0x3f;
+
+/// An index into one of several tables, the lower bits select the table
+struct CodedIndex {
+    tag_bits: u32,
+    tables: &'static [usize],
+}
+
+const TYPE_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TYPE_DEF, TYPE_REF, TYPE_SPEC],
+};
+const HAS_CONSTANT: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[FIELD, PARAM, PROPERTY],
+};
+const HAS_CUSTOM_ATTRIBUTE: CodedIndex = CodedIndex {
+    tag_bits: 5,
+    tables: &[
+        METHOD_DEF,
+        FIELD,
+        TYPE_REF,
+        TYPE_DEF,
+        PARAM,
+        INTERFACE_IMPL,
+        MEMBER_REF,
+        MODULE,
+        DECL_SECURITY,
+        PROPERTY,
+        EVENT,
+        STAND_ALONE_SIG,
+        MODULE_REF,
+        TYPE_SPEC,
+        ASSEMBLY,
+        ASSEMBLY_REF,
+        FILE,
+        EXPORTED_TYPE,
+        MANIFEST_RESOURCE,
+        GENERIC_PARAM,
+        GENERIC_PARAM_CONSTRAINT,
+        METHOD_SPEC,
+    ],
+};
+const HAS_FIELD_MARSHAL: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[FIELD, PARAM],
+};
+const HAS_DECL_SECURITY: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TYPE_DEF, METHOD_DEF, ASSEMBLY],
+};
+const MEMBER_REF_PARENT: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[TYPE_DEF, TYPE_REF, MODULE_REF, METHOD_DEF, TYPE_SPEC],
+};
+const HAS_SEMANTICS: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[EVENT, PROPERTY],
+};
+const METHOD_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[METHOD_DEF, MEMBER_REF],
+};
+const MEMBER_FORWARDED: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[FIELD, METHOD_DEF],
+};
+const IMPLEMENTATION: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[FILE, ASSEMBLY_REF, EXPORTED_TYPE],
+};
+const CUSTOM_ATTRIBUTE_TYPE: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[UNUSED, UNUSED, METHOD_DEF, MEMBER_REF, UNUSED],
+};
+const RESOLUTION_SCOPE: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[MODULE, MODULE_REF, ASSEMBLY_REF, TYPE_REF],
+};
+const TYPE_OR_METHOD_DEF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TYPE_DEF, METHOD_DEF],
+};
+
+#[derive(Clone, Copy)]
+enum Column {
+    U16,
+    U32,
+    StringIndex,
+    GuidIndex,
+    BlobIndex,
+    TableIndex(usize),
+    Coded(&'static CodedIndex),
+}
+
+use Column::*;
+
+// Columns of the tables defined by ECMA-335, indexed by table id
+const SCHEMA: [&[Column]; 0x2d] = [
+    // Module
+    &[U16, StringIndex, GuidIndex, GuidIndex, GuidIndex],
+    // TypeRef
+    &[Coded(&RESOLUTION_SCOPE), StringIndex, StringIndex],
+    // TypeDef
+    &[
+        U32,
+        StringIndex,
+        StringIndex,
+        Coded(&TYPE_DEF_OR_REF),
+        TableIndex(FIELD),
+        TableIndex(METHOD_DEF),
+    ],
+    // FieldPtr
+    &[TableIndex(FIELD)],
+    // Field
+    &[U16, StringIndex, BlobIndex],
+    // MethodPtr
+    &[TableIndex(METHOD_DEF)],
+    // MethodDef
+    &[U32, U16, U16, StringIndex, BlobIndex, TableIndex(PARAM)],
+    // ParamPtr
+    &[TableIndex(PARAM)],
+    // Param
+    &[U16, U16, StringIndex],
+    // InterfaceImpl
+    &[TableIndex(TYPE_DEF), Coded(&TYPE_DEF_OR_REF)],
+    // MemberRef
+    &[Coded(&MEMBER_REF_PARENT), StringIndex, BlobIndex],
+    // Constant, the type is a byte followed by a padding byte
+    &[U16, Coded(&HAS_CONSTANT), BlobIndex],
+    // CustomAttribute
+    &[
+        Coded(&HAS_CUSTOM_ATTRIBUTE),
+        Coded(&CUSTOM_ATTRIBUTE_TYPE),
+        BlobIndex,
+    ],
+    // FieldMarshal
+    &[Coded(&HAS_FIELD_MARSHAL), BlobIndex],
+    // DeclSecurity
+    &[U16, Coded(&HAS_DECL_SECURITY), BlobIndex],
+    // ClassLayout
+    &[U16, U32, TableIndex(TYPE_DEF)],
+    // FieldLayout
+    &[U32, TableIndex(FIELD)],
+    // StandAloneSig
+    &[BlobIndex],
+    // EventMap
+    &[TableIndex(TYPE_DEF), TableIndex(EVENT)],
+    // EventPtr
+    &[TableIndex(EVENT)],
+    // Event
+    &[U16, StringIndex, Coded(&TYPE_DEF_OR_REF)],
+    // PropertyMap
+    &[TableIndex(TYPE_DEF), TableIndex(PROPERTY)],
+    // PropertyPtr
+    &[TableIndex(PROPERTY)],
+    // Property
+    &[U16, StringIndex, BlobIndex],
+    // MethodSemantics
+    &[U16, TableIndex(METHOD_DEF), Coded(&HAS_SEMANTICS)],
+    // MethodImpl
+    &[
+        TableIndex(TYPE_DEF),
+        Coded(&METHOD_DEF_OR_REF),
+        Coded(&METHOD_DEF_OR_REF),
+    ],
+    // ModuleRef
+    &[StringIndex],
+    // TypeSpec
+    &[BlobIndex],
+    // ImplMap
+    &[
+        U16,
+        Coded(&MEMBER_FORWARDED),
+        StringIndex,
+        TableIndex(MODULE_REF),
+    ],
+    // FieldRVA
+    &[U32, TableIndex(FIELD)],
+    // EncLog
+    &[U32, U32],
+    // EncMap
+    &[U32],
+    // Assembly
+    &[
+        U32,
+        U16,
+        U16,
+        U16,
+        U16,
+        U32,
+        BlobIndex,
+        StringIndex,
+        StringIndex,
+    ],
+    // AssemblyProcessor
+    &[U32],
+    // AssemblyOS
+    &[U32, U32, U32],
+    // AssemblyRef
+    &[
+        U16,
+        U16,
+        U16,
+        U16,
+        U32,
+        BlobIndex,
+        StringIndex,
+        StringIndex,
+        BlobIndex,
+    ],
+    // AssemblyRefProcessor
+    &[U32, TableIndex(ASSEMBLY_REF)],
+    // AssemblyRefOS
+    &[U32, U32, U32, TableIndex(ASSEMBLY_REF)],
+    // File
+    &[U32, StringIndex, BlobIndex],
+    // ExportedType
+    &[U32, U32, StringIndex, StringIndex, Coded(&IMPLEMENTATION)],
+    // ManifestResource
+    &[U32, U32, StringIndex, Coded(&IMPLEMENTATION)],
+    // NestedClass
+    &[TableIndex(TYPE_DEF), TableIndex(TYPE_DEF)],
+    // GenericParam
+    &[U16, U16, Coded(&TYPE_OR_METHOD_DEF), StringIndex],
+    // MethodSpec
+    &[Coded(&METHOD_DEF_OR_REF), BlobIndex],
+    // GenericParamConstraint
+    &[TableIndex(GENERIC_PARAM), Coded(&TYPE_DEF_OR_REF)],
+];
+
+/// The CLI header referenced by the COM descriptor data directory
+pub struct CliHeader {
+    pub(super) runtime_version: (u16, u16),
+    pub(super) metadata_rva: u32,
+    pub(super) metadata_size: u32,
+    pub(super) flags: u32,
+    // Metadata token of the entry point method, or an RVA for native entry points
+    pub(super) entry_point: u32,
+    pub(super) resources: (u32, u32),
+    pub(super) strong_name_signature: (u32, u32),
+}
+
+/// A metadata stream, the offset is a file offset
+pub struct Stream {
+    pub(super) name: String,
+    pub(super) offset: usize,
+    pub(super) size: usize,
+}
+
+pub struct TypeDef {
+    pub(super) token: u32,
+    pub(super) flags: u32,
+    pub(super) namespace: String,
+    pub(super) name: String,
+    pub(super) extends: String,
+    // Range of indices into the method list
+    pub(super) methods: std::ops::Range<usize>,
+}
+
+impl TypeDef {
+    pub(super) fn full_name(&self) -> String {
+        full_name(&self.namespace, &self.name)
+    }
+
+    pub(super) fn details(&self) -> String {
+        let visibility = match self.flags & 0x7 {
+            0 => "internal",
+            1 => "public",
+            2 => "nested public",
+            3 => "nested private",
+            4 => "nested family",
+            5 => "nested assembly",
+            6 => "nested family and assembly",
+            _ => "nested family or assembly",
+        };
+        let mut details = visibility.to_string();
+        if self.flags & 0x80 != 0 {
+            details.push_str(" abstract");
+        }
+        if self.flags & 0x100 != 0 {
+            details.push_str(" sealed");
+        }
+        details.push_str(if self.flags & 0x20 != 0 {
+            " interface"
+        } else {
+            " class"
+        });
+        if !self.extends.is_empty() {
+            details.push_str(&format!(", extends {}", self.extends));
+        }
+        details
+    }
+}
+
+pub struct MethodDef {
+    pub(super) token: u32,
+    pub(super) rva: u32,
+    pub(super) impl_flags: u16,
+    pub(super) flags: u16,
+    pub(super) name: String,
+    // File offset and size of the IL method body
+    pub(super) body: Option<(usize, usize)>,
+}
+
+impl MethodDef {
+    pub(super) fn details(&self) -> String {
+        let access = match self.flags & 0x7 {
+            0 => "compiler controlled",
+            1 => "private",
+            2 => "family and assembly",
+            3 => "internal",
+            4 => "protected",
+            5 => "protected internal",
+            _ => "public",
+        };
+        let mut details = access.to_string();
+        for (flag, name) in [
+            (0x10, "static"),
+            (0x20, "final"),
+            (0x40, "virtual"),
+            (0x400, "abstract"),
+            (0x2000, "pinvoke"),
+        ] {
+            if self.flags & flag != 0 {
+                details.push(' ');
+                details.push_str(name);
+            }
+        }
+        match self.impl_flags & 0x3 {
+            1 => details.push_str(", native"),
+            3 => details.push_str(", runtime"),
+            _ => {}
+        }
+        if self.impl_flags & 0x1000 != 0 {
+            details.push_str(", internal call");
+        }
+        if self.rva != 0 {
+            details.push_str(&format!(", RVA 0x{:08x}", self.rva));
+        }
+        if let Some((_, size)) = self.body {
+            details.push_str(&format!(", {size} bytes IL"));
+        }
+        details
+    }
+}
+
+pub struct TypeRef {
+    pub(super) token: u32,
+    pub(super) scope: String,
+    pub(super) namespace: String,
+    pub(super) name: String,
+}
+
+pub struct MemberRef {
+    pub(super) token: u32,
+    pub(super) class: String,
+    pub(super) name: String,
+}
+
+pub struct AssemblyRef {
+    pub(super) token: u32,
+    pub(super) version: (u16, u16, u16, u16),
+    pub(super) name: String,
+    pub(super) culture: String,
+    pub(super) public_key_token: String,
+}
+
+impl AssemblyRef {
+    pub(super) fn details(&self) -> String {
+        let (major, minor, build, revision) = self.version;
+        let mut details = format!("version {major}.{minor}.{build}.{revision}");
+        if !self.culture.is_empty() {
+            details.push_str(&format!(", culture {}", self.culture));
+        }
+        if !self.public_key_token.is_empty() {
+            details.push_str(&format!(", public key token {}", self.public_key_token));
+        }
+        details
+    }
+}
+
+/// CLI header and the decoded metadata of a managed assembly
+pub struct DotNet {
+    pub(super) cli_header: CliHeader,
+    pub(super) metadata_version: String,
+    pub(super) streams: Vec<Stream>,
+    pub(super) assembly: Option<AssemblyRef>,
+    pub(super) assembly_refs: Vec<AssemblyRef>,
+    pub(super) type_refs: Vec<TypeRef>,
+    pub(super) type_defs: Vec<TypeDef>,
+    pub(super) method_defs: Vec<MethodDef>,
+    pub(super) member_refs: Vec<MemberRef>,
+    pub(super) user_strings: Vec<String>,
+}
+
+impl DotNet {
+    pub(super) fn info(&self) -> Vec<(String, String)> {
+        let header = &self.cli_header;
+        let mut info = vec![
+            (
+                "CLR runtime version".to_string(),
+                format!("{}.{}", header.runtime_version.0, header.runtime_version.1),
+            ),
+            (
+                "CLR metadata version".to_string(),
+                self.metadata_version.clone(),
+            ),
+            (
+                "CLR flags".to_string(),
+                format!(
+                    "0x{:08x} {}",
+                    header.flags,
+                    flags_as_string(header.flags, &CLI_FLAGS_NAMES)
+                ),
+            ),
+        ];
+        if let Some(assembly) = &self.assembly {
+            info.push((
+                "CLR assembly".to_string(),
+                format!("{}, {}", assembly.name, assembly.details()),
+            ));
+        }
+        if header.entry_point != 0 {
+            let entry_point = if header.flags & 0x10 != 0 {
+                format!("RVA 0x{:08x}", header.entry_point)
+            } else {
+                match self.method_name(header.entry_point) {
+                    Some(name) => format!("0x{:08x} {name}", header.entry_point),
+                    None => format!("0x{:08x}", header.entry_point),
+                }
+            };
+            info.push(("CLR entry point".to_string(), entry_point));
+        }
+        if header.strong_name_signature.0 != 0 {
+            info.push((
+                "CLR strong name signature".to_string(),
+                format!(
+                    "RVA 0x{:08x}, {} bytes",
+                    header.strong_name_signature.0, header.strong_name_signature.1
+                ),
+            ));
+        }
+        if header.resources.0 != 0 {
+            info.push((
+                "CLR resources".to_string(),
+                format!(
+                    "RVA 0x{:08x}, {} bytes",
+                    header.resources.0, header.resources.1
+                ),
+            ));
+        }
+        info.push((
+            "CLR metadata".to_string(),
+            format!(
+                "RVA 0x{:08x}, {} bytes, {} types, {} methods",
+                header.metadata_rva,
+                header.metadata_size,
+                self.type_defs.len(),
+                self.method_defs.len()
+            ),
+        ));
+        info
+    }
+
+    /// "Type::Method" for a MethodDef token
+    pub(super) fn method_name(&self, token: u32) -> Option<String> {
+        if token as usize >> 24 != METHOD_DEF {
+            return None;
+        }
+        let idx = (token & 0xffffff) as usize;
+        let method = self.method_defs.get(idx.checked_sub(1)?)?;
+        let owner = self
+            .type_defs
+            .iter()
+            .find(|type_def| type_def.methods.contains(&(idx - 1)));
+        Some(match owner {
+            Some(owner) => format!("{}::{}", owner.full_name(), method.name),
+            None => method.name.clone(),
+        })
+    }
+}
+
+fn full_name(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{namespace}.{name}")
+    }
+}
+
+fn token(table: usize, row: usize) -> u32 {
+    (table as u32) << 24 | row as u32
+}
+
+/// Access to the metadata tables and heaps
+struct Tables<'a> {
+    blob: &'a Blob,
+    rows: [u32; 64],
+    // File offset of the first row of each table
+    offsets: [usize; 64],
+    heap_sizes: u8,
+    strings: Option<&'a Stream>,
+    blobs: Option<&'a Stream>,
+}
+
+impl<'a> Tables<'a> {
+    fn new(blob: &'a Blob, streams: &'a [Stream]) -> Result<Option<Self>> {
+        let stream = |name: &str| streams.iter().find(|stream| stream.name == name);
+        // "#-" is the uncompressed variant of the table stream
+        let Some(table_stream) = stream("#~").or_else(|| stream("#-")) else {
+            return Ok(None);
+        };
+        let heap_sizes = blob.get_u8(table_stream.offset + 6)?;
+        let valid = blob.get_u64(table_stream.offset + 8)?;
+        let mut rows = [0; 64];
+        let mut offset = table_stream.offset + 24;
+        for (table, rows) in rows.iter_mut().enumerate() {
+            if valid & (1 << table) != 0 {
+                *rows = blob.get_u32(offset)?;
+                offset += 4;
+            }
+        }
+        // Uncompressed streams may carry four bytes of extra data
+        if heap_sizes & 0x40 != 0 {
+            offset += 4;
+        }
+        let mut tables = Self {
+            blob,
+            rows,
+            offsets: [0; 64],
+            heap_sizes,
+            strings: stream("#Strings"),
+            blobs: stream("#Blob"),
+        };
+        let end = table_stream.offset + table_stream.size;
+        for table in 0..SCHEMA.len() {
+            tables.offsets[table] = offset;
+            let size = (tables.row_size(table) as u64).saturating_mul(tables.rows[table] as u64);
+            offset = offset.saturating_add(size as usize);
+            if offset > end {
+                return Err(PeError::MalformedMetadata);
+            }
+        }
+        Ok(Some(tables))
+    }
+
+    fn index_size(&self, table: usize) -> usize {
+        if self.rows[table] > 0xffff {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn column_size(&self, column: Column) -> usize {
+        match column {
+            U16 => 2,
+            U32 => 4,
+            StringIndex if self.heap_sizes & 0x1 != 0 => 4,
+            GuidIndex if self.heap_sizes & 0x2 != 0 => 4,
+            BlobIndex if self.heap_sizes & 0x4 != 0 => 4,
+            StringIndex | GuidIndex | BlobIndex => 2,
+            TableIndex(table) => self.index_size(table),
+            Coded(coded) => {
+                let max_rows = coded
+                    .tables
+                    .iter()
+                    .map(|table| self.rows[*table])
+                    .max()
+                    .unwrap_or_default();
+                if max_rows < 1 << (16 - coded.tag_bits) {
+                    2
+                } else {
+                    4
+                }
+            }
+        }
+    }
+
+    fn row_size(&self, table: usize) -> usize {
+        SCHEMA[table]
+            .iter()
+            .map(|column| self.column_size(*column))
+            .sum()
+    }
+
+    /// Value of `column` in row `row` of `table`, rows are counted from one
+    fn get(&self, table: usize, row: usize, column: usize) -> Result<u32> {
+        if row == 0 || row > self.rows[table] as usize {
+            return Err(PeError::MalformedMetadata);
+        }
+        let columns = SCHEMA[table];
+        let mut offset = self.offsets[table] + (row - 1) * self.row_size(table);
+        for column in &columns[..column] {
+            offset += self.column_size(*column);
+        }
+        match self.column_size(columns[column]) {
+            2 => Ok(self.blob.get_u16(offset)? as u32),
+            _ => Ok(self.blob.get_u32(offset)?),
+        }
+    }
+
+    fn string(&self, table: usize, row: usize, column: usize) -> Result<String> {
+        let index = self.get(table, row, column)? as usize;
+        let strings = self.strings.ok_or(PeError::MalformedMetadata)?;
+        if index >= strings.size {
+            return Err(PeError::MalformedMetadata);
+        }
+        let string = self.blob.get_cstr(strings.offset + index)?;
+        Ok(string.to_string_lossy().to_string())
+    }
+
+    /// Like `string`, but a broken index is shown instead of failing the whole table
+    fn string_or_invalid(&self, table: usize, row: usize, column: usize) -> String {
+        self.string(table, row, column)
+            .unwrap_or_else(|_| "invalid string".to_string())
+    }
+
+    fn blob_bytes(&self, table: usize, row: usize, column: usize) -> Result<&'a [u8]> {
+        let index = self.get(table, row, column)? as usize;
+        let blobs = self.blobs.ok_or(PeError::MalformedMetadata)?;
+        if index >= blobs.size {
+            return Err(PeError::MalformedMetadata);
+        }
+        let (len, header) = compressed_length(self.blob, blobs.offset + index)?;
+        Ok(self.blob.get_slice(blobs.offset + index + header, len)?)
+    }
+
+    /// Table and row referenced by a coded index
+    fn coded(&self, table: usize, row: usize, column: usize) -> Result<(usize, usize)> {
+        let Coded(coded) = SCHEMA[table][column] else {
+            return Err(PeError::InternalError);
+        };
+        let value = self.get(table, row, column)?;
+        let tag = (value & ((1 << coded.tag_bits) - 1)) as usize;
+        let table = *coded.tables.get(tag).ok_or(PeError::MalformedMetadata)?;
+        Ok((table, (value >> coded.tag_bits) as usize))
+    }
+
+    /// Readable name of a type, module or assembly reference
+    fn name_of(&self, table: usize, row: usize) -> Result<String> {
+        if row == 0 {
+            return Ok(String::new());
+        }
+        match table {
+            TYPE_DEF => Ok(full_name(
+                &self.string(TYPE_DEF, row, 2)?,
+                &self.string(TYPE_DEF, row, 1)?,
+            )),
+            TYPE_REF => Ok(full_name(
+                &self.string(TYPE_REF, row, 2)?,
+                &self.string(TYPE_REF, row, 1)?,
+            )),
+            MODULE => self.string(MODULE, row, 1),
+            MODULE_REF => self.string(MODULE_REF, row, 0),
+            ASSEMBLY_REF => self.string(ASSEMBLY_REF, row, 6),
+            METHOD_DEF => self.string(METHOD_DEF, row, 3),
+            TYPE_SPEC => Ok(format!("TypeSpec 0x{:08x}", token(TYPE_SPEC, row))),
+            _ => Ok(format!("0x{:08x}", token(table, row))),
+        }
+    }
+
+    /// Name of the row referenced by a coded index, a broken reference is shown instead of
+    /// failing the whole table
+    fn coded_name(&self, table: usize, row: usize, column: usize) -> String {
+        self.coded(table, row, column)
+            .and_then(|(table, row)| self.name_of(table, row))
+            .unwrap_or_else(|_| "invalid reference".to_string())
+    }
+
+    fn assembly(&self, table: usize, row: usize) -> Result<AssemblyRef> {
+        // The Assembly table has an additional hash algorithm column in front
+        let first = if table == ASSEMBLY { 1 } else { 0 };
+        let flags = self.get(table, row, first + 4)?;
+        let public_key = self.blob_bytes(table, row, first + 5)?;
+        // Full public keys are identified by the last eight bytes of their SHA-1 hash
+        let public_key_token = if flags & 0x1 != 0 || table == ASSEMBLY {
+            if public_key.is_empty() {
+                String::new()
+            } else {
+                let hash = Sha1::digest(public_key);
+                let mut token = hash[hash.len() - 8..].to_vec();
+                token.reverse();
+                hex_string(&token)
+            }
+        } else {
+            hex_string(public_key)
+        };
+        Ok(AssemblyRef {
+            token: token(table, row),
+            version: (
+                self.get(table, row, first)? as u16,
+                self.get(table, row, first + 1)? as u16,
+                self.get(table, row, first + 2)? as u16,
+                self.get(table, row, first + 3)? as u16,
+            ),
+            name: self.string(table, row, first + 6)?,
+            culture: self.string(table, row, first + 7)?,
+            public_key_token,
+        })
+    }
+}
+
+/// Length prefix of blob and user string heap entries, returns length and prefix size
+fn compressed_length(blob: &Blob, offset: usize) -> Result<(usize, usize)> {
+    let first = blob.get_u8(offset)? as usize;
+    if first & 0x80 == 0 {
+        Ok((first, 1))
+    } else if first & 0xc0 == 0x80 {
+        Ok(((first & 0x3f) << 8 | blob.get_u8(offset + 1)? as usize, 2))
+    } else if first & 0xe0 == 0xc0 {
+        let rest = blob.get_slice(offset + 1, 3)?;
+        Ok((
+            (first & 0x1f) << 24
+                | (rest[0] as usize) << 16
+                | (rest[1] as usize) << 8
+                | rest[2] as usize,
+            4,
+        ))
+    } else {
+        Err(PeError::MalformedMetadata)
+    }
+}
+
+/// Strings of the user string heap, UTF-16 with a trailing flag byte. A broken entry ends the
+/// heap, the strings before it are kept.
+fn user_strings(blob: &Blob, stream: &Stream) -> Vec<String> {
+    let mut strings = Vec::new();
+    // The first entry is always the empty string
+    let mut offset = stream.offset + 1;
+    let end = stream.offset + stream.size;
+    while offset < end {
+        let Ok((len, header)) = compressed_length(blob, offset) else {
+            break;
+        };
+        if len == 0 {
+            // Heaps are padded with zero bytes
+            offset += 1;
+            continue;
+        }
+        let Ok(data) = blob.get_slice(offset + header, len) else {
+            break;
+        };
+        let chars: Vec<u16> = data[..len - 1]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        strings.push(String::from_utf16_lossy(&chars));
+        offset += header + len;
+    }
+    strings
+}
+
+/// File offset and size of an IL method body, tiny or fat header
+fn method_body(binary: &PeBinary, rva: u32) -> Result<Option<(usize, usize)>> {
+    if rva == 0 {
+        return Ok(None);
+    }
+    let Some(offset) = binary.rva_to_offset(rva)? else {
+        return Ok(None);
+    };
+    let first = binary.blob.get_u8(offset)?;
+    let body = match first & 0x3 {
+        // Tiny header, the code size is in the upper six bits
+        0x2 => Some((offset, 1 + (first >> 2) as usize)),
+        0x3 => {
+            let header_size = 4 * (binary.blob.get_u16(offset)? >> 12) as usize;
+            let code_size = binary.blob.get_u32(offset + 4)? as usize;
+            Some((offset, header_size + code_size))
+        }
+        _ => None,
+    };
+    Ok(body)
+}
+
+fn parse_cli_header(binary: &PeBinary) -> Result<Option<CliHeader>> {
+    let Some(directory) = binary.data_directory(CLI_HEADER_DIRECTORY) else {
+        return Ok(None);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let directory = |field: usize| -> Result<(u32, u32)> {
+        Ok((
+            blob.get_u32(offset + field)?,
+            blob.get_u32(offset + field + 4)?,
+        ))
+    };
+    let (metadata_rva, metadata_size) = directory(8)?;
+    Ok(Some(CliHeader {
+        runtime_version: (blob.get_u16(offset + 4)?, blob.get_u16(offset + 6)?),
+        metadata_rva,
+        metadata_size,
+        flags: blob.get_u32(offset + 16)?,
+        entry_point: blob.get_u32(offset + 20)?,
+        resources: directory(24)?,
+        strong_name_signature: directory(32)?,
+    }))
+}
+
+/// Decode the CLI header and the metadata of managed code, sections have to be loaded
+pub(super) fn parse_dotnet(binary: &PeBinary) -> Result<Option<DotNet>> {
+    let Some(cli_header) = parse_cli_header(binary)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let root = binary
+        .rva_to_offset(cli_header.metadata_rva)?
+        .ok_or(PeError::MalformedMetadata)?;
+    if blob.get_u32(root)? != METADATA_SIGNATURE {
+        return Err(PeError::MalformedMetadata);
+    }
+    let version_length = blob.get_u32(root + 12)? as usize;
+    let version = blob.get_slice(root + 16, version_length)?;
+    let metadata_version = String::from_utf8_lossy(version)
+        .trim_end_matches('\0')
+        .to_string();
+    let mut offset = root + 16 + version_length;
+    let stream_count = blob.get_u16(offset + 2)?;
+    offset += 4;
+    let mut streams = Vec::new();
+    for _ in 0..stream_count {
+        let stream_offset = blob.get_u32(offset)? as usize;
+        let size = blob.get_u32(offset + 4)? as usize;
+        let name = blob.get_cstr(offset + 8)?;
+        // Names are zero terminated and padded to four bytes
+        offset += 8 + (name.to_bytes().len() + 4) / 4 * 4;
+        streams.push(Stream {
+            name: name.to_string_lossy().to_string(),
+            offset: root + stream_offset,
+            size,
+        });
+    }
+
+    let mut dotnet = DotNet {
+        cli_header,
+        metadata_version,
+        streams: Vec::new(),
+        assembly: None,
+        assembly_refs: Vec::new(),
+        type_refs: Vec::new(),
+        type_defs: Vec::new(),
+        method_defs: Vec::new(),
+        member_refs: Vec::new(),
+        user_strings: Vec::new(),
+    };
+    if let Some(stream) = streams.iter().find(|stream| stream.name == "#US") {
+        dotnet.user_strings = user_strings(blob, stream);
+    }
+    if let Some(tables) = Tables::new(blob, &streams)? {
+        // Broken names are marked and broken assembly references skipped, the other
+        // entries are still shown
+        if tables.rows[ASSEMBLY] > 0 {
+            dotnet.assembly = tables.assembly(ASSEMBLY, 1).ok();
+        }
+        for row in 1..=tables.rows[ASSEMBLY_REF] as usize {
+            if let Ok(assembly_ref) = tables.assembly(ASSEMBLY_REF, row) {
+                dotnet.assembly_refs.push(assembly_ref);
+            }
+        }
+        for row in 1..=tables.rows[TYPE_REF] as usize {
+            dotnet.type_refs.push(TypeRef {
+                token: token(TYPE_REF, row),
+                scope: tables.coded_name(TYPE_REF, row, 0),
+                namespace: tables.string_or_invalid(TYPE_REF, row, 2),
+                name: tables.string_or_invalid(TYPE_REF, row, 1),
+            });
+        }
+        let method_count = tables.rows[METHOD_DEF] as usize;
+        for row in 1..=method_count {
+            let rva = tables.get(METHOD_DEF, row, 0)?;
+            dotnet.method_defs.push(MethodDef {
+                token: token(METHOD_DEF, row),
+                rva,
+                impl_flags: tables.get(METHOD_DEF, row, 1)? as u16,
+                flags: tables.get(METHOD_DEF, row, 2)? as u16,
+                name: tables.string_or_invalid(METHOD_DEF, row, 3),
+                body: method_body(binary, rva).unwrap_or(None),
+            });
+        }
+        // A type owns the methods up to the start of the method list of the next type
+        let type_count = tables.rows[TYPE_DEF] as usize;
+        for row in 1..=type_count {
+            let start = tables.get(TYPE_DEF, row, 5)? as usize;
+            let end = if row < type_count {
+                tables.get(TYPE_DEF, row + 1, 5)? as usize
+            } else {
+                method_count + 1
+            };
+            let start = start.clamp(1, method_count + 1) - 1;
+            let end = end.clamp(1, method_count + 1) - 1;
+            dotnet.type_defs.push(TypeDef {
+                token: token(TYPE_DEF, row),
+                flags: tables.get(TYPE_DEF, row, 0)?,
+                namespace: tables.string_or_invalid(TYPE_DEF, row, 2),
+                name: tables.string_or_invalid(TYPE_DEF, row, 1),
+                extends: tables.coded_name(TYPE_DEF, row, 3),
+                methods: start..end.max(start),
+            });
+        }
+        for row in 1..=tables.rows[MEMBER_REF] as usize {
+            dotnet.member_refs.push(MemberRef {
+                token: token(MEMBER_REF, row),
+                class: tables.coded_name(MEMBER_REF, row, 0),
+                name: tables.string_or_invalid(MEMBER_REF, row, 1),
+            });
+        }
+    }
+    dotnet.streams = streams;
+    Ok(Some(dotnet))
+}
@@ -0,0 +1,260 @@
+use super::{MachineType, PeBinary, Result};
+use crate::blob::Blob;
+
+const EXCEPTION_DIRECTORY: usize = 3;
+
+// Flags of x64 unwind information
+const UNW_FLAG_EHANDLER: u8 = 0x1;
+const UNW_FLAG_UHANDLER: u8 = 0x2;
+const UNW_FLAG_CHAININFO: u8 = 0x4;
+
+const X64_REGISTERS: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+/// One step of an x64 prolog, `offset` is the end of the instruction within the prolog
+#[derive(Clone)]
+pub struct UnwindCode {
+    pub offset: u8,
+    pub operation: String,
+}
+
+/// x64 UNWIND_INFO describing how to undo the prolog of a function
+#[derive(Clone)]
+pub struct UnwindInfo {
+    pub version: u8,
+    pub flags: u8,
+    pub prolog_size: u8,
+    // Register used as frame pointer, zero if the function uses none
+    pub frame_register: u8,
+    // Offset of the frame pointer from the stack pointer in bytes
+    pub frame_offset: u32,
+    pub codes: Vec<UnwindCode>,
+    // RVA of the language specific handler and its data
+    pub exception_handler: Option<u32>,
+    pub handler_data: Option<u32>,
+    // Begin address of the function this entry continues
+    pub chained_function: Option<u32>,
+}
+
+impl UnwindInfo {
+    pub fn frame_register_name(&self) -> Option<&'static str> {
+        if self.frame_register == 0 {
+            None
+        } else {
+            Some(X64_REGISTERS[self.frame_register as usize & 0xf])
+        }
+    }
+
+    fn flags_as_string(&self) -> String {
+        let mut flags = Vec::new();
+        if self.flags & UNW_FLAG_EHANDLER != 0 {
+            flags.push("EHANDLER");
+        }
+        if self.flags & UNW_FLAG_UHANDLER != 0 {
+            flags.push("UHANDLER");
+        }
+        if self.flags & UNW_FLAG_CHAININFO != 0 {
+            flags.push("CHAININFO");
+        }
+        flags.join(" ")
+    }
+}
+
+/// A RUNTIME_FUNCTION entry of the exception directory, addresses are RVAs
+#[derive(Clone)]
+pub struct RuntimeFunction {
+    pub begin_address: u32,
+    pub end_address: u32,
+    // RVA of the unwind information or, for packed ARM64 entries, the unwind data itself
+    pub unwind_data: u32,
+    // Decoded unwind information of x64 functions
+    pub unwind_info: Option<UnwindInfo>,
+}
+
+impl RuntimeFunction {
+    pub fn size(&self) -> u32 {
+        self.end_address.saturating_sub(self.begin_address)
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        let mut v = vec![
+            format!("0x{:08x}", self.begin_address),
+            format!("0x{:08x}", self.end_address),
+            self.size().to_string(),
+            format!("0x{:08x}", self.unwind_data),
+        ];
+        match &self.unwind_info {
+            Some(unwind_info) => {
+                v.push(unwind_info.flags_as_string());
+                v.push(unwind_info.prolog_size.to_string());
+                v.push(match unwind_info.frame_register_name() {
+                    Some(register) => format!("{register}+0x{:x}", unwind_info.frame_offset),
+                    None => String::new(),
+                });
+                v.push(
+                    unwind_info
+                        .codes
+                        .iter()
+                        .map(|code| format!("0x{:02x}: {}", code.offset, code.operation))
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                );
+                v.push(
+                    match (unwind_info.chained_function, unwind_info.exception_handler) {
+                        (Some(function), _) => format!("chained to 0x{function:08x}"),
+                        (None, Some(handler)) => format!(
+                            "handler 0x{handler:08x}, data 0x{:08x}",
+                            unwind_info.handler_data.unwrap_or_default()
+                        ),
+                        (None, None) => String::new(),
+                    },
+                );
+            }
+            None => v.extend([
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]),
+        }
+        v
+    }
+}
+
+/// Decode the unwind codes of an x64 prolog, each code occupies one to three slots
+fn unwind_codes(blob: &Blob, offset: usize, count: usize) -> Result<Vec<UnwindCode>> {
+    let slot = |idx: usize| -> Result<u16> { Ok(blob.get_u16(offset + 2 * idx)?) };
+    let large =
+        |idx: usize| -> Result<u32> { Ok(slot(idx)? as u32 | (slot(idx + 1)? as u32) << 16) };
+    let mut codes = Vec::new();
+    let mut idx = 0;
+    while idx < count {
+        let code_offset = blob.get_u8(offset + 2 * idx)?;
+        let op = blob.get_u8(offset + 2 * idx + 1)?;
+        let info = (op >> 4) as usize;
+        let register = X64_REGISTERS[info];
+        let (operation, slots) = match op & 0xf {
+            0 => (format!("push {register}"), 1),
+            1 if info == 0 => (format!("alloc 0x{:x}", 8 * slot(idx + 1)? as u32), 2),
+            1 => (format!("alloc 0x{:x}", large(idx + 1)?), 3),
+            2 => (format!("alloc 0x{:x}", 8 * info + 8), 1),
+            3 => ("set frame pointer".to_string(), 1),
+            4 => (
+                format!("save {register} at rsp+0x{:x}", 8 * slot(idx + 1)? as u32),
+                2,
+            ),
+            5 => (format!("save {register} at rsp+0x{:x}", large(idx + 1)?), 3),
+            // Version 2 describes epilogs, version 1 used the code for saving xmm registers
+            6 => ("epilog".to_string(), 2),
+            7 => ("spare".to_string(), 3),
+            8 => (
+                format!("save xmm{info} at rsp+0x{:x}", 16 * slot(idx + 1)? as u32),
+                2,
+            ),
+            9 => (format!("save xmm{info} at rsp+0x{:x}", large(idx + 1)?), 3),
+            10 if info == 0 => ("push machine frame".to_string(), 1),
+            10 => ("push machine frame with error code".to_string(), 1),
+            op => (format!("unknown operation {op}"), 1),
+        };
+        codes.push(UnwindCode {
+            offset: code_offset,
+            operation,
+        });
+        idx += slots;
+    }
+    Ok(codes)
+}
+
+fn parse_unwind_info(binary: &PeBinary, rva: u32) -> Result<Option<UnwindInfo>> {
+    let Some(offset) = binary.rva_to_offset(rva)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let version_flags = blob.get_u8(offset)?;
+    let count = blob.get_u8(offset + 2)? as usize;
+    let frame = blob.get_u8(offset + 3)?;
+    let mut unwind_info = UnwindInfo {
+        version: version_flags & 0x7,
+        flags: version_flags >> 3,
+        prolog_size: blob.get_u8(offset + 1)?,
+        frame_register: frame & 0xf,
+        frame_offset: 16 * (frame >> 4) as u32,
+        codes: unwind_codes(blob, offset + 4, count)?,
+        exception_handler: None,
+        handler_data: None,
+        chained_function: None,
+    };
+    // The code array is padded to an even number of slots
+    let rest = offset + 4 + 2 * ((count + 1) & !1);
+    let rest_rva = rva.wrapping_add((rest - offset) as u32);
+    if unwind_info.flags & UNW_FLAG_CHAININFO != 0 {
+        unwind_info.chained_function = Some(blob.get_u32(rest)?);
+    } else if unwind_info.flags & (UNW_FLAG_EHANDLER | UNW_FLAG_UHANDLER) != 0 {
+        unwind_info.exception_handler = Some(blob.get_u32(rest)?);
+        unwind_info.handler_data = Some(rest_rva.wrapping_add(4));
+    }
+    Ok(Some(unwind_info))
+}
+
+/// Length of an ARM64 function, from packed unwind data or the header of its .xdata record
+fn arm64_function_length(binary: &PeBinary, unwind_data: u32) -> Result<u32> {
+    let length = match unwind_data & 0x3 {
+        0 => match binary.rva_to_offset(unwind_data)? {
+            Some(offset) => (binary.blob.get_u32(offset)? & 0x3ffff) * 4,
+            None => 0,
+        },
+        _ => ((unwind_data >> 2) & 0x7ff) * 4,
+    };
+    Ok(length)
+}
+
+/// Decode the RUNTIME_FUNCTION entries of x64 and ARM64 images, sections have to be loaded
+pub(super) fn parse_runtime_functions(binary: &PeBinary) -> Result<Vec<RuntimeFunction>> {
+    let mut functions = Vec::new();
+    let Some(directory) = binary.data_directory(EXCEPTION_DIRECTORY) else {
+        return Ok(functions);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(functions);
+    };
+    let blob = &binary.blob;
+    match binary.coff_header.machine {
+        MachineType::Amd64 => {
+            for idx in 0..directory.size as usize / 12 {
+                let entry = offset + 12 * idx;
+                let unwind_data = blob.get_u32(entry + 8)?;
+                functions.push(RuntimeFunction {
+                    begin_address: blob.get_u32(entry)?,
+                    end_address: blob.get_u32(entry + 4)?,
+                    unwind_data,
+                    // Unwind information is aligned, odd addresses are not decoded. Broken
+                    // unwind information is left out, the function is still listed.
+                    unwind_info: if unwind_data & 1 == 0 {
+                        parse_unwind_info(binary, unwind_data).unwrap_or(None)
+                    } else {
+                        None
+                    },
+                });
+            }
+        }
+        MachineType::Arm64 => {
+            for idx in 0..directory.size as usize / 8 {
+                let entry = offset + 8 * idx;
+                let begin_address = blob.get_u32(entry)?;
+                let unwind_data = blob.get_u32(entry + 4)?;
+                functions.push(RuntimeFunction {
+                    begin_address,
+                    end_address: begin_address
+                        .wrapping_add(arm64_function_length(binary, unwind_data)?),
+                    unwind_data,
+                    unwind_info: None,
+                });
+            }
+        }
+        _ => {}
+    }
+    Ok(functions)
+}
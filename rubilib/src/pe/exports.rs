@@ -0,0 +1,96 @@
+use super::{PeBinary, PeError, Result};
+
+const EXPORT_DIRECTORY: usize = 0;
+
+/// Header of the export directory
+pub struct ExportDirectory {
+    pub(super) dll_name: String,
+    pub(super) time_date_stamp: u32,
+    pub(super) version: (u16, u16),
+    pub(super) ordinal_base: u32,
+    pub(super) number_of_functions: u32,
+    pub(super) number_of_names: u32,
+}
+
+/// A function or variable exported by the image
+pub struct Export {
+    pub(super) ordinal: u32,
+    // Exports without name can only be imported by ordinal
+    pub(super) name: Option<String>,
+    pub(super) rva: u32,
+    // Exports pointing into the export directory forward to another DLL, e.g. "NTDLL.RtlAllocateHeap"
+    pub(super) forwarder: Option<String>,
+}
+
+impl Export {
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.ordinal.to_string(),
+            self.name.clone().unwrap_or_default(),
+            format!("0x{:08x}", self.rva),
+            self.forwarder.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Decode the export directory, sections have to be loaded
+pub(super) fn parse_exports(binary: &PeBinary) -> Result<Option<(ExportDirectory, Vec<Export>)>> {
+    let Some(directory) = binary.data_directory(EXPORT_DIRECTORY) else {
+        return Ok(None);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let header = ExportDirectory {
+        dll_name: binary.rva_to_string(blob.get_u32(offset + 12)?)?,
+        time_date_stamp: blob.get_u32(offset + 4)?,
+        version: (blob.get_u16(offset + 8)?, blob.get_u16(offset + 10)?),
+        ordinal_base: blob.get_u32(offset + 16)?,
+        number_of_functions: blob.get_u32(offset + 20)?,
+        number_of_names: blob.get_u32(offset + 24)?,
+    };
+
+    if header.number_of_functions as usize > blob.len() / 4 {
+        return Err(PeError::InternalError);
+    }
+
+    // Names are mapped to functions through the parallel table of name ordinals
+    let mut names = vec![None; header.number_of_functions as usize];
+    let name_table = binary.rva_to_offset(blob.get_u32(offset + 32)?)?;
+    let ordinal_table = binary.rva_to_offset(blob.get_u32(offset + 36)?)?;
+    if let (Some(name_table), Some(ordinal_table)) = (name_table, ordinal_table) {
+        for idx in 0..header.number_of_names as usize {
+            let name = binary.rva_to_string(blob.get_u32(name_table + 4 * idx)?)?;
+            let function = blob.get_u16(ordinal_table + 2 * idx)? as usize;
+            if let Some(slot) = names.get_mut(function) {
+                *slot = Some(name);
+            }
+        }
+    }
+
+    let mut exports = Vec::new();
+    if let Some(function_table) = binary.rva_to_offset(blob.get_u32(offset + 28)?)? {
+        let directory_range =
+            directory.virtual_address..directory.virtual_address.saturating_add(directory.size);
+        for (idx, name) in names.into_iter().enumerate() {
+            let rva = blob.get_u32(function_table + 4 * idx)?;
+            // Unused slots of the ordinal range
+            if rva == 0 {
+                continue;
+            }
+            let forwarder = if directory_range.contains(&rva) {
+                Some(binary.rva_to_string(rva)?)
+            } else {
+                None
+            };
+            exports.push(Export {
+                ordinal: header.ordinal_base.wrapping_add(idx as u32),
+                name,
+                rva,
+                forwarder,
+            });
+        }
+    }
+    Ok(Some((header, exports)))
+}
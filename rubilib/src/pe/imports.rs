@@ -0,0 +1,148 @@
+use super::{PeBinary, Result};
+
+const IMPORT_DIRECTORY: usize = 1;
+const DELAY_IMPORT_DIRECTORY: usize = 13;
+
+// Delay load descriptors with this attribute use RVAs, older ones virtual addresses
+const DELAY_ATTRIBUTE_RVA_BASED: u32 = 0x1;
+
+/// A function imported from a DLL, either by name or by ordinal
+pub struct Import {
+    pub(super) dll: String,
+    pub(super) delay_load: bool,
+    pub(super) name: Option<String>,
+    pub(super) ordinal: Option<u16>,
+    // Index into the export name table of the DLL, used to speed up lookup
+    pub(super) hint: Option<u16>,
+    // Slot of the import address table the loader writes the address to
+    pub(super) iat_rva: u32,
+}
+
+impl Import {
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.dll.clone(),
+            if self.delay_load {
+                "delay load"
+            } else {
+                "load"
+            }
+            .to_string(),
+            self.name.clone().unwrap_or_default(),
+            self.ordinal.map(|o| o.to_string()).unwrap_or_default(),
+            self.hint.map(|h| h.to_string()).unwrap_or_default(),
+            format!("0x{:08x}", self.iat_rva),
+        ]
+    }
+}
+
+/// Imports of the import and delay load import directories, sections have to be loaded
+pub(super) fn parse_imports(binary: &PeBinary) -> Result<Vec<Import>> {
+    let mut imports = Vec::new();
+    if let Some(directory) = binary.data_directory(IMPORT_DIRECTORY) {
+        if let Some(mut idx) = binary.rva_to_offset(directory.virtual_address)? {
+            loop {
+                let lookup_table = binary.blob.get_u32(idx)?;
+                let name = binary.blob.get_u32(idx + 12)?;
+                let address_table = binary.blob.get_u32(idx + 16)?;
+                if name == 0 && address_table == 0 {
+                    break;
+                }
+                let dll = binary.rva_to_string(name)?;
+                // Without lookup table the names are read from the not yet bound IAT
+                let lookup_table = if lookup_table == 0 {
+                    address_table
+                } else {
+                    lookup_table
+                };
+                parse_thunks(
+                    binary,
+                    &dll,
+                    false,
+                    lookup_table,
+                    address_table,
+                    &mut imports,
+                )?;
+                idx += 20;
+            }
+        }
+    }
+    if let Some(directory) = binary.data_directory(DELAY_IMPORT_DIRECTORY) {
+        if let Some(mut idx) = binary.rva_to_offset(directory.virtual_address)? {
+            loop {
+                let attributes = binary.blob.get_u32(idx)?;
+                let mut name = binary.blob.get_u32(idx + 4)?;
+                let mut address_table = binary.blob.get_u32(idx + 12)?;
+                let mut name_table = binary.blob.get_u32(idx + 16)?;
+                if name == 0 {
+                    break;
+                }
+                if attributes & DELAY_ATTRIBUTE_RVA_BASED == 0 {
+                    let image_base = binary.image_base() as u32;
+                    name = name.wrapping_sub(image_base);
+                    address_table = address_table.wrapping_sub(image_base);
+                    name_table = name_table.wrapping_sub(image_base);
+                }
+                let dll = binary.rva_to_string(name)?;
+                parse_thunks(binary, &dll, true, name_table, address_table, &mut imports)?;
+                idx += 32;
+            }
+        }
+    }
+    Ok(imports)
+}
+
+/// Walk the lookup table of one DLL, each entry is an ordinal or the RVA of a hint/name pair
+fn parse_thunks(
+    binary: &PeBinary,
+    dll: &str,
+    delay_load: bool,
+    lookup_table: u32,
+    address_table: u32,
+    imports: &mut Vec<Import>,
+) -> Result<()> {
+    let Some(mut idx) = binary.rva_to_offset(lookup_table)? else {
+        return Ok(());
+    };
+    let is_64 = binary.is_64();
+    let (thunk_size, ordinal_flag) = if is_64 {
+        (8, 1u64 << 63)
+    } else {
+        (4, 1u64 << 31)
+    };
+    let mut iat_rva = address_table;
+    loop {
+        let thunk = if is_64 {
+            binary.blob.get_u64(idx)?
+        } else {
+            binary.blob.get_u32(idx)? as u64
+        };
+        if thunk == 0 {
+            break;
+        }
+        let (name, ordinal, hint) = if thunk & ordinal_flag != 0 {
+            (None, Some(thunk as u16), None)
+        } else {
+            let hint_name = thunk as u32;
+            match binary.rva_to_offset(hint_name)? {
+                Some(offset) => (
+                    Some(binary.blob.get_cname(Some(offset + 2))?),
+                    None,
+                    Some(binary.blob.get_u16(offset)?),
+                ),
+                None => (None, None, None),
+            }
+        };
+        imports.push(Import {
+            dll: dll.to_string(),
+            delay_load,
+            name,
+            ordinal,
+            hint,
+            iat_rva,
+        });
+        idx += thunk_size;
+        iat_rva = iat_rva.wrapping_add(thunk_size as u32);
+    }
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use super::{flags_as_string, format_timestamp, PeBinary, Result, LOAD_CONFIG_DIRECTORY};
+
+const GUARD_FLAGS_NAMES: [(u32, &str); 17] = [
+    (0x100, "CF_INSTRUMENTED"),
+    (0x200, "CFW_INSTRUMENTED"),
+    (0x400, "CF_FUNCTION_TABLE_PRESENT"),
+    (0x800, "SECURITY_COOKIE_UNUSED"),
+    (0x1000, "PROTECT_DELAYLOAD_IAT"),
+    (0x2000, "DELAYLOAD_IAT_IN_ITS_OWN_SECTION"),
+    (0x4000, "CF_EXPORT_SUPPRESSION_INFO_PRESENT"),
+    (0x8000, "CF_ENABLE_EXPORT_SUPPRESSION"),
+    (0x10000, "CF_LONGJUMP_TABLE_PRESENT"),
+    (0x20000, "RF_INSTRUMENTED"),
+    (0x40000, "RF_ENABLE"),
+    (0x80000, "RF_STRICT"),
+    (0x100000, "RETPOLINE_PRESENT"),
+    (0x400000, "EH_CONTINUATION_TABLE_PRESENT"),
+    (0x800000, "XFG_ENABLED"),
+    (0x1000000, "CASTGUARD_PRESENT"),
+    (0x2000000, "MEMCPY_PRESENT"),
+];
+
+// The upper four bits of the guard flags hold the number of bytes following each RVA
+// in the CFG function table
+const GUARD_FUNCTION_TABLE_STRIDE_MASK: u32 = 0xf0000000;
+const GUARD_FUNCTION_TABLE_STRIDE_SHIFT: u32 = 28;
+
+/// The fields of IMAGE_LOAD_CONFIG_DIRECTORY, missing fields of older versions are zero
+#[derive(Default)]
+pub struct LoadConfig {
+    pub(super) size: u32,
+    pub(super) time_date_stamp: u32,
+    pub(super) version: (u16, u16),
+    pub(super) global_flags_clear: u32,
+    pub(super) global_flags_set: u32,
+    pub(super) critical_section_default_timeout: u32,
+    pub(super) process_heap_flags: u32,
+    pub(super) dependent_load_flags: u16,
+    pub(super) security_cookie: u64,
+    pub(super) se_handler_table: u64,
+    pub(super) se_handler_count: u64,
+    pub(super) guard_cf_check_function_pointer: u64,
+    pub(super) guard_cf_dispatch_function_pointer: u64,
+    pub(super) guard_cf_function_table: u64,
+    pub(super) guard_cf_function_count: u64,
+    pub(super) guard_flags: u32,
+    pub(super) guard_address_taken_iat_entry_table: u64,
+    pub(super) guard_address_taken_iat_entry_count: u64,
+    pub(super) guard_long_jump_target_table: u64,
+    pub(super) guard_long_jump_target_count: u64,
+    pub(super) guard_eh_continuation_table: u64,
+    pub(super) guard_eh_continuation_count: u64,
+    // RVAs of the registered safe exception handlers
+    pub(super) se_handlers: Vec<u32>,
+    // RVAs of valid indirect call targets with their flags
+    pub(super) guard_cf_functions: Vec<(u32, u8)>,
+}
+
+impl LoadConfig {
+    pub(super) fn info(&self) -> Vec<(String, String)> {
+        let hex = |value: u64| format!("0x{value:x}");
+        let mut info = vec![
+            ("Size".to_string(), hex(self.size as u64)),
+            (
+                "Time stamp".to_string(),
+                if self.time_date_stamp == 0 {
+                    "0".to_string()
+                } else {
+                    format_timestamp(self.time_date_stamp)
+                },
+            ),
+            (
+                "Version".to_string(),
+                format!("{}.{}", self.version.0, self.version.1),
+            ),
+            (
+                "Global flags clear / set".to_string(),
+                format!(
+                    "0x{:08x} / 0x{:08x}",
+                    self.global_flags_clear, self.global_flags_set
+                ),
+            ),
+            (
+                "Critical section default timeout".to_string(),
+                self.critical_section_default_timeout.to_string(),
+            ),
+            (
+                "Process heap flags".to_string(),
+                hex(self.process_heap_flags as u64),
+            ),
+            (
+                "Dependent load flags".to_string(),
+                hex(self.dependent_load_flags as u64),
+            ),
+            ("Security cookie".to_string(), hex(self.security_cookie)),
+            (
+                "SEH table / count".to_string(),
+                format!("{} / {}", hex(self.se_handler_table), self.se_handler_count),
+            ),
+            (
+                "CFG check function pointer".to_string(),
+                hex(self.guard_cf_check_function_pointer),
+            ),
+            (
+                "CFG dispatch function pointer".to_string(),
+                hex(self.guard_cf_dispatch_function_pointer),
+            ),
+            (
+                "CFG function table / count".to_string(),
+                format!(
+                    "{} / {}",
+                    hex(self.guard_cf_function_table),
+                    self.guard_cf_function_count
+                ),
+            ),
+            (
+                "Guard flags".to_string(),
+                format!(
+                    "0x{:08x} {}",
+                    self.guard_flags,
+                    self.guard_flags_as_string()
+                ),
+            ),
+            (
+                "CFG address taken IAT table / count".to_string(),
+                format!(
+                    "{} / {}",
+                    hex(self.guard_address_taken_iat_entry_table),
+                    self.guard_address_taken_iat_entry_count
+                ),
+            ),
+            (
+                "CFG long jump target table / count".to_string(),
+                format!(
+                    "{} / {}",
+                    hex(self.guard_long_jump_target_table),
+                    self.guard_long_jump_target_count
+                ),
+            ),
+            (
+                "EH continuation table / count".to_string(),
+                format!(
+                    "{} / {}",
+                    hex(self.guard_eh_continuation_table),
+                    self.guard_eh_continuation_count
+                ),
+            ),
+        ];
+        for handler in &self.se_handlers {
+            info.push(("SEH handler RVA".to_string(), format!("0x{handler:08x}")));
+        }
+        for (function, flags) in &self.guard_cf_functions {
+            info.push((
+                "CFG function RVA".to_string(),
+                format!("0x{function:08x} flags 0x{flags:02x}"),
+            ));
+        }
+        info
+    }
+
+    fn guard_flags_as_string(&self) -> String {
+        let stride = self.guard_flags >> GUARD_FUNCTION_TABLE_STRIDE_SHIFT;
+        let flags = flags_as_string(
+            self.guard_flags & !GUARD_FUNCTION_TABLE_STRIDE_MASK,
+            &GUARD_FLAGS_NAMES,
+        );
+        if stride > 0 {
+            format!("{flags} STRIDE={stride}")
+        } else {
+            flags
+        }
+    }
+}
+
+/// Decode the load configuration directory, sections have to be loaded
+pub(super) fn parse_load_config(binary: &PeBinary) -> Result<Option<LoadConfig>> {
+    let Some(directory) = binary.data_directory(LOAD_CONFIG_DIRECTORY) else {
+        return Ok(None);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let is_64 = binary.is_64();
+    // The structure grew over time, its first field tells how much is present
+    let size = blob.get_u32(offset)?;
+    let present = |field: usize, len: usize| field + len <= size as usize;
+    let u16_field = |field: usize| -> Result<u16> {
+        if present(field, 2) {
+            Ok(blob.get_u16(offset + field)?)
+        } else {
+            Ok(0)
+        }
+    };
+    let u32_field = |field: usize| -> Result<u32> {
+        if present(field, 4) {
+            Ok(blob.get_u32(offset + field)?)
+        } else {
+            Ok(0)
+        }
+    };
+    // Pointer sized fields, the offsets are given for 32 and 64 bit images
+    let word = |field32: usize, field64: usize| -> Result<u64> {
+        if is_64 {
+            if present(field64, 8) {
+                Ok(blob.get_u64(offset + field64)?)
+            } else {
+                Ok(0)
+            }
+        } else {
+            Ok(u32_field(field32)? as u64)
+        }
+    };
+    let (process_heap_flags, dependent_load_flags, guard_flags) = if is_64 {
+        (u32_field(72)?, u16_field(78)?, u32_field(144)?)
+    } else {
+        (u32_field(44)?, u16_field(54)?, u32_field(88)?)
+    };
+    let mut load_config = LoadConfig {
+        size,
+        time_date_stamp: u32_field(4)?,
+        version: (u16_field(8)?, u16_field(10)?),
+        global_flags_clear: u32_field(12)?,
+        global_flags_set: u32_field(16)?,
+        critical_section_default_timeout: u32_field(20)?,
+        process_heap_flags,
+        dependent_load_flags,
+        security_cookie: word(60, 88)?,
+        se_handler_table: word(64, 96)?,
+        se_handler_count: word(68, 104)?,
+        guard_cf_check_function_pointer: word(72, 112)?,
+        guard_cf_dispatch_function_pointer: word(76, 120)?,
+        guard_cf_function_table: word(80, 128)?,
+        guard_cf_function_count: word(84, 136)?,
+        guard_flags,
+        guard_address_taken_iat_entry_table: word(104, 160)?,
+        guard_address_taken_iat_entry_count: word(108, 168)?,
+        guard_long_jump_target_table: word(112, 176)?,
+        guard_long_jump_target_count: word(116, 184)?,
+        guard_eh_continuation_table: word(164, 264)?,
+        guard_eh_continuation_count: word(168, 272)?,
+        se_handlers: Vec::new(),
+        guard_cf_functions: Vec::new(),
+    };
+
+    let image_base = binary.image_base();
+    // Tables are referenced by virtual address, counts are checked against the file size
+    let table_offset = |address: u64, count: u64, entry_size: usize| -> Result<Option<usize>> {
+        if address == 0 || count as usize > blob.len() / entry_size {
+            return Ok(None);
+        }
+        binary.rva_to_offset(address.wrapping_sub(image_base) as u32)
+    };
+    if let Some(table) = table_offset(
+        load_config.se_handler_table,
+        load_config.se_handler_count,
+        4,
+    )? {
+        for idx in 0..load_config.se_handler_count as usize {
+            load_config.se_handlers.push(blob.get_u32(table + 4 * idx)?);
+        }
+    }
+    let stride = (guard_flags >> GUARD_FUNCTION_TABLE_STRIDE_SHIFT) as usize;
+    if let Some(table) = table_offset(
+        load_config.guard_cf_function_table,
+        load_config.guard_cf_function_count,
+        4 + stride,
+    )? {
+        for idx in 0..load_config.guard_cf_function_count as usize {
+            let entry = table + (4 + stride) * idx;
+            let flags = if stride > 0 {
+                blob.get_u8(entry + 4)?
+            } else {
+                0
+            };
+            load_config
+                .guard_cf_functions
+                .push((blob.get_u32(entry)?, flags));
+        }
+    }
+    Ok(Some(load_config))
+}
@@ -1,28 +1,1679 @@
-use crate::blob::{BinaryType, Blob};
+use crate::blob::{BinaryType, Blob, BlobError};
+use crate::overlay::Overlay;
+use crate::table::{Row, RowAction, Table, TableType};
+use std::fmt::{self, Display};
+use strum::FromRepr;
 use thiserror::Error;
 
 type Result<T> = std::result::Result<T, PeError>;
 
+mod authenticode;
+mod base_relocations;
+mod checksec;
+mod debug;
+mod der;
+mod dotnet;
+mod exceptions;
+mod exports;
+mod imports;
+mod load_config;
+mod resources;
+mod rich;
+#[cfg(test)]
+mod tests;
+mod tls;
+
+use authenticode::Signature;
+use base_relocations::BaseRelocation;
+use debug::{DebugEntry, DebugInfo};
+use dotnet::DotNet;
+use exports::{Export, ExportDirectory};
+use imports::Import;
+use resources::{Resource, ResourceId, ResourceType};
+
+pub(crate) use der::hex_string;
+pub use exceptions::{RuntimeFunction, UnwindCode, UnwindInfo};
+
 #[derive(Error, Debug, Clone)]
 pub enum PeError {
     #[error("no pe binary")]
     NoPeBinary,
+    #[error("invalid binary")]
+    InternalError,
+    #[error("malformed authenticode signature")]
+    MalformedSignature,
+    #[error("malformed .NET metadata")]
+    MalformedMetadata,
+    #[error("binary corrupted")]
+    BlobCorrupted(#[from] BlobError),
+}
+
+// Index of the load configuration in the data directories
+const LOAD_CONFIG_DIRECTORY: usize = 10;
+// The certificate table is addressed by file offset and not mapped into memory
+const CERTIFICATE_DIRECTORY: usize = 4;
+
+const DATA_DIRECTORY_NAMES: [&str; 16] = [
+    "Export",
+    "Import",
+    "Resource",
+    "Exception",
+    "Certificate",
+    "Base Relocation",
+    "Debug",
+    "Architecture",
+    "Global Pointer",
+    "TLS",
+    "Load Config",
+    "Bound Import",
+    "IAT",
+    "Delay Import",
+    "CLR Runtime",
+    "Reserved",
+];
+
+pub(crate) const CHARACTERISTICS_NAMES: [(u32, &str); 15] = [
+    (0x1, "RELOCS_STRIPPED"),
+    (0x2, "EXECUTABLE_IMAGE"),
+    (0x4, "LINE_NUMS_STRIPPED"),
+    (0x8, "LOCAL_SYMS_STRIPPED"),
+    (0x10, "AGGRESSIVE_WS_TRIM"),
+    (0x20, "LARGE_ADDRESS_AWARE"),
+    (0x80, "BYTES_REVERSED_LO"),
+    (0x100, "32BIT_MACHINE"),
+    (0x200, "DEBUG_STRIPPED"),
+    (0x400, "REMOVABLE_RUN_FROM_SWAP"),
+    (0x800, "NET_RUN_FROM_SWAP"),
+    (0x1000, "SYSTEM"),
+    (0x2000, "DLL"),
+    (0x4000, "UP_SYSTEM_ONLY"),
+    (0x8000, "BYTES_REVERSED_HI"),
+];
+
+const DLL_CHARACTERISTICS_NAMES: [(u32, &str); 11] = [
+    (0x20, "HIGH_ENTROPY_VA"),
+    (0x40, "DYNAMIC_BASE"),
+    (0x80, "FORCE_INTEGRITY"),
+    (0x100, "NX_COMPAT"),
+    (0x200, "NO_ISOLATION"),
+    (0x400, "NO_SEH"),
+    (0x800, "NO_BIND"),
+    (0x1000, "APPCONTAINER"),
+    (0x2000, "WDM_DRIVER"),
+    (0x4000, "GUARD_CF"),
+    (0x8000, "TERMINAL_SERVER_AWARE"),
+];
+
+const SECTION_CHARACTERISTICS_NAMES: [(u32, &str); 20] = [
+    (0x8, "TYPE_NO_PAD"),
+    (0x20, "CNT_CODE"),
+    (0x40, "CNT_INITIALIZED_DATA"),
+    (0x80, "CNT_UNINITIALIZED_DATA"),
+    (0x100, "LNK_OTHER"),
+    (0x200, "LNK_INFO"),
+    (0x800, "LNK_REMOVE"),
+    (0x1000, "LNK_COMDAT"),
+    (0x8000, "GPREL"),
+    (0x20000, "MEM_16BIT"),
+    (0x40000, "MEM_LOCKED"),
+    (0x80000, "MEM_PRELOAD"),
+    (0x1000000, "LNK_NRELOC_OVFL"),
+    (0x2000000, "MEM_DISCARDABLE"),
+    (0x4000000, "MEM_NOT_CACHED"),
+    (0x8000000, "MEM_NOT_PAGED"),
+    (0x10000000, "MEM_SHARED"),
+    (0x20000000, "MEM_EXECUTE"),
+    (0x40000000, "MEM_READ"),
+    (0x80000000, "MEM_WRITE"),
+];
+
+// Alignment of object file sections, encoded as 4 bit power of two
+const SECTION_ALIGNMENT_MASK: u32 = 0x00f00000;
+
+/// Round `value` up to a multiple of `align`, which has to be a power of two
+pub(crate) fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+pub(crate) fn flags_as_string(value: u32, names: &[(u32, &str)]) -> String {
+    let mut flags = Vec::new();
+    let mut rest = value;
+    for (flag, name) in names {
+        if value & flag != 0 {
+            flags.push(name.to_string());
+            rest &= !flag;
+        }
+    }
+    if rest != 0 {
+        flags.push(format!("0x{rest:x}"));
+    }
+    flags.join(" ")
+}
+
+/// Format seconds since the Unix epoch as UTC date and time
+pub(crate) fn format_timestamp(timestamp: u32) -> String {
+    let seconds = timestamp as i64;
+    let days = seconds.div_euclid(86400);
+    let time = seconds.rem_euclid(86400);
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[repr(u16)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum MachineType {
+    Unknown = 0x0,
+    I386 = 0x14c,
+    R3000 = 0x162,
+    R4000 = 0x166,
+    R10000 = 0x168,
+    WceMipsV2 = 0x169,
+    Alpha = 0x184,
+    Sh3 = 0x1a2,
+    Sh3Dsp = 0x1a3,
+    Sh4 = 0x1a6,
+    Sh5 = 0x1a8,
+    Arm = 0x1c0,
+    Thumb = 0x1c2,
+    ArmNt = 0x1c4,
+    Am33 = 0x1d3,
+    PowerPc = 0x1f0,
+    PowerPcFp = 0x1f1,
+    Ia64 = 0x200,
+    Mips16 = 0x266,
+    Alpha64 = 0x284,
+    MipsFpu = 0x366,
+    MipsFpu16 = 0x466,
+    Chpe = 0x3a64,
+    Ebc = 0xebc,
+    RiscV32 = 0x5032,
+    RiscV64 = 0x5064,
+    RiscV128 = 0x5128,
+    LoongArch32 = 0x6232,
+    LoongArch64 = 0x6264,
+    Amd64 = 0x8664,
+    M32R = 0x9041,
+    Arm64Ec = 0xa641,
+    Arm64X = 0xa64e,
+    Arm64 = 0xaa64,
+    Reserved = 0xffff,
+}
+
+impl Display for MachineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        match self {
+            MachineType::Unknown => write!(f, "Unknown"),
+            MachineType::I386 => write!(f, "Intel 386"),
+            MachineType::R3000 => write!(f, "MIPS R3000"),
+            MachineType::R4000 => write!(f, "MIPS R4000"),
+            MachineType::R10000 => write!(f, "MIPS R10000"),
+            MachineType::WceMipsV2 => write!(f, "MIPS WCE v2"),
+            MachineType::Alpha => write!(f, "Alpha AXP"),
+            MachineType::Sh3 => write!(f, "Hitachi SH3"),
+            MachineType::Sh3Dsp => write!(f, "Hitachi SH3 DSP"),
+            MachineType::Sh4 => write!(f, "Hitachi SH4"),
+            MachineType::Sh5 => write!(f, "Hitachi SH5"),
+            MachineType::Arm => write!(f, "ARM little endian"),
+            MachineType::Thumb => write!(f, "ARM Thumb"),
+            MachineType::ArmNt => write!(f, "ARM Thumb-2"),
+            MachineType::Am33 => write!(f, "Matsushita AM33"),
+            MachineType::PowerPc => write!(f, "PowerPC little endian"),
+            MachineType::PowerPcFp => write!(f, "PowerPC with floating point"),
+            MachineType::Ia64 => write!(f, "Intel Itanium"),
+            MachineType::Mips16 => write!(f, "MIPS16"),
+            MachineType::Alpha64 => write!(f, "Alpha 64"),
+            MachineType::MipsFpu => write!(f, "MIPS with FPU"),
+            MachineType::MipsFpu16 => write!(f, "MIPS16 with FPU"),
+            MachineType::Chpe => write!(f, "x86 hybrid PE"),
+            MachineType::Ebc => write!(f, "EFI byte code"),
+            MachineType::RiscV32 => write!(f, "RISC-V 32 bit"),
+            MachineType::RiscV64 => write!(f, "RISC-V 64 bit"),
+            MachineType::RiscV128 => write!(f, "RISC-V 128 bit"),
+            MachineType::LoongArch32 => write!(f, "LoongArch 32 bit"),
+            MachineType::LoongArch64 => write!(f, "LoongArch 64 bit"),
+            MachineType::Amd64 => write!(f, "AMD x86-64"),
+            MachineType::M32R => write!(f, "Mitsubishi M32R"),
+            MachineType::Arm64Ec => write!(f, "ARM64EC"),
+            MachineType::Arm64X => write!(f, "ARM64X"),
+            MachineType::Arm64 => write!(f, "ARM64 little endian"),
+            MachineType::Reserved => write!(f, "Reserved"),
+        }
+    }
+}
+
+#[repr(u16)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum Subsystem {
+    Unknown = 0,
+    Native = 1,
+    WindowsGui = 2,
+    WindowsCui = 3,
+    Os2Cui = 5,
+    PosixCui = 7,
+    NativeWindows = 8,
+    WindowsCeGui = 9,
+    EfiApplication = 10,
+    EfiBootServiceDriver = 11,
+    EfiRuntimeDriver = 12,
+    EfiRom = 13,
+    Xbox = 14,
+    WindowsBootApplication = 16,
+    Reserved = 0xffff,
+}
+
+/// The MS-DOS stub header every PE file starts with
+pub struct DosHeader {
+    // Bytes on last page of file
+    bytes_last_page: u16,
+    // Pages in file
+    pages: u16,
+    // Relocations
+    relocations: u16,
+    // Size of header in paragraphs
+    header_paragraphs: u16,
+    min_alloc: u16,
+    max_alloc: u16,
+    initial_ss: u16,
+    initial_sp: u16,
+    checksum: u16,
+    initial_ip: u16,
+    initial_cs: u16,
+    relocation_table_offset: u16,
+    overlay_number: u16,
+    oem_id: u16,
+    oem_info: u16,
+    // File offset of the PE signature
+    pe_offset: u32,
+}
+
+impl DosHeader {
+    pub fn new(blob: &Blob) -> Result<Self> {
+        Ok(Self {
+            bytes_last_page: blob.get_u16(0x02)?,
+            pages: blob.get_u16(0x04)?,
+            relocations: blob.get_u16(0x06)?,
+            header_paragraphs: blob.get_u16(0x08)?,
+            min_alloc: blob.get_u16(0x0a)?,
+            max_alloc: blob.get_u16(0x0c)?,
+            initial_ss: blob.get_u16(0x0e)?,
+            initial_sp: blob.get_u16(0x10)?,
+            checksum: blob.get_u16(0x12)?,
+            initial_ip: blob.get_u16(0x14)?,
+            initial_cs: blob.get_u16(0x16)?,
+            relocation_table_offset: blob.get_u16(0x18)?,
+            overlay_number: blob.get_u16(0x1a)?,
+            oem_id: blob.get_u16(0x24)?,
+            oem_info: blob.get_u16(0x26)?,
+            pe_offset: blob.get_u32(0x3c)?,
+        })
+    }
+
+    pub fn info(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "DOS bytes on last page / pages".to_string(),
+                format!("{} / {}", self.bytes_last_page, self.pages),
+            ),
+            (
+                "DOS relocations".to_string(),
+                format!(
+                    "{}, table at 0x{:04x}",
+                    self.relocations, self.relocation_table_offset
+                ),
+            ),
+            (
+                "DOS header paragraphs".to_string(),
+                self.header_paragraphs.to_string(),
+            ),
+            (
+                "DOS min / max alloc".to_string(),
+                format!("0x{:04x} / 0x{:04x}", self.min_alloc, self.max_alloc),
+            ),
+            (
+                "DOS initial SS:SP / CS:IP".to_string(),
+                format!(
+                    "{:04x}:{:04x} / {:04x}:{:04x}",
+                    self.initial_ss, self.initial_sp, self.initial_cs, self.initial_ip
+                ),
+            ),
+            (
+                "DOS checksum".to_string(),
+                format!("0x{:04x}", self.checksum),
+            ),
+            (
+                "DOS overlay number".to_string(),
+                self.overlay_number.to_string(),
+            ),
+            (
+                "DOS OEM id / info".to_string(),
+                format!("0x{:04x} / 0x{:04x}", self.oem_id, self.oem_info),
+            ),
+            (
+                "PE header offset".to_string(),
+                format!("0x{:08x}", self.pe_offset),
+            ),
+        ]
+    }
+}
+
+/// The COFF file header following the PE signature
+pub struct CoffHeader {
+    // Raw machine value, kept for unknown machines
+    machine_raw: u16,
+    machine: MachineType,
+    number_of_sections: u16,
+    time_date_stamp: u32,
+    pointer_to_symbol_table: u32,
+    number_of_symbols: u32,
+    size_of_optional_header: u16,
+    characteristics: u16,
+}
+
+impl CoffHeader {
+    pub fn new(blob: &Blob, offset: usize) -> Result<Self> {
+        let machine_raw = blob.get_u16(offset)?;
+        Ok(Self {
+            machine_raw,
+            machine: MachineType::from_repr(machine_raw).unwrap_or(MachineType::Reserved),
+            number_of_sections: blob.get_u16(offset + 2)?,
+            time_date_stamp: blob.get_u32(offset + 4)?,
+            pointer_to_symbol_table: blob.get_u32(offset + 8)?,
+            number_of_symbols: blob.get_u32(offset + 12)?,
+            size_of_optional_header: blob.get_u16(offset + 16)?,
+            characteristics: blob.get_u16(offset + 18)?,
+        })
+    }
+
+    pub fn info(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "Machine type".to_string(),
+                format!("{} (0x{:04x})", self.machine, self.machine_raw),
+            ),
+            (
+                "Number of sections".to_string(),
+                self.number_of_sections.to_string(),
+            ),
+            (
+                "Time stamp".to_string(),
+                // Reproducible builds store a hash instead of a time
+                format!(
+                    "0x{:08x} ({})",
+                    self.time_date_stamp,
+                    format_timestamp(self.time_date_stamp)
+                ),
+            ),
+            (
+                "Symbol table".to_string(),
+                format!(
+                    "0x{:08x}, count: {}",
+                    self.pointer_to_symbol_table, self.number_of_symbols
+                ),
+            ),
+            (
+                "Optional header size".to_string(),
+                format!("0x{:04x}", self.size_of_optional_header),
+            ),
+            (
+                "Characteristics".to_string(),
+                format!(
+                    "0x{:04x} {}",
+                    self.characteristics,
+                    flags_as_string(self.characteristics as u32, &CHARACTERISTICS_NAMES)
+                ),
+            ),
+        ]
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct DataDirectory {
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+/// The PE32 or PE32+ optional header, mandatory for images
+pub struct OptionalHeader {
+    magic: u16,
+    linker_version: (u8, u8),
+    size_of_code: u32,
+    size_of_initialized_data: u32,
+    size_of_uninitialized_data: u32,
+    address_of_entry_point: u32,
+    base_of_code: u32,
+    // Only present in PE32
+    base_of_data: Option<u32>,
+    image_base: u64,
+    section_alignment: u32,
+    file_alignment: u32,
+    os_version: (u16, u16),
+    image_version: (u16, u16),
+    subsystem_version: (u16, u16),
+    win32_version_value: u32,
+    size_of_image: u32,
+    size_of_headers: u32,
+    checksum: u32,
+    subsystem_raw: u16,
+    subsystem: Subsystem,
+    dll_characteristics: u16,
+    size_of_stack_reserve: u64,
+    size_of_stack_commit: u64,
+    size_of_heap_reserve: u64,
+    size_of_heap_commit: u64,
+    loader_flags: u32,
+    data_directories: Vec<DataDirectory>,
+}
+
+impl OptionalHeader {
+    pub fn new(blob: &Blob, offset: usize) -> Result<Self> {
+        let magic = blob.get_u16(offset)?;
+        let is_64 = magic == 0x20b;
+        let word = |field: usize| -> Result<u64> {
+            if is_64 {
+                Ok(blob.get_u64(offset + field)?)
+            } else {
+                Ok(blob.get_u32(offset + field)? as u64)
+            }
+        };
+        let word_size = if is_64 { 8 } else { 4 };
+        // Fields from SizeOfStackReserve on are shifted by the wider image base and sizes
+        let (image_base, base_of_data) = if is_64 {
+            (blob.get_u64(offset + 24)?, None)
+        } else {
+            (
+                blob.get_u32(offset + 28)? as u64,
+                Some(blob.get_u32(offset + 24)?),
+            )
+        };
+        let loader_flags = 72 + 4 * word_size;
+        let number_of_rva_and_sizes = blob.get_u32(offset + loader_flags + 4)? as usize;
+        let mut data_directories = Vec::with_capacity(number_of_rva_and_sizes.min(16));
+        let mut idx = offset + loader_flags + 8;
+        for _ in 0..number_of_rva_and_sizes.min(16) {
+            data_directories.push(DataDirectory {
+                virtual_address: blob.get_u32(idx)?,
+                size: blob.get_u32(idx + 4)?,
+            });
+            idx += 8;
+        }
+        let subsystem_raw = blob.get_u16(offset + 68)?;
+        Ok(Self {
+            magic,
+            linker_version: (blob.get_u8(offset + 2)?, blob.get_u8(offset + 3)?),
+            size_of_code: blob.get_u32(offset + 4)?,
+            size_of_initialized_data: blob.get_u32(offset + 8)?,
+            size_of_uninitialized_data: blob.get_u32(offset + 12)?,
+            address_of_entry_point: blob.get_u32(offset + 16)?,
+            base_of_code: blob.get_u32(offset + 20)?,
+            base_of_data,
+            image_base,
+            section_alignment: blob.get_u32(offset + 32)?,
+            file_alignment: blob.get_u32(offset + 36)?,
+            os_version: (blob.get_u16(offset + 40)?, blob.get_u16(offset + 42)?),
+            image_version: (blob.get_u16(offset + 44)?, blob.get_u16(offset + 46)?),
+            subsystem_version: (blob.get_u16(offset + 48)?, blob.get_u16(offset + 50)?),
+            win32_version_value: blob.get_u32(offset + 52)?,
+            size_of_image: blob.get_u32(offset + 56)?,
+            size_of_headers: blob.get_u32(offset + 60)?,
+            checksum: blob.get_u32(offset + 64)?,
+            subsystem_raw,
+            subsystem: Subsystem::from_repr(subsystem_raw).unwrap_or(Subsystem::Reserved),
+            dll_characteristics: blob.get_u16(offset + 70)?,
+            size_of_stack_reserve: word(72)?,
+            size_of_stack_commit: word(72 + word_size)?,
+            size_of_heap_reserve: word(72 + 2 * word_size)?,
+            size_of_heap_commit: word(72 + 3 * word_size)?,
+            loader_flags: blob.get_u32(offset + loader_flags)?,
+            data_directories,
+        })
+    }
+
+    pub fn is_64(&self) -> bool {
+        self.magic == 0x20b
+    }
+
+    /// Data directory `index`, if present and not empty
+    pub fn data_directory(&self, index: usize) -> Option<DataDirectory> {
+        self.data_directories
+            .get(index)
+            .filter(|directory| directory.virtual_address != 0)
+            .copied()
+    }
+
+    pub fn info(&self) -> Vec<(String, String)> {
+        let mut info = vec![
+            (
+                "Format".to_string(),
+                match self.magic {
+                    0x10b => "PE32".to_string(),
+                    0x20b => "PE32+".to_string(),
+                    0x107 => "ROM image".to_string(),
+                    magic => format!("0x{magic:04x}"),
+                },
+            ),
+            (
+                "Linker version".to_string(),
+                format!("{}.{}", self.linker_version.0, self.linker_version.1),
+            ),
+            (
+                "Entry point".to_string(),
+                format!("0x{:08x}", self.address_of_entry_point),
+            ),
+            (
+                "Image base".to_string(),
+                format!("0x{:016x}", self.image_base),
+            ),
+            (
+                "Size of code / initialized / uninitialized data".to_string(),
+                format!(
+                    "0x{:x} / 0x{:x} / 0x{:x}",
+                    self.size_of_code,
+                    self.size_of_initialized_data,
+                    self.size_of_uninitialized_data
+                ),
+            ),
+            (
+                "Base of code".to_string(),
+                format!("0x{:08x}", self.base_of_code),
+            ),
+        ];
+        if let Some(base_of_data) = self.base_of_data {
+            info.push(("Base of data".to_string(), format!("0x{base_of_data:08x}")));
+        }
+        info.extend([
+            (
+                "Section / file alignment".to_string(),
+                format!(
+                    "0x{:x} / 0x{:x}",
+                    self.section_alignment, self.file_alignment
+                ),
+            ),
+            (
+                "OS version".to_string(),
+                format!("{}.{}", self.os_version.0, self.os_version.1),
+            ),
+            (
+                "Image version".to_string(),
+                format!("{}.{}", self.image_version.0, self.image_version.1),
+            ),
+            (
+                "Subsystem version".to_string(),
+                format!("{}.{}", self.subsystem_version.0, self.subsystem_version.1),
+            ),
+            (
+                "Win32 version value".to_string(),
+                self.win32_version_value.to_string(),
+            ),
+            (
+                "Size of image / headers".to_string(),
+                format!("0x{:x} / 0x{:x}", self.size_of_image, self.size_of_headers),
+            ),
+            ("Checksum".to_string(), format!("0x{:08x}", self.checksum)),
+            (
+                "Subsystem".to_string(),
+                format!("{:?} ({})", self.subsystem, self.subsystem_raw),
+            ),
+            (
+                "DLL characteristics".to_string(),
+                format!(
+                    "0x{:04x} {}",
+                    self.dll_characteristics,
+                    flags_as_string(self.dll_characteristics as u32, &DLL_CHARACTERISTICS_NAMES)
+                ),
+            ),
+            (
+                "Stack reserve / commit".to_string(),
+                format!(
+                    "0x{:x} / 0x{:x}",
+                    self.size_of_stack_reserve, self.size_of_stack_commit
+                ),
+            ),
+            (
+                "Heap reserve / commit".to_string(),
+                format!(
+                    "0x{:x} / 0x{:x}",
+                    self.size_of_heap_reserve, self.size_of_heap_commit
+                ),
+            ),
+            (
+                "Loader flags".to_string(),
+                format!("0x{:08x}", self.loader_flags),
+            ),
+        ]);
+        for (idx, directory) in self.data_directories.iter().enumerate() {
+            if directory.virtual_address == 0 && directory.size == 0 {
+                continue;
+            }
+            info.push((
+                format!("Data directory {}", DATA_DIRECTORY_NAMES[idx]),
+                format!(
+                    "0x{:08x}, size: 0x{:x}",
+                    directory.virtual_address, directory.size
+                ),
+            ));
+        }
+        info
+    }
+}
+
+/// Entry of the section table following the optional header
+pub struct SectionHeader {
+    // Section name, long names of object files are resolved through the string table
+    pub(crate) name: String,
+    virtual_size: u32,
+    virtual_address: u32,
+    pub(crate) size_of_raw_data: u32,
+    pub(crate) pointer_to_raw_data: u32,
+    pub(crate) pointer_to_relocations: u32,
+    pointer_to_line_numbers: u32,
+    pub(crate) number_of_relocations: u16,
+    number_of_line_numbers: u16,
+    pub(crate) characteristics: u32,
+}
+
+impl SectionHeader {
+    pub fn new(blob: &Blob, offset: usize, string_table_offset: Option<usize>) -> Result<Self> {
+        let raw_name = blob.get_slice(offset, 8)?;
+        let short_name = String::from_utf8_lossy(raw_name)
+            .trim_end_matches('\0')
+            .to_string();
+        let name = match (string_table_offset, Self::long_name_offset(&short_name)) {
+            (Some(string_table_offset), Some(name_offset)) => {
+                blob.get_cname(Some(string_table_offset + name_offset))?
+            }
+            _ => short_name,
+        };
+        Ok(Self {
+            name,
+            virtual_size: blob.get_u32(offset + 8)?,
+            virtual_address: blob.get_u32(offset + 12)?,
+            size_of_raw_data: blob.get_u32(offset + 16)?,
+            pointer_to_raw_data: blob.get_u32(offset + 20)?,
+            pointer_to_relocations: blob.get_u32(offset + 24)?,
+            pointer_to_line_numbers: blob.get_u32(offset + 28)?,
+            number_of_relocations: blob.get_u16(offset + 32)?,
+            number_of_line_numbers: blob.get_u16(offset + 34)?,
+            characteristics: blob.get_u32(offset + 36)?,
+        })
+    }
+
+    /// String table offset of a long name, written as "/nnn" in decimal or "//xxxxxx" in base64
+    fn long_name_offset(name: &str) -> Option<usize> {
+        if let Some(encoded) = name.strip_prefix("//") {
+            encoded.bytes().try_fold(0usize, |offset, c| {
+                let digit = match c {
+                    b'A'..=b'Z' => c - b'A',
+                    b'a'..=b'z' => c - b'a' + 26,
+                    b'0'..=b'9' => c - b'0' + 52,
+                    b'+' => 62,
+                    b'/' => 63,
+                    _ => return None,
+                };
+                Some(offset * 64 + digit as usize)
+            })
+        } else {
+            name.strip_prefix('/')?.parse().ok()
+        }
+    }
+
+    /// Whether the relative virtual address lies within the section
+    fn contains_rva(&self, rva: u32) -> bool {
+        rva >= self.virtual_address
+            && rva - self.virtual_address < self.virtual_size.max(self.size_of_raw_data)
+    }
+
+    /// Whether the relative virtual address is backed by raw data in the file, the rest of
+    /// the section is zero filled when loaded
+    fn has_raw_data_at(&self, rva: u32) -> bool {
+        let raw_size = match self.virtual_size {
+            0 => self.size_of_raw_data,
+            virtual_size => virtual_size.min(self.size_of_raw_data),
+        };
+        rva >= self.virtual_address && rva - self.virtual_address < raw_size
+    }
+
+    fn flags_as_string(&self) -> String {
+        let mut flags = String::new();
+        flags.push(if self.characteristics & 0x40000000 != 0 {
+            'r'
+        } else {
+            '-'
+        });
+        flags.push(if self.characteristics & 0x80000000 != 0 {
+            'w'
+        } else {
+            '-'
+        });
+        flags.push(if self.characteristics & 0x20000000 != 0 {
+            'x'
+        } else {
+            '-'
+        });
+        let names = flags_as_string(
+            self.characteristics & !SECTION_ALIGNMENT_MASK,
+            &SECTION_CHARACTERISTICS_NAMES,
+        );
+        if !names.is_empty() {
+            flags.push(' ');
+            flags.push_str(&names);
+        }
+        let alignment = (self.characteristics & SECTION_ALIGNMENT_MASK) >> 20;
+        if alignment != 0 {
+            flags.push_str(&format!(" ALIGN_{}BYTES", 1u32 << (alignment - 1)));
+        }
+        flags
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            format!("0x{:08x}", self.characteristics),
+            self.flags_as_string(),
+            format!("0x{:08x}", self.virtual_address),
+            format!("0x{:08x}", self.virtual_size),
+            format!("0x{:08x}", self.pointer_to_raw_data),
+            format!("0x{:08x}", self.size_of_raw_data),
+            format!(
+                "0x{:08x}, count: {}",
+                self.pointer_to_relocations, self.number_of_relocations
+            ),
+            format!(
+                "0x{:08x}, count: {}",
+                self.pointer_to_line_numbers, self.number_of_line_numbers
+            ),
+        ]
+    }
 }
 
 pub struct PeBinary {
-    _blob: Blob,
+    blob: Blob,
+    dos_header: DosHeader,
+    coff_header: CoffHeader,
+    optional_header: Option<OptionalHeader>,
+    section_headers: Vec<SectionHeader>,
+    imports: Vec<Import>,
+    export_directory: Option<ExportDirectory>,
+    exports: Vec<Export>,
+    resources: Vec<Resource>,
+    signatures: Vec<Signature>,
+    base_relocations: Vec<BaseRelocation>,
+    debug_entries: Vec<DebugEntry>,
+    dotnet: Option<DotNet>,
+    // File info, collected once since it summarizes several directories
+    info: Vec<(String, String)>,
 }
 
 impl PeBinary {
     pub fn new(blob: Blob) -> Result<Self> {
         if !matches!(blob.bin_type, BinaryType::Pe) {
-            Err(PeError::NoPeBinary)
+            return Err(PeError::NoPeBinary);
+        }
+        let dos_header = DosHeader::new(&blob)?;
+        // The COFF header follows the "PE\0\0" signature
+        let coff_offset = dos_header.pe_offset as usize + 4;
+        let coff_header = CoffHeader::new(&blob, coff_offset)?;
+        let optional_header = if coff_header.size_of_optional_header == 0 {
+            None
         } else {
-            Ok(Self { _blob: blob })
+            Some(OptionalHeader::new(&blob, coff_offset + 20)?)
+        };
+        Ok(Self {
+            blob,
+            dos_header,
+            coff_header,
+            optional_header,
+            section_headers: Vec::new(),
+            imports: Vec::new(),
+            export_directory: None,
+            exports: Vec::new(),
+            resources: Vec::new(),
+            signatures: Vec::new(),
+            base_relocations: Vec::new(),
+            debug_entries: Vec::new(),
+            dotnet: None,
+            info: Vec::new(),
+        })
+    }
+
+    pub(crate) fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    pub fn header_info(&mut self) -> Vec<(String, String)> {
+        if self.info.is_empty() {
+            self.info = self.collect_info();
+        }
+        self.info.clone()
+    }
+
+    /// Decoded headers and summaries of the directories, the image digest is left to the
+    /// signatures table as it reads the whole file
+    fn collect_info(&mut self) -> Vec<(String, String)> {
+        let dotnet = self.get_dotnet();
+        let mut ident = match &self.optional_header {
+            Some(header) if header.is_64() => "Windows PE32+ binary".to_string(),
+            _ => "Windows PE binary".to_string(),
+        };
+        if self.dotnet.is_some() {
+            ident.push_str(", .NET assembly");
+        }
+        let mut info = vec![("Ident".to_string(), ident)];
+        info.extend(self.coff_header.info());
+        if let Some(optional_header) = &self.optional_header {
+            info.extend(optional_header.info());
+        }
+        match (dotnet, &self.dotnet) {
+            (Ok(()), Some(dotnet)) => info.extend(dotnet.info()),
+            (Ok(()), None) => {}
+            (Err(e), _) => info.push(("CLR metadata".to_string(), e.to_string())),
+        }
+        if self.get_exports().is_ok() {
+            if let Some(directory) = &self.export_directory {
+                info.push(("Export DLL name".to_string(), directory.dll_name.clone()));
+                info.push((
+                    "Export version".to_string(),
+                    format!("{}.{}", directory.version.0, directory.version.1),
+                ));
+                info.push((
+                    "Export time stamp".to_string(),
+                    format!(
+                        "0x{:08x} ({})",
+                        directory.time_date_stamp,
+                        format_timestamp(directory.time_date_stamp)
+                    ),
+                ));
+                info.push((
+                    "Export ordinal base".to_string(),
+                    directory.ordinal_base.to_string(),
+                ));
+                info.push((
+                    "Exported functions / names".to_string(),
+                    format!(
+                        "{} / {}",
+                        directory.number_of_functions, directory.number_of_names
+                    ),
+                ));
+            }
+        }
+        if self.get_resources().is_ok() {
+            if let Some(version) = self
+                .resources
+                .iter()
+                .find(|resource| resource.resource_type() == Some(ResourceType::Version))
+            {
+                if let Some(offset) = version.offset {
+                    if let Ok(version_info) =
+                        resources::parse_version_info(&self.blob, offset, version.size as usize)
+                    {
+                        info.extend(version_info);
+                    }
+                }
+            }
+        }
+        let authenticode = match self.data_directory(CERTIFICATE_DIRECTORY) {
+            Some(directory) => format!(
+                "certificate table of {} bytes, see Signatures",
+                directory.size
+            ),
+            None => "not signed".to_string(),
+        };
+        info.push(("Authenticode".to_string(), authenticode));
+        if let Ok(Some(overlay)) = self.overlay() {
+            info.push(("Overlay".to_string(), overlay.to_string()));
         }
+        match rich::parse_rich_header(&self.blob, self.dos_header.pe_offset as usize) {
+            Ok(Some(rich_header)) => info.push(("Rich header".to_string(), rich_header.summary())),
+            Ok(None) => {}
+            Err(e) => info.push(("Rich header".to_string(), e.to_string())),
+        }
+        // GUID and age identify the matching PDB file on a symbol server
+        if self.get_debug_entries().is_ok() {
+            for entry in &self.debug_entries {
+                if let DebugInfo::CodeView { guid, age, path } = &entry.info {
+                    info.push(("PDB GUID / age".to_string(), format!("{guid} / {age}")));
+                    info.push(("PDB path".to_string(), path.clone()));
+                }
+            }
+        }
+        info.extend(self.dos_header.info());
+        info
+    }
+
+    /// Data behind the section data, the COFF symbol table and debug data, up to the
+    /// certificate table
+    pub fn overlay(&mut self) -> Result<Option<Overlay>> {
+        self.get_sections()?;
+        // Broken debug data does not hide the overlay
+        let _ = self.get_debug_entries();
+        let mut end = self
+            .optional_header
+            .as_ref()
+            .map(|header| header.size_of_headers as usize)
+            .unwrap_or_default();
+        for section in &self.section_headers {
+            end = end.max(section.pointer_to_raw_data as usize + section.size_of_raw_data as usize);
+        }
+        // The symbol table is followed by the string table, which starts with its size
+        let pointer_to_symbol_table = self.coff_header.pointer_to_symbol_table as usize;
+        if pointer_to_symbol_table != 0 {
+            let string_table =
+                pointer_to_symbol_table + 18 * self.coff_header.number_of_symbols as usize;
+            let size = self.blob.get_u32(string_table).unwrap_or_default() as usize;
+            end = end.max(string_table + size.max(4));
+        }
+        for entry in &self.debug_entries {
+            end = end.max(entry.pointer_to_raw_data as usize + entry.size_of_data as usize);
+        }
+        // The certificate table is appended behind the image. Data in front of it is the
+        // overlay, data behind it only if the table directly follows the image.
+        if let Some(directory) = self.data_directory(CERTIFICATE_DIRECTORY) {
+            let table_start = directory.virtual_address as usize;
+            if table_start > end {
+                return Ok(Overlay::between(&self.blob, end, table_start)?);
+            }
+            end = end.max(table_start + directory.size as usize);
+        }
+        Ok(Overlay::new(&self.blob, end)?)
+    }
+
+    /// Hardening properties like ASLR, DEP, CFG and SafeSEH
+    pub fn checksec(&mut self) -> Result<Vec<(String, String)>> {
+        self.get_sections()?;
+        checksec::checksec(self)
+    }
+
+    pub fn section_headers_table(&mut self) -> Result<Table> {
+        self.get_sections()?;
+        let headers = [
+            "Nr.",
+            "Name",
+            "Characteristics",
+            "Flags",
+            "VirtAddress",
+            "VirtSize",
+            "FileOffset",
+            "FileSize",
+            "Relocations",
+            "Line Numbers",
+        ];
+        let mut rows = Vec::with_capacity(self.section_headers.len());
+        for (idx, section) in self.section_headers.iter().enumerate() {
+            let mut v = section.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push((idx + 1).to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::PeSectionHeaders, &headers, rows))
+    }
+
+    /// Functions imported by name or ordinal, including delay load imports
+    pub fn imports_table(&mut self) -> Result<Table> {
+        self.get_imports()?;
+        let headers = [
+            "Nr.",
+            "DLL",
+            "Kind",
+            "Name",
+            "Ordinal",
+            "Hint",
+            "IAT Address",
+        ];
+        let mut rows = Vec::with_capacity(self.imports.len());
+        for (idx, import) in self.imports.iter().enumerate() {
+            let mut v = import.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::PeImports, &headers, rows))
+    }
+
+    /// Exported functions, ordinal only exports have no name, forwarders point to another DLL
+    pub fn exports_table(&mut self) -> Result<Table> {
+        self.get_exports()?;
+        let headers = ["Nr.", "Ordinal", "Name", "Address", "Forwarder"];
+        let mut rows = Vec::with_capacity(self.exports.len());
+        for (idx, export) in self.exports.iter().enumerate() {
+            let mut v = export.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::PeExports, &headers, rows))
+    }
+
+    /// Leaves of the resource tree, rows can be viewed as hex dump
+    pub fn resources_table(&mut self) -> Result<Table> {
+        self.get_resources()?;
+        let headers = [
+            "Nr.",
+            "Type",
+            "Name",
+            "Language",
+            "Address",
+            "Size",
+            "Code page",
+            "Details",
+        ];
+        let mut rows = Vec::with_capacity(self.resources.len());
+        for (idx, resource) in self.resources.iter().enumerate() {
+            let mut v = resource.to_vec(&self.blob);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            let action = match resource.offset {
+                Some(offset) => RowAction::View {
+                    offset,
+                    size: resource.size as usize,
+                },
+                None => RowAction::None,
+            };
+            rows.push(Row { content, action });
+        }
+        Ok(Table::new(TableType::PeResources, &headers, rows))
+    }
+
+    /// Strings of all string table resources
+    pub fn resource_strings_table(&mut self) -> Result<Table> {
+        self.get_resources()?;
+        let headers = ["Nr.", "ID", "Language", "String"];
+        let mut rows = Vec::new();
+        for resource in &self.resources {
+            let (Some(ResourceType::String), ResourceId::Id(block), Some(offset)) =
+                (resource.resource_type(), &resource.name, resource.offset)
+            else {
+                continue;
+            };
+            let strings =
+                resources::string_table(&self.blob, offset, resource.size as usize, *block)?;
+            for (id, string) in strings {
+                let content = vec![
+                    rows.len().to_string(),
+                    id.to_string(),
+                    resource.language_as_string(),
+                    string,
+                ];
+                if headers.len() != content.len() {
+                    return Err(PeError::InternalError);
+                }
+                rows.push(Row {
+                    content,
+                    action: RowAction::None,
+                });
+            }
+        }
+        Ok(Table::new(TableType::PeResourceStrings, &headers, rows))
+    }
+
+    /// The embedded application manifest, one row per line
+    pub fn manifest_table(&mut self) -> Result<Table> {
+        self.get_resources()?;
+        let headers = ["Nr.", "Line"];
+        let mut rows = Vec::new();
+        for resource in &self.resources {
+            let (Some(ResourceType::Manifest), Some(offset)) =
+                (resource.resource_type(), resource.offset)
+            else {
+                continue;
+            };
+            let manifest = resources::manifest(&self.blob, offset, resource.size as usize)?;
+            for line in manifest.lines() {
+                let content = vec![(rows.len() + 1).to_string(), line.to_string()];
+                if headers.len() != content.len() {
+                    return Err(PeError::InternalError);
+                }
+                rows.push(Row {
+                    content,
+                    action: RowAction::None,
+                });
+            }
+        }
+        Ok(Table::new(TableType::PeManifest, &headers, rows))
+    }
+
+    /// Authenticode signatures with signer, time stamps and the check of the image digest
+    pub fn signatures_table(&mut self) -> Result<Table> {
+        self.get_signatures()?;
+        let headers = ["Nr.", "Signature", "Property", "Value"];
+        let mut rows = Vec::new();
+        for (idx, signature) in self.signatures.iter().enumerate() {
+            for (property, value, range) in signature.properties() {
+                let content = vec![
+                    rows.len().to_string(),
+                    (idx + 1).to_string(),
+                    property,
+                    value,
+                ];
+                if headers.len() != content.len() {
+                    return Err(PeError::InternalError);
+                }
+                let action = match range {
+                    Some((offset, size)) => RowAction::View { offset, size },
+                    None => RowAction::None,
+                };
+                rows.push(Row { content, action });
+            }
+        }
+        Ok(Table::new(TableType::PeSignatures, &headers, rows))
+    }
+
+    /// Locations patched by the loader when the image is rebased
+    pub fn base_relocations_table(&mut self) -> Result<Table> {
+        self.get_base_relocations()?;
+        let headers = ["Nr.", "Page", "Address", "Type", "Target"];
+        let machine = self.coff_header.machine;
+        let mut rows = Vec::with_capacity(self.base_relocations.len());
+        for (idx, relocation) in self.base_relocations.iter().enumerate() {
+            let mut v = relocation.to_vec(machine);
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(TableType::PeBaseRelocations, &headers, rows))
+    }
+
+    /// TLS directory with the callbacks that run before the entry point
+    pub fn tls_table(&mut self) -> Result<Table> {
+        self.get_sections()?;
+        let mut info = Vec::new();
+        if let Some(tls) = tls::parse_tls(self)? {
+            let hex = |value: u64| format!("0x{value:x}");
+            info.push((
+                "Raw data start / end".to_string(),
+                format!(
+                    "{} / {}",
+                    hex(tls.start_address_of_raw_data),
+                    hex(tls.end_address_of_raw_data)
+                ),
+            ));
+            info.push(("Address of index".to_string(), hex(tls.address_of_index)));
+            info.push((
+                "Address of callbacks".to_string(),
+                hex(tls.address_of_callbacks),
+            ));
+            info.push((
+                "Size of zero fill".to_string(),
+                tls.size_of_zero_fill.to_string(),
+            ));
+            info.push((
+                "Characteristics".to_string(),
+                format!("0x{:08x}", tls.characteristics),
+            ));
+            for callback in &tls.callbacks {
+                let rva = callback.wrapping_sub(self.image_base()) as u32;
+                let section = self.section_name(rva).unwrap_or("not mapped");
+                info.push((
+                    "Callback".to_string(),
+                    format!("{} (RVA 0x{rva:08x}, section {section})", hex(*callback)),
+                ));
+            }
+        }
+        self.key_value_table(TableType::PeTls, info)
+    }
+
+    /// Load configuration with security cookie, SEH handlers and CFG function table
+    pub fn load_config_table(&mut self) -> Result<Table> {
+        self.get_sections()?;
+        let info = match load_config::parse_load_config(self)? {
+            Some(load_config) => load_config.info(),
+            None => Vec::new(),
+        };
+        self.key_value_table(TableType::PeLoadConfig, info)
+    }
+
+    /// Debug directory with the PDB reference, POGO, repro hash and extended DLL characteristics
+    pub fn debug_table(&mut self) -> Result<Table> {
+        self.get_debug_entries()?;
+        let headers = [
+            "Nr.",
+            "Type",
+            "Time stamp",
+            "Version",
+            "Address",
+            "File offset",
+            "Size",
+            "Details",
+        ];
+        let mut rows = Vec::with_capacity(self.debug_entries.len());
+        for (idx, entry) in self.debug_entries.iter().enumerate() {
+            let mut v = entry.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            let action = if entry.pointer_to_raw_data != 0 && entry.size_of_data != 0 {
+                RowAction::View {
+                    offset: entry.pointer_to_raw_data as usize,
+                    size: entry.size_of_data as usize,
+                }
+            } else {
+                RowAction::None
+            };
+            rows.push(Row { content, action });
+        }
+        Ok(Table::new(TableType::PeDebug, &headers, rows))
+    }
+
+    /// Entries of the Rich header with the tool versions used to build the binary
+    pub fn rich_header_table(&self) -> Result<Table> {
+        let headers = [
+            "Nr.",
+            "Product id",
+            "Product",
+            "Build",
+            "Count",
+            "Visual Studio",
+        ];
+        let mut rows = Vec::new();
+        if let Some(rich_header) =
+            rich::parse_rich_header(&self.blob, self.dos_header.pe_offset as usize)?
+        {
+            for (idx, entry) in rich_header.entries.iter().enumerate() {
+                let mut v = entry.to_vec();
+                let mut content = Vec::with_capacity(v.len() + 1);
+                content.push(idx.to_string());
+                content.append(&mut v);
+                if headers.len() != content.len() {
+                    return Err(PeError::InternalError);
+                }
+                rows.push(Row {
+                    content,
+                    action: RowAction::View {
+                        offset: entry.offset,
+                        size: 8,
+                    },
+                });
+            }
+        }
+        Ok(Table::new(TableType::PeRichHeader, &headers, rows))
+    }
+
+    /// Namespaces, types and methods of a managed assembly with the referenced assemblies,
+    /// types and members
+    pub fn dotnet_table(&mut self) -> Result<Table> {
+        self.get_dotnet()?;
+        let headers = ["Nr.", "Kind", "Token", "Namespace", "Name", "Details"];
+        let mut entries = Vec::new();
+        if let Some(dotnet) = &self.dotnet {
+            let token = |token: u32| format!("0x{token:08x}");
+            for stream in &dotnet.streams {
+                entries.push((
+                    vec![
+                        "Stream".to_string(),
+                        String::new(),
+                        String::new(),
+                        stream.name.clone(),
+                        format!("offset 0x{:x}, {} bytes", stream.offset, stream.size),
+                    ],
+                    Some((stream.offset, stream.size)),
+                ));
+            }
+            if let Some(assembly) = &dotnet.assembly {
+                entries.push((
+                    vec![
+                        "Assembly".to_string(),
+                        token(assembly.token),
+                        String::new(),
+                        assembly.name.clone(),
+                        assembly.details(),
+                    ],
+                    None,
+                ));
+            }
+            for assembly_ref in &dotnet.assembly_refs {
+                entries.push((
+                    vec![
+                        "AssemblyRef".to_string(),
+                        token(assembly_ref.token),
+                        String::new(),
+                        assembly_ref.name.clone(),
+                        assembly_ref.details(),
+                    ],
+                    None,
+                ));
+            }
+            // Types are grouped by namespace, each followed by its methods
+            let mut type_defs: Vec<_> = dotnet.type_defs.iter().collect();
+            type_defs.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+            let mut namespace = None;
+            for type_def in type_defs {
+                if namespace != Some(&type_def.namespace) {
+                    namespace = Some(&type_def.namespace);
+                    entries.push((
+                        vec![
+                            "Namespace".to_string(),
+                            String::new(),
+                            if type_def.namespace.is_empty() {
+                                "(global)".to_string()
+                            } else {
+                                type_def.namespace.clone()
+                            },
+                            String::new(),
+                            String::new(),
+                        ],
+                        None,
+                    ));
+                }
+                entries.push((
+                    vec![
+                        "TypeDef".to_string(),
+                        token(type_def.token),
+                        type_def.namespace.clone(),
+                        type_def.name.clone(),
+                        type_def.details(),
+                    ],
+                    None,
+                ));
+                for method in dotnet
+                    .method_defs
+                    .get(type_def.methods.clone())
+                    .unwrap_or(&[])
+                {
+                    entries.push((
+                        vec![
+                            "MethodDef".to_string(),
+                            token(method.token),
+                            type_def.namespace.clone(),
+                            format!("{}::{}", type_def.name, method.name),
+                            method.details(),
+                        ],
+                        method.body,
+                    ));
+                }
+            }
+            for type_ref in &dotnet.type_refs {
+                entries.push((
+                    vec![
+                        "TypeRef".to_string(),
+                        token(type_ref.token),
+                        type_ref.namespace.clone(),
+                        type_ref.name.clone(),
+                        format!("scope {}", type_ref.scope),
+                    ],
+                    None,
+                ));
+            }
+            for member_ref in &dotnet.member_refs {
+                entries.push((
+                    vec![
+                        "MemberRef".to_string(),
+                        token(member_ref.token),
+                        String::new(),
+                        format!("{}::{}", member_ref.class, member_ref.name),
+                        String::new(),
+                    ],
+                    None,
+                ));
+            }
+            for user_string in &dotnet.user_strings {
+                entries.push((
+                    vec![
+                        "UserString".to_string(),
+                        String::new(),
+                        String::new(),
+                        user_string.clone(),
+                        String::new(),
+                    ],
+                    None,
+                ));
+            }
+        }
+        let mut rows = Vec::with_capacity(entries.len());
+        for (idx, (mut v, range)) in entries.into_iter().enumerate() {
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            let action = match range {
+                Some((offset, size)) => RowAction::View { offset, size },
+                None => RowAction::None,
+            };
+            rows.push(Row { content, action });
+        }
+        Ok(Table::new(TableType::PeDotNet, &headers, rows))
+    }
+
+    /// RUNTIME_FUNCTION entries of the exception directory, the function boundaries of x64
+    /// and ARM64 images
+    pub fn runtime_functions(&mut self) -> Result<Vec<RuntimeFunction>> {
+        self.get_sections()?;
+        exceptions::parse_runtime_functions(self)
+    }
+
+    pub fn exceptions_table(&mut self) -> Result<Table> {
+        let headers = [
+            "Nr.",
+            "Begin",
+            "End",
+            "Size",
+            "Unwind Data",
+            "Flags",
+            "Prolog",
+            "Frame",
+            "Unwind Codes",
+            "Handler",
+        ];
+        let functions = self.runtime_functions()?;
+        let mut rows = Vec::with_capacity(functions.len());
+        for (idx, function) in functions.iter().enumerate() {
+            let mut v = function.to_vec();
+            let mut content = Vec::with_capacity(v.len() + 1);
+            content.push(idx.to_string());
+            content.append(&mut v);
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            // Show the code of the function
+            let action = match self.rva_to_offset(function.begin_address)? {
+                Some(offset) if function.size() > 0 => RowAction::View {
+                    offset,
+                    size: function.size() as usize,
+                },
+                _ => RowAction::None,
+            };
+            rows.push(Row { content, action });
+        }
+        Ok(Table::new(TableType::PeExceptions, &headers, rows))
+    }
+
+    fn key_value_table(&self, table_type: TableType, info: Vec<(String, String)>) -> Result<Table> {
+        let headers = ["Nr.", "Field", "Value"];
+        let mut rows = Vec::with_capacity(info.len());
+        for (idx, (field, value)) in info.into_iter().enumerate() {
+            let content = vec![idx.to_string(), field, value];
+            if headers.len() != content.len() {
+                return Err(PeError::InternalError);
+            }
+            rows.push(Row {
+                content,
+                action: RowAction::None,
+            });
+        }
+        Ok(Table::new(table_type, &headers, rows))
+    }
+
+    fn get_dotnet(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.dotnet.is_none() {
+            self.dotnet = dotnet::parse_dotnet(self)?;
+        }
+        Ok(())
+    }
+
+    fn get_debug_entries(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.debug_entries.is_empty() {
+            self.debug_entries = debug::parse_debug_directory(self)?;
+        }
+        Ok(())
+    }
+
+    fn get_base_relocations(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.base_relocations.is_empty() {
+            self.base_relocations = base_relocations::parse_base_relocations(self)?;
+        }
+        Ok(())
+    }
+
+    fn get_signatures(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.signatures.is_empty() {
+            self.signatures = authenticode::parse_signatures(self)?;
+        }
+        Ok(())
     }
 
-    pub fn header_info(&self) -> Vec<(String, String)> {
-        vec![("Ident".to_string(), "Windows PE binary".to_string())]
+    fn get_resources(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.resources.is_empty() {
+            self.resources = resources::parse_resources(self)?;
+        }
+        Ok(())
+    }
+
+    fn get_exports(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.export_directory.is_none() {
+            if let Some((directory, exports)) = exports::parse_exports(self)? {
+                self.export_directory = Some(directory);
+                self.exports = exports;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_imports(&mut self) -> Result<()> {
+        self.get_sections()?;
+        if self.imports.is_empty() {
+            self.imports = imports::parse_imports(self)?;
+        }
+        Ok(())
+    }
+
+    fn get_sections(&mut self) -> Result<()> {
+        if self.section_headers.is_empty() {
+            // Long section names are only used in object files, images have no string table
+            let string_table_offset = if self.coff_header.pointer_to_symbol_table == 0 {
+                None
+            } else {
+                Some(
+                    self.coff_header.pointer_to_symbol_table as usize
+                        + 18 * self.coff_header.number_of_symbols as usize,
+                )
+            };
+            let mut idx = self.dos_header.pe_offset as usize
+                + 24
+                + self.coff_header.size_of_optional_header as usize;
+            for _ in 0..self.coff_header.number_of_sections {
+                self.section_headers.push(SectionHeader::new(
+                    &self.blob,
+                    idx,
+                    string_table_offset,
+                )?);
+                idx += 40;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_64(&self) -> bool {
+        self.optional_header
+            .as_ref()
+            .is_some_and(|header| header.is_64())
+    }
+
+    /// Data directory `index`, if present and not empty
+    fn data_directory(&self, index: usize) -> Option<DataDirectory> {
+        self.optional_header
+            .as_ref()
+            .and_then(|header| header.data_directory(index))
+    }
+
+    fn image_base(&self) -> u64 {
+        self.optional_header
+            .as_ref()
+            .map(|header| header.image_base)
+            .unwrap_or_default()
+    }
+
+    /// Name of the section containing `rva`, sections have to be loaded
+    fn section_name(&self, rva: u32) -> Option<&str> {
+        self.section_headers
+            .iter()
+            .find(|section| section.contains_rva(rva))
+            .map(|section| section.name.as_str())
+    }
+
+    /// Zero terminated string at a relative virtual address
+    fn rva_to_string(&self, rva: u32) -> Result<String> {
+        Ok(self.blob.get_cname(self.rva_to_offset(rva)?)?)
+    }
+
+    /// Translate a relative virtual address into a file offset, sections have to be loaded
+    fn rva_to_offset(&self, rva: u32) -> Result<Option<usize>> {
+        // Addresses within the headers map one to one
+        if let Some(header) = &self.optional_header {
+            if rva < header.size_of_headers {
+                return Ok(Some(rva as usize));
+            }
+        }
+        Ok(self
+            .section_headers
+            .iter()
+            .find(|section| section.has_raw_data_at(rva))
+            .and_then(|section| {
+                (section.pointer_to_raw_data as usize)
+                    .checked_add((rva - section.virtual_address) as usize)
+            }))
     }
 }
@@ -0,0 +1,438 @@
+use super::{align_up, PeBinary, PeError, Result};
+use crate::blob::Blob;
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use strum::FromRepr;
+
+const RESOURCE_DIRECTORY: usize = 2;
+
+// Entries with this bit set point to a subdirectory or a name string
+const RESOURCE_SUBDIRECTORY: u32 = 0x80000000;
+
+// Signature of VS_FIXEDFILEINFO
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef04bd;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+#[repr(u32)]
+#[derive(Debug, FromRepr, PartialEq, Eq, Clone, Copy)]
+pub enum ResourceType {
+    Cursor = 1,
+    Bitmap = 2,
+    Icon = 3,
+    Menu = 4,
+    Dialog = 5,
+    String = 6,
+    FontDir = 7,
+    Font = 8,
+    Accelerator = 9,
+    RcData = 10,
+    MessageTable = 11,
+    GroupCursor = 12,
+    GroupIcon = 14,
+    Version = 16,
+    DlgInclude = 17,
+    PlugPlay = 19,
+    Vxd = 20,
+    AniCursor = 21,
+    AniIcon = 22,
+    Html = 23,
+    Manifest = 24,
+}
+
+impl Display for ResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        match self {
+            ResourceType::Cursor => write!(f, "Cursor"),
+            ResourceType::Bitmap => write!(f, "Bitmap"),
+            ResourceType::Icon => write!(f, "Icon"),
+            ResourceType::Menu => write!(f, "Menu"),
+            ResourceType::Dialog => write!(f, "Dialog"),
+            ResourceType::String => write!(f, "String table"),
+            ResourceType::FontDir => write!(f, "Font directory"),
+            ResourceType::Font => write!(f, "Font"),
+            ResourceType::Accelerator => write!(f, "Accelerators"),
+            ResourceType::RcData => write!(f, "Raw data"),
+            ResourceType::MessageTable => write!(f, "Message table"),
+            ResourceType::GroupCursor => write!(f, "Cursor group"),
+            ResourceType::GroupIcon => write!(f, "Icon group"),
+            ResourceType::Version => write!(f, "Version info"),
+            ResourceType::DlgInclude => write!(f, "Dialog include"),
+            ResourceType::PlugPlay => write!(f, "Plug and play"),
+            ResourceType::Vxd => write!(f, "VXD"),
+            ResourceType::AniCursor => write!(f, "Animated cursor"),
+            ResourceType::AniIcon => write!(f, "Animated icon"),
+            ResourceType::Html => write!(f, "HTML"),
+            ResourceType::Manifest => write!(f, "Manifest"),
+        }
+    }
+}
+
+/// Resources are identified either by number or by name
+#[derive(Clone, PartialEq, Eq)]
+pub enum ResourceId {
+    Id(u32),
+    Name(String),
+}
+
+impl Display for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        match self {
+            ResourceId::Id(id) => write!(f, "{id}"),
+            ResourceId::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+/// A leaf of the resource tree
+pub struct Resource {
+    pub(super) type_id: ResourceId,
+    pub(super) name: ResourceId,
+    pub(super) language: ResourceId,
+    pub(super) rva: u32,
+    pub(super) size: u32,
+    pub(super) code_page: u32,
+    // File offset of the data, if mapped by a section
+    pub(super) offset: Option<usize>,
+}
+
+impl Resource {
+    pub(super) fn resource_type(&self) -> Option<ResourceType> {
+        match self.type_id {
+            ResourceId::Id(id) => ResourceType::from_repr(id),
+            ResourceId::Name(_) => None,
+        }
+    }
+
+    pub(super) fn type_as_string(&self) -> String {
+        match self.resource_type() {
+            Some(resource_type) => resource_type.to_string(),
+            None => self.type_id.to_string(),
+        }
+    }
+
+    pub(super) fn language_as_string(&self) -> String {
+        match self.language {
+            ResourceId::Id(0) => "neutral".to_string(),
+            ResourceId::Id(id) => format!("0x{id:04x}"),
+            ResourceId::Name(ref name) => name.clone(),
+        }
+    }
+
+    /// Short description of the content for the common resource types
+    pub(super) fn details(&self, blob: &Blob) -> String {
+        let Some(offset) = self.offset else {
+            return "not mapped".to_string();
+        };
+        let size = self.size as usize;
+        let details = match self.resource_type() {
+            Some(ResourceType::Icon) => icon_details(blob, offset),
+            Some(ResourceType::GroupIcon) | Some(ResourceType::GroupCursor) => blob
+                .get_u16(offset + 4)
+                .map(|count| format!("{count} images"))
+                .ok(),
+            Some(ResourceType::Dialog) => dialog_details(blob, offset, size),
+            Some(ResourceType::Version) => {
+                parse_version_info(blob, offset, size).ok().map(|info| {
+                    info.into_iter()
+                        .filter(|(key, _)| key == "FileVersion" || key == "ProductName")
+                        .map(|(key, value)| format!("{key}: {value}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+            }
+            Some(ResourceType::String) => match self.name {
+                ResourceId::Id(block) => string_table(blob, offset, size, block)
+                    .ok()
+                    .map(|strings| format!("{} strings", strings.len())),
+                ResourceId::Name(_) => None,
+            },
+            Some(ResourceType::Manifest) => Some("XML application manifest".to_string()),
+            _ => None,
+        };
+        details.unwrap_or_default()
+    }
+
+    pub(super) fn to_vec(&self, blob: &Blob) -> Vec<String> {
+        vec![
+            self.type_as_string(),
+            self.name.to_string(),
+            self.language_as_string(),
+            format!("0x{:08x}", self.rva),
+            self.size.to_string(),
+            self.code_page.to_string(),
+            self.details(blob),
+        ]
+    }
+}
+
+/// Walk the three levels of the resource tree: type, name and language
+pub(super) fn parse_resources(binary: &PeBinary) -> Result<Vec<Resource>> {
+    let mut resources = Vec::new();
+    let Some(directory) = binary.data_directory(RESOURCE_DIRECTORY) else {
+        return Ok(resources);
+    };
+    let Some(base) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(resources);
+    };
+    let blob = &binary.blob;
+    // Each directory is walked once, shared directories would multiply the number of leaves
+    let mut visited = HashSet::from([base]);
+    for (type_id, type_entry) in directory_entries(blob, base, base)? {
+        let Some(type_dir) = subdirectory(base, type_entry).filter(|dir| visited.insert(*dir))
+        else {
+            continue;
+        };
+        for (name, name_entry) in directory_entries(blob, base, type_dir)? {
+            let Some(name_dir) = subdirectory(base, name_entry).filter(|dir| visited.insert(*dir))
+            else {
+                continue;
+            };
+            for (language, data_entry) in directory_entries(blob, base, name_dir)? {
+                // The last level points to a data entry
+                if data_entry & RESOURCE_SUBDIRECTORY != 0 {
+                    continue;
+                }
+                let entry = base + data_entry as usize;
+                let rva = blob.get_u32(entry)?;
+                resources.push(Resource {
+                    type_id: type_id.clone(),
+                    name: name.clone(),
+                    language,
+                    rva,
+                    size: blob.get_u32(entry + 4)?,
+                    code_page: blob.get_u32(entry + 8)?,
+                    offset: binary.rva_to_offset(rva)?,
+                });
+            }
+        }
+    }
+    Ok(resources)
+}
+
+fn subdirectory(base: usize, entry: u32) -> Option<usize> {
+    if entry & RESOURCE_SUBDIRECTORY != 0 {
+        Some(base + (entry & !RESOURCE_SUBDIRECTORY) as usize)
+    } else {
+        None
+    }
+}
+
+/// Entries of one directory table, named entries come first, followed by numbered ones
+fn directory_entries(blob: &Blob, base: usize, offset: usize) -> Result<Vec<(ResourceId, u32)>> {
+    let count = blob.get_u16(offset + 12)? as usize + blob.get_u16(offset + 14)? as usize;
+    if offset + 16 + 8 * count > blob.len() {
+        return Err(PeError::InternalError);
+    }
+    let mut entries = Vec::with_capacity(count);
+    for idx in 0..count {
+        let entry = offset + 16 + 8 * idx;
+        let name = blob.get_u32(entry)?;
+        let id = if name & RESOURCE_SUBDIRECTORY != 0 {
+            // Names are counted UTF-16 strings relative to the start of the resource section
+            let name_offset = base + (name & !RESOURCE_SUBDIRECTORY) as usize;
+            let len = blob.get_u16(name_offset)? as usize;
+            ResourceId::Name(utf16_string(blob, name_offset + 2, len)?)
+        } else {
+            ResourceId::Id(name)
+        };
+        entries.push((id, blob.get_u32(entry + 4)?));
+    }
+    Ok(entries)
+}
+
+/// UTF-16 string of `len` characters
+fn utf16_string(blob: &Blob, offset: usize, len: usize) -> Result<String> {
+    let bytes = blob.get_slice(offset, 2 * len)?;
+    let chars: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&chars))
+}
+
+/// Zero terminated UTF-16 string, returns the string and the offset after the terminator
+fn utf16_cstring(blob: &Blob, offset: usize, end: usize) -> Result<(String, usize)> {
+    let mut chars = Vec::new();
+    let mut idx = offset;
+    while idx + 2 <= end {
+        let c = blob.get_u16(idx)?;
+        idx += 2;
+        if c == 0 {
+            break;
+        }
+        chars.push(c);
+    }
+    Ok((String::from_utf16_lossy(&chars), idx))
+}
+
+fn icon_details(blob: &Blob, offset: usize) -> Option<String> {
+    let header = blob.get_slice(offset, 24).ok()?;
+    if header[..8] == PNG_SIGNATURE {
+        // Width and height of the IHDR chunk are big endian
+        let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+        return Some(format!("{width}x{height} PNG"));
+    }
+    // A BITMAPINFOHEADER, the height covers both the color and the mask bitmap
+    let width = blob.get_u32(offset + 4).ok()?;
+    let height = blob.get_u32(offset + 8).ok()? / 2;
+    let bit_count = blob.get_u16(offset + 14).ok()?;
+    Some(format!("{width}x{height}, {bit_count} bit"))
+}
+
+fn dialog_details(blob: &Blob, offset: usize, size: usize) -> Option<String> {
+    let end = offset + size;
+    // Extended templates start with version 1 and signature 0xffff
+    let extended = blob.get_u16(offset).ok()? == 1 && blob.get_u16(offset + 2).ok()? == 0xffff;
+    let (kind, items, rect) = if extended {
+        ("DIALOGEX", offset + 16, offset + 18)
+    } else {
+        ("DIALOG", offset + 8, offset + 10)
+    };
+    let items = blob.get_u16(items).ok()?;
+    let width = blob.get_u16(rect + 4).ok()?;
+    let height = blob.get_u16(rect + 6).ok()?;
+    // Menu and window class are either absent, an ordinal or a string, followed by the title
+    let mut idx = rect + 8;
+    for _ in 0..2 {
+        match blob.get_u16(idx).ok()? {
+            0 => idx += 2,
+            0xffff => idx += 4,
+            _ => idx = utf16_cstring(blob, idx, end).ok()?.1,
+        }
+    }
+    let (title, _) = utf16_cstring(blob, idx, end).ok()?;
+    Some(format!(
+        "{kind} \"{title}\", {width}x{height} units, {items} controls"
+    ))
+}
+
+/// A node of the version info tree, the value is either text or binary
+struct VersionNode {
+    key: String,
+    value_offset: usize,
+    value_size: usize,
+    is_text: bool,
+    children: Vec<VersionNode>,
+}
+
+fn parse_version_node(blob: &Blob, offset: usize, end: usize) -> Result<VersionNode> {
+    let length = blob.get_u16(offset)? as usize;
+    let value_length = blob.get_u16(offset + 2)? as usize;
+    let is_text = blob.get_u16(offset + 4)? == 1;
+    let end = end.min(offset + length);
+    let (key, key_end) = utf16_cstring(blob, offset + 6, end)?;
+    let value_offset = align_up(key_end, 4);
+    // Text values are measured in characters
+    let value_size = if is_text {
+        2 * value_length
+    } else {
+        value_length
+    };
+    let mut children = Vec::new();
+    let mut idx = align_up(value_offset + value_size, 4);
+    while idx + 6 <= end {
+        let child_length = blob.get_u16(idx)? as usize;
+        if child_length == 0 {
+            break;
+        }
+        children.push(parse_version_node(blob, idx, end)?);
+        idx = align_up(idx + child_length, 4);
+    }
+    Ok(VersionNode {
+        key,
+        value_offset,
+        value_size: value_size.min(end.saturating_sub(value_offset)),
+        is_text,
+        children,
+    })
+}
+
+fn version_as_string(most: u32, least: u32) -> String {
+    format!(
+        "{}.{}.{}.{}",
+        most >> 16,
+        most & 0xffff,
+        least >> 16,
+        least & 0xffff
+    )
+}
+
+/// Decode a VS_VERSIONINFO resource into the fixed file versions and the string file info
+pub(super) fn parse_version_info(
+    blob: &Blob,
+    offset: usize,
+    size: usize,
+) -> Result<Vec<(String, String)>> {
+    let root = parse_version_node(blob, offset, offset + size)?;
+    if root.key != "VS_VERSION_INFO" {
+        return Err(PeError::InternalError);
+    }
+    let mut info = Vec::new();
+    if root.value_size >= 52 && blob.get_u32(root.value_offset)? == FIXED_FILE_INFO_SIGNATURE {
+        let fixed = root.value_offset;
+        info.push((
+            "Fixed file version".to_string(),
+            version_as_string(blob.get_u32(fixed + 8)?, blob.get_u32(fixed + 12)?),
+        ));
+        info.push((
+            "Fixed product version".to_string(),
+            version_as_string(blob.get_u32(fixed + 16)?, blob.get_u32(fixed + 20)?),
+        ));
+    }
+    // StringFileInfo holds one table per language and code page
+    for file_info in root
+        .children
+        .iter()
+        .filter(|node| node.key == "StringFileInfo")
+    {
+        for table in &file_info.children {
+            for string in &table.children {
+                let value = if string.is_text {
+                    utf16_cstring(
+                        blob,
+                        string.value_offset,
+                        string.value_offset + string.value_size,
+                    )?
+                    .0
+                } else {
+                    String::new()
+                };
+                info.push((string.key.clone(), value));
+            }
+        }
+    }
+    Ok(info)
+}
+
+/// The 16 strings of a string table block, empty strings are skipped
+pub(super) fn string_table(
+    blob: &Blob,
+    offset: usize,
+    size: usize,
+    block: u32,
+) -> Result<Vec<(u32, String)>> {
+    let end = offset + size;
+    let mut strings = Vec::new();
+    let mut idx = offset;
+    for n in 0..16 {
+        if idx + 2 > end {
+            break;
+        }
+        let len = blob.get_u16(idx)? as usize;
+        if len > 0 {
+            let id = block.wrapping_sub(1).wrapping_mul(16) + n;
+            strings.push((id, utf16_string(blob, idx + 2, len)?));
+        }
+        idx += 2 + 2 * len;
+    }
+    Ok(strings)
+}
+
+/// The manifest is UTF-8 encoded XML, possibly starting with a byte order mark
+pub(super) fn manifest(blob: &Blob, offset: usize, size: usize) -> Result<String> {
+    let bytes = blob.get_slice(offset, size)?;
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    Ok(String::from_utf8_lossy(bytes).to_string())
+}
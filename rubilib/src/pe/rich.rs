@@ -0,0 +1,384 @@
+use super::{PeError, Result};
+use crate::blob::Blob;
+
+// "Rich" marks the end of the header and is followed by the XOR key
+const RICH_SIGNATURE: u32 = 0x68636952;
+// "DanS" marks the start of the header once decoded
+const DANS_SIGNATURE: u32 = 0x536e6144;
+// The header is placed behind the DOS header and stub
+const DOS_HEADER_SIZE: usize = 0x40;
+// Position of the PE offset in the DOS header, which is excluded from the checksum
+const PE_OFFSET_FIELD: usize = 0x3c;
+
+// Names of the product ids used by the Microsoft tool chain, the index is the product id.
+// Later tool versions use the blocks listed in PRODUCT_BLOCKS.
+const PRODUCT_NAMES: [&str; 0x98] = [
+    "Unknown",
+    "Import0",
+    "Linker510",
+    "Cvtomf510",
+    "Linker600",
+    "Cvtomf600",
+    "Cvtres500",
+    "Utc11_Basic",
+    "Utc11_C",
+    "Utc12_Basic",
+    "Utc12_C",
+    "Utc12_CPP",
+    "AliasObj60",
+    "VisualBasic60",
+    "Masm613",
+    "Masm710",
+    "Linker511",
+    "Cvtomf511",
+    "Masm614",
+    "Linker512",
+    "Cvtomf512",
+    "Utc12_C_Std",
+    "Utc12_CPP_Std",
+    "Utc12_C_Book",
+    "Utc12_CPP_Book",
+    "Implib700",
+    "Cvtomf700",
+    "Utc13_Basic",
+    "Utc13_C",
+    "Utc13_CPP",
+    "Linker610",
+    "Cvtomf610",
+    "Linker601",
+    "Cvtomf601",
+    "Utc12_1_Basic",
+    "Utc12_1_C",
+    "Utc12_1_CPP",
+    "Linker620",
+    "Cvtomf620",
+    "AliasObj70",
+    "Linker621",
+    "Cvtomf621",
+    "Masm615",
+    "Utc13_LTCG_C",
+    "Utc13_LTCG_CPP",
+    "Masm620",
+    "ILAsm100",
+    "Utc12_2_Basic",
+    "Utc12_2_C",
+    "Utc12_2_CPP",
+    "Utc12_2_C_Std",
+    "Utc12_2_CPP_Std",
+    "Utc12_2_C_Book",
+    "Utc12_2_CPP_Book",
+    "Implib622",
+    "Cvtomf622",
+    "Cvtres501",
+    "Utc13_C_Std",
+    "Utc13_CPP_Std",
+    "Cvtpgd1300",
+    "Linker622",
+    "Linker700",
+    "Export622",
+    "Export700",
+    "Masm700",
+    "Utc13_POGO_I_C",
+    "Utc13_POGO_I_CPP",
+    "Utc13_POGO_O_C",
+    "Utc13_POGO_O_CPP",
+    "Cvtres700",
+    "Cvtres710p",
+    "Linker710p",
+    "Cvtomf710p",
+    "Export710p",
+    "Implib710p",
+    "Masm710p",
+    "Utc1310p_C",
+    "Utc1310p_CPP",
+    "Utc1310p_C_Std",
+    "Utc1310p_CPP_Std",
+    "Utc1310p_LTCG_C",
+    "Utc1310p_LTCG_CPP",
+    "Utc1310p_POGO_I_C",
+    "Utc1310p_POGO_I_CPP",
+    "Utc1310p_POGO_O_C",
+    "Utc1310p_POGO_O_CPP",
+    "Linker624",
+    "Cvtomf624",
+    "Export624",
+    "Implib624",
+    "Linker710",
+    "Cvtomf710",
+    "Export710",
+    "Implib710",
+    "Cvtres710",
+    "Utc1310_C",
+    "Utc1310_CPP",
+    "Utc1310_C_Std",
+    "Utc1310_CPP_Std",
+    "Utc1310_LTCG_C",
+    "Utc1310_LTCG_CPP",
+    "Utc1310_POGO_I_C",
+    "Utc1310_POGO_I_CPP",
+    "Utc1310_POGO_O_C",
+    "Utc1310_POGO_O_CPP",
+    "AliasObj710",
+    "AliasObj710p",
+    "Cvtpgd1310",
+    "Cvtpgd1310p",
+    "Utc1400_C",
+    "Utc1400_CPP",
+    "Utc1400_C_Std",
+    "Utc1400_CPP_Std",
+    "Utc1400_LTCG_C",
+    "Utc1400_LTCG_CPP",
+    "Utc1400_POGO_I_C",
+    "Utc1400_POGO_I_CPP",
+    "Utc1400_POGO_O_C",
+    "Utc1400_POGO_O_CPP",
+    "Cvtpgd1400",
+    "Linker800",
+    "Cvtomf800",
+    "Export800",
+    "Implib800",
+    "Cvtres800",
+    "Masm800",
+    "AliasObj800",
+    "PhoenixPrerelease",
+    "Utc1400_CVTCIL_C",
+    "Utc1400_CVTCIL_CPP",
+    "Utc1400_LTCG_MSIL",
+    "Utc1500_C",
+    "Utc1500_CPP",
+    "Utc1500_C_Std",
+    "Utc1500_CPP_Std",
+    "Utc1500_CVTCIL_C",
+    "Utc1500_CVTCIL_CPP",
+    "Utc1500_LTCG_C",
+    "Utc1500_LTCG_CPP",
+    "Utc1500_LTCG_MSIL",
+    "Utc1500_POGO_I_C",
+    "Utc1500_POGO_I_CPP",
+    "Utc1500_POGO_O_C",
+    "Utc1500_POGO_O_CPP",
+    "Cvtpgd1500",
+    "Linker900",
+    "Export900",
+    "Implib900",
+    "Cvtres900",
+    "Masm900",
+    "AliasObj900",
+    "Resource",
+];
+
+// Tools of the Visual Studio 2010 and later blocks, in product id order
+const BLOCK_TOOLS: [&str; 7] = [
+    "AliasObj", "Cvtpgd", "Cvtres", "Export", "Implib", "Linker", "Masm",
+];
+
+// Compiler variants, in product id order
+const COMPILER_VARIANTS: [&str; 11] = [
+    "C",
+    "CPP",
+    "CVTCIL_C",
+    "CVTCIL_CPP",
+    "LTCG_C",
+    "LTCG_CPP",
+    "LTCG_MSIL",
+    "POGO_I_C",
+    "POGO_I_CPP",
+    "POGO_O_C",
+    "POGO_O_CPP",
+];
+
+// First product id, tool version and compiler version of each block. The Visual Studio
+// 2010 block has an additional set of Phoenix compilers.
+const PRODUCT_BLOCKS: [(u16, &str, &str); 6] = [
+    (0x0098, "1000", "1600"),
+    (0x00b5, "1010", "1610"),
+    (0x00c7, "1100", "1700"),
+    (0x00d9, "1200", "1800"),
+    (0x00eb, "1210", "1810"),
+    (0x00fd, "1400", "1900"),
+];
+
+/// One record of the Rich header, the number of objects built by a tool version
+pub struct RichEntry {
+    pub(super) product_id: u16,
+    pub(super) build: u16,
+    pub(super) count: u32,
+    // File offset of the encoded record
+    pub(super) offset: usize,
+}
+
+impl RichEntry {
+    pub(super) fn product_name(&self) -> String {
+        if let Some(name) = PRODUCT_NAMES.get(self.product_id as usize) {
+            return name.to_string();
+        }
+        for (idx, (start, tool_version, compiler_version)) in PRODUCT_BLOCKS.iter().enumerate() {
+            let end = PRODUCT_BLOCKS
+                .get(idx + 1)
+                .map(|(start, _, _)| *start)
+                .unwrap_or(start + (BLOCK_TOOLS.len() + COMPILER_VARIANTS.len()) as u16);
+            if self.product_id < *start || self.product_id >= end {
+                continue;
+            }
+            let mut pos = (self.product_id - start) as usize;
+            if pos < BLOCK_TOOLS.len() {
+                let version = if BLOCK_TOOLS[pos] == "Cvtpgd" {
+                    compiler_version
+                } else {
+                    tool_version
+                };
+                return format!("{}{version}", BLOCK_TOOLS[pos]);
+            }
+            pos -= BLOCK_TOOLS.len();
+            if idx == 0 {
+                if pos < COMPILER_VARIANTS.len() {
+                    return format!("Phx{compiler_version}_{}", COMPILER_VARIANTS[pos]);
+                }
+                pos -= COMPILER_VARIANTS.len();
+            }
+            if let Some(variant) = COMPILER_VARIANTS.get(pos) {
+                return format!("Utc{compiler_version}_{variant}");
+            }
+        }
+        "Unknown".to_string()
+    }
+
+    /// Visual Studio release shipping the tool, derived from the product id and build number
+    pub(super) fn visual_studio(&self) -> &'static str {
+        match self.product_id {
+            0x0002 | 0x0003 | 0x0006..=0x0008 | 0x0010 | 0x0011 | 0x0013 | 0x0014 => {
+                "Visual Studio 97"
+            }
+            0x0004 | 0x0005 | 0x0009..=0x000e | 0x0012 | 0x0015..=0x0018 | 0x001e..=0x0026 => {
+                "Visual Studio 6.0"
+            }
+            0x0028..=0x002a | 0x002d | 0x002f..=0x0038 | 0x003c | 0x003e | 0x0056..=0x0059 => {
+                "Visual Studio 6.0"
+            }
+            0x0019..=0x001d | 0x0027 | 0x002b | 0x002c | 0x002e | 0x0039..=0x003b => {
+                "Visual Studio 2002"
+            }
+            0x003d | 0x003f..=0x0045 => "Visual Studio 2002",
+            0x0046..=0x0055 | 0x006a | 0x006c => "Visual Studio 2003 prerelease",
+            0x000f | 0x005a..=0x0069 | 0x006b => "Visual Studio 2003",
+            0x006d..=0x0082 => "Visual Studio 2005",
+            0x0083..=0x0096 => "Visual Studio 2008",
+            0x0098..=0x00b4 => "Visual Studio 2010",
+            0x00b5..=0x00c6 => "Visual Studio 2010 SP1",
+            0x00c7..=0x00d8 => "Visual Studio 2012",
+            0x00d9..=0x00ea => "Visual Studio 2013",
+            0x00eb..=0x00fc => "Visual Studio 2013 Update",
+            // Later releases kept the product ids of 2015, only the build number changed
+            0x00fd..=0x010e => match self.build {
+                0..=24999 => "Visual Studio 2015",
+                25000..=27499 => "Visual Studio 2017",
+                27500..=30699 => "Visual Studio 2019",
+                _ => "Visual Studio 2022",
+            },
+            _ => "",
+        }
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<String> {
+        vec![
+            format!("0x{:04x}", self.product_id),
+            self.product_name(),
+            self.build.to_string(),
+            self.count.to_string(),
+            self.visual_studio().to_string(),
+        ]
+    }
+}
+
+/// The Rich header the Microsoft linker places between the DOS stub and the PE header
+pub struct RichHeader {
+    // File offset of the "DanS" marker
+    pub(super) offset: usize,
+    pub(super) key: u32,
+    // Checksum of the DOS header, stub and entries, must match the key
+    pub(super) checksum: u32,
+    pub(super) entries: Vec<RichEntry>,
+}
+
+impl RichHeader {
+    pub(super) fn is_valid(&self) -> bool {
+        self.checksum == self.key
+    }
+
+    pub(super) fn summary(&self) -> String {
+        let check = if self.is_valid() {
+            "checksum valid".to_string()
+        } else {
+            format!("checksum MISMATCH (computed 0x{:08x})", self.checksum)
+        };
+        format!(
+            "offset 0x{:x}, key 0x{:08x}, {} entries, {check}",
+            self.offset,
+            self.key,
+            self.entries.len()
+        )
+    }
+}
+
+/// Decode the Rich header in front of the PE header at `pe_offset`
+pub(super) fn parse_rich_header(blob: &Blob, pe_offset: usize) -> Result<Option<RichHeader>> {
+    let end = pe_offset.min(blob.len());
+    let mut rich = None;
+    let mut idx = DOS_HEADER_SIZE;
+    while idx + 8 <= end {
+        if blob.get_u32(idx)? == RICH_SIGNATURE {
+            rich = Some(idx);
+            break;
+        }
+        idx += 4;
+    }
+    let Some(rich) = rich else {
+        return Ok(None);
+    };
+    let key = blob.get_u32(rich + 4)?;
+
+    // Search backwards for the start marker, all values are XORed with the key
+    let mut start = None;
+    let mut idx = rich;
+    while idx >= DOS_HEADER_SIZE + 4 {
+        idx -= 4;
+        if blob.get_u32(idx)? ^ key == DANS_SIGNATURE {
+            start = Some(idx);
+            break;
+        }
+    }
+    // A "Rich" marker without start is not a Rich header
+    let Some(start) = start else {
+        return Ok(None);
+    };
+    // Three zero padding values follow the start marker
+    if start + 16 > rich || (rich - start - 16) % 8 != 0 {
+        return Err(PeError::InternalError);
+    }
+
+    let mut checksum = start as u32;
+    for (idx, byte) in blob.get_slice(0, start)?.iter().enumerate() {
+        if (PE_OFFSET_FIELD..PE_OFFSET_FIELD + 4).contains(&idx) {
+            continue;
+        }
+        checksum = checksum.wrapping_add((*byte as u32).rotate_left(idx as u32));
+    }
+    let mut entries = Vec::new();
+    for offset in (start + 16..rich).step_by(8) {
+        let comp_id = blob.get_u32(offset)? ^ key;
+        let count = blob.get_u32(offset + 4)? ^ key;
+        checksum = checksum.wrapping_add(comp_id.rotate_left(count));
+        entries.push(RichEntry {
+            product_id: (comp_id >> 16) as u16,
+            build: comp_id as u16,
+            count,
+            offset,
+        });
+    }
+    Ok(Some(RichHeader {
+        offset: start,
+        key,
+        checksum,
+        entries,
+    }))
+}
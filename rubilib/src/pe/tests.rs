@@ -0,0 +1,600 @@
+use super::*;
+
+const FILE_ALIGNMENT: usize = 0x200;
+const SECTION_ALIGNMENT: u32 = 0x1000;
+const OPTIONAL_HEADER_OFFSET: usize = 0x58;
+const OPTIONAL_HEADER_SIZE: usize = 0xf0;
+
+/// Section of a hand built PE file, mapped at the virtual address and stored at the
+/// next file aligned offset unless the raw data pointer is given
+struct TestSection {
+    name: &'static str,
+    virtual_address: u32,
+    pointer_to_raw_data: Option<u32>,
+    data: Vec<u8>,
+}
+
+fn section(name: &'static str, virtual_address: u32, data: Vec<u8>) -> TestSection {
+    TestSection {
+        name,
+        virtual_address,
+        pointer_to_raw_data: None,
+        data,
+    }
+}
+
+/// PE32+ x86-64 image with the given sections and data directories as index, RVA and size
+fn build_pe_data(sections: &[TestSection], directories: &[(usize, u32, u32)]) -> Vec<u8> {
+    let mut data = vec![0u8; FILE_ALIGNMENT];
+    data[..2].copy_from_slice(b"MZ");
+    data[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+    data[0x40..0x44].copy_from_slice(b"PE\0\0");
+    data[0x44..0x46].copy_from_slice(&(MachineType::Amd64 as u16).to_le_bytes());
+    data[0x46..0x48].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+    data[0x54..0x56].copy_from_slice(&(OPTIONAL_HEADER_SIZE as u16).to_le_bytes());
+    data[0x56..0x58].copy_from_slice(&0x22u16.to_le_bytes());
+
+    let optional = OPTIONAL_HEADER_OFFSET;
+    data[optional..optional + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+    data[optional + 24..optional + 32].copy_from_slice(&0x140000000u64.to_le_bytes());
+    data[optional + 32..optional + 36].copy_from_slice(&SECTION_ALIGNMENT.to_le_bytes());
+    data[optional + 36..optional + 40].copy_from_slice(&(FILE_ALIGNMENT as u32).to_le_bytes());
+    let size_of_image = SECTION_ALIGNMENT * (sections.len() as u32 + 1);
+    data[optional + 56..optional + 60].copy_from_slice(&size_of_image.to_le_bytes());
+    data[optional + 60..optional + 64].copy_from_slice(&(FILE_ALIGNMENT as u32).to_le_bytes());
+    data[optional + 68..optional + 70].copy_from_slice(&3u16.to_le_bytes());
+    data[optional + 108..optional + 112].copy_from_slice(&16u32.to_le_bytes());
+    for (index, rva, size) in directories {
+        let entry = optional + 112 + 8 * index;
+        data[entry..entry + 4].copy_from_slice(&rva.to_le_bytes());
+        data[entry + 4..entry + 8].copy_from_slice(&size.to_le_bytes());
+    }
+
+    let mut raw_data = FILE_ALIGNMENT;
+    for (idx, section) in sections.iter().enumerate() {
+        let header = optional + OPTIONAL_HEADER_SIZE + 40 * idx;
+        let size = align(section.data.len());
+        data[header..header + section.name.len()].copy_from_slice(section.name.as_bytes());
+        data[header + 8..header + 12].copy_from_slice(&SECTION_ALIGNMENT.to_le_bytes());
+        data[header + 12..header + 16].copy_from_slice(&section.virtual_address.to_le_bytes());
+        data[header + 16..header + 20].copy_from_slice(&(size as u32).to_le_bytes());
+        let pointer = section.pointer_to_raw_data.unwrap_or(raw_data as u32);
+        data[header + 20..header + 24].copy_from_slice(&pointer.to_le_bytes());
+        data[header + 36..header + 40].copy_from_slice(&0x40000040u32.to_le_bytes());
+        raw_data += size;
+    }
+    for section in sections {
+        let start = data.len();
+        data.extend_from_slice(&section.data);
+        data.resize(start + align(section.data.len()), 0);
+    }
+    data
+}
+
+fn align(size: usize) -> usize {
+    (size + FILE_ALIGNMENT - 1) & !(FILE_ALIGNMENT - 1)
+}
+
+fn build_pe(sections: &[TestSection], directories: &[(usize, u32, u32)]) -> PeBinary {
+    PeBinary::new(Blob::new(build_pe_data(sections, directories)).unwrap()).unwrap()
+}
+
+#[test]
+fn raw_data_pointer_near_end_of_address_space_does_not_overflow() {
+    let mut data = section(".idata", 0x1000, vec![0; 0x100]);
+    data.pointer_to_raw_data = Some(0xffffff00);
+    let mut pe = build_pe(&[data], &[(1, 0x1100, 0x28)]);
+    assert!(pe.imports_table().is_err());
+}
+
+/// Resource directory table with numbered entries of id and target
+fn resource_directory(entries: &[(u32, u32)]) -> Vec<u8> {
+    let mut data = vec![0u8; 16];
+    data[14..16].copy_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (id, target) in entries {
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&target.to_le_bytes());
+    }
+    data
+}
+
+/// Resource tree with `count` entries per level, all pointing to the same directory of the
+/// next level and finally to the same data entry
+fn shared_resource_tree(count: u32) -> Vec<u8> {
+    let directory_size = 16 + 8 * count;
+    let level = |id: u32, target: u32| -> Vec<u8> {
+        let entries: Vec<_> = (0..count).map(|idx| (id + idx, target)).collect();
+        resource_directory(&entries)
+    };
+    let mut data = level(10, 0x80000000 | directory_size);
+    data.extend(level(1, 0x80000000 | (2 * directory_size)));
+    data.extend(level(0x409, 3 * directory_size));
+    for value in [0x1000u32, 4, 0, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn resource_tree_is_walked() {
+    let mut pe = build_pe(
+        &[section(".rsrc", 0x1000, shared_resource_tree(1))],
+        &[(2, 0x1000, 0x100)],
+    );
+    let table = pe.resources_table().unwrap();
+    assert_eq!(table.rows.len(), 1);
+    assert_eq!(
+        table.rows[0].content[..6],
+        ["0", "Raw data", "1", "0x0409", "0x00001000", "4"]
+    );
+}
+
+#[test]
+fn shared_resource_directories_are_walked_once() {
+    let mut pe = build_pe(
+        &[section(".rsrc", 0x1000, shared_resource_tree(100))],
+        &[(2, 0x1000, 0x1000)],
+    );
+    assert_eq!(pe.resources_table().unwrap().rows.len(), 100);
+}
+
+#[test]
+fn header_info_leaves_the_certificate_table_to_the_signatures_table() {
+    let mut data = build_pe_data(
+        &[section(".text", 0x1000, vec![0xc3; 16])],
+        &[(CERTIFICATE_DIRECTORY, 0x400, 0x10)],
+    );
+    data.extend_from_slice(&[0xaa; 0x10]);
+    let mut pe = PeBinary::new(Blob::new(data).unwrap()).unwrap();
+    let info = pe.header_info();
+    assert!(info.contains(&(
+        "Authenticode".to_string(),
+        "certificate table of 16 bytes, see Signatures".to_string()
+    )));
+    assert_eq!(pe.header_info(), info);
+    assert!(pe.signatures_table().is_err());
+}
+
+#[test]
+fn zero_filled_section_tail_has_no_file_offset() {
+    let mut pe = build_pe(
+        &[
+            section(".data", 0x1000, vec![1; 0x200]),
+            section(".rdata", 0x2000, vec![2; 0x200]),
+        ],
+        &[],
+    );
+    pe.get_sections().unwrap();
+    assert_eq!(pe.rva_to_offset(0x1100).unwrap(), Some(0x300));
+    // Within the virtual size of .data, but behind its raw data
+    assert_eq!(pe.rva_to_offset(0x1300).unwrap(), None);
+    assert_eq!(pe.rva_to_offset(0x2000).unwrap(), Some(0x400));
+}
+
+// Layout of the test image: checksum and certificate table entry of the optional
+// header, certificate table behind the section data
+const CHECKSUM: usize = 0x98;
+const CERTIFICATE_ENTRY: usize = 0xe8;
+const TABLE_START: usize = 0x400;
+const TABLE_END: usize = 0x410;
+
+fn signed_image() -> Vec<u8> {
+    let mut data = build_pe_data(
+        &[section(".text", 0x1000, vec![0xc3; 16])],
+        &[(CERTIFICATE_DIRECTORY, TABLE_START as u32, 0x10)],
+    );
+    data.extend_from_slice(&[0xaa; 0x10]);
+    data.extend_from_slice(b"trailer!");
+    data
+}
+
+fn digest(data: Vec<u8>) -> Vec<u8> {
+    let binary = PeBinary::new(Blob::new(data).unwrap()).unwrap();
+    authenticode::image_digest(&binary, authenticode::DigestAlgorithm::Sha256)
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn image_digest_skips_checksum_and_certificates() {
+    // SHA-256 of the image without the checksum, the certificate table entry and the table
+    assert_eq!(
+        hex_string(&digest(signed_image())),
+        "c1a1f2cbf72362b45a3e0e92d6e26c03cefc4585e04b9aab44f5cb236d0788be"
+    );
+}
+
+#[test]
+fn image_digest_covers_everything_else() {
+    let original = digest(signed_image());
+    let mut data = signed_image();
+    data[CHECKSUM] ^= 0xff;
+    data[TABLE_START] ^= 0xff;
+    assert_eq!(digest(data), original);
+    for offset in [
+        CHECKSUM + 4,
+        CERTIFICATE_ENTRY + 8,
+        TABLE_START - 1,
+        TABLE_END,
+    ] {
+        let mut data = signed_image();
+        data[offset] ^= 0xff;
+        assert_ne!(digest(data), original);
+    }
+}
+
+fn words(values: &[u64]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+#[test]
+fn base_relocations_before_a_broken_block_are_kept() {
+    let mut relocations = Vec::new();
+    // HIGHADJ with its parameter in the next slot, DIR64 and padding
+    relocations.extend_from_slice(&0x2000u32.to_le_bytes());
+    relocations.extend_from_slice(&16u32.to_le_bytes());
+    for entry in [0x4010u16, 0x1234, 0xa020, 0] {
+        relocations.extend_from_slice(&entry.to_le_bytes());
+    }
+    // Block larger than the directory
+    relocations.extend_from_slice(&0x3000u32.to_le_bytes());
+    relocations.extend_from_slice(&0x1000u32.to_le_bytes());
+    relocations.extend_from_slice(&0xa000u16.to_le_bytes());
+    let mut data = vec![0; 0x20];
+    data.extend(words(&[0x140002000]));
+    let mut pe = build_pe(
+        &[
+            section(".reloc", 0x1000, relocations),
+            section(".data", 0x2000, data),
+        ],
+        &[(5, 0x1000, 26)],
+    );
+    let table = pe.base_relocations_table().unwrap();
+    let rows: Vec<_> = table.rows.iter().map(|row| &row.content[1..]).collect();
+    assert_eq!(
+        rows,
+        [
+            ["0x00002000", "0x00002010", "HIGHADJ", "0x1234"],
+            ["0x00002000", "0x00002020", "DIR64", "0x140002000"]
+        ]
+    );
+}
+
+#[test]
+fn tls_callbacks_end_at_the_terminator() {
+    let mut tls = words(&[0, 0, 0, 0x140001100, 0]);
+    tls.resize(0x100, 0);
+    tls.extend(words(&[0x140001200, 0x140002000, 0, 0x140001300]));
+    let mut pe = build_pe(&[section(".tls", 0x1000, tls)], &[(9, 0x1000, 40)]);
+    let table = pe.tls_table().unwrap();
+    let callbacks: Vec<_> = table
+        .rows
+        .iter()
+        .filter(|row| row.content[1] == "Callback")
+        .map(|row| row.content[2].as_str())
+        .collect();
+    assert_eq!(
+        callbacks,
+        [
+            "0x140001200 (RVA 0x00001200, section .tls)",
+            "0x140002000 (RVA 0x00002000, section not mapped)"
+        ]
+    );
+}
+
+#[test]
+fn load_config_fields_beyond_its_size_are_zero() {
+    // The size covers the security cookie but not the SEH handler table behind it
+    let mut load_config = vec![0; 0x100];
+    load_config[..4].copy_from_slice(&96u32.to_le_bytes());
+    load_config[88..96].copy_from_slice(&0x2b992ddfa232u64.to_le_bytes());
+    load_config[96..104].copy_from_slice(&0x140001000u64.to_le_bytes());
+    load_config[104..112].copy_from_slice(&1u64.to_le_bytes());
+    load_config[144..148].copy_from_slice(&0x500u32.to_le_bytes());
+    let mut pe = build_pe(
+        &[section(".rdata", 0x1000, load_config)],
+        &[(LOAD_CONFIG_DIRECTORY, 0x1000, 0x100)],
+    );
+    pe.get_sections().unwrap();
+    let load_config = load_config::parse_load_config(&pe).unwrap().unwrap();
+    assert_eq!(load_config.size, 96);
+    assert_eq!(load_config.security_cookie, 0x2b992ddfa232);
+    assert_eq!(load_config.se_handler_table, 0);
+    assert_eq!(load_config.se_handler_count, 0);
+    assert_eq!(load_config.guard_flags, 0);
+    assert!(load_config.se_handlers.is_empty());
+}
+
+/// DOS header, stub and Rich header of an x86-64 image built by Visual Studio 2010
+fn rich_image() -> Vec<u8> {
+    let mut data = vec![0u8; 0x40];
+    data[..0x1a].copy_from_slice(b"MZ\x90\0\x03\0\0\0\x04\0\0\0\xff\xff\0\0\xb8\0\0\0\0\0\0\0@\0");
+    data[0x3c..0x40].copy_from_slice(&0xf8u32.to_le_bytes());
+    data.extend_from_slice(b"\x0e\x1f\xba\x0e\0\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21");
+    data.extend_from_slice(b"This program cannot be run in DOS mode.\r\r\n$\0\0\0\0\0\0\0");
+    for value in [
+        0x7660faa3u32,
+        0x250e9be7,
+        0x250e9be7,
+        0x250e9be7,
+        0x2596d574,
+        0x250e9be6,
+        0x25a506fc,
+        0x250e9bc6,
+        0x25a406fc,
+        0x250e9b91,
+        0x259006fc,
+        0x250e9bee,
+        0x259de3ee,
+        0x250e9be2,
+        0x250f9be7,
+        0x250e9bb8,
+        0x25a006fc,
+        0x250e9be6,
+        0x259406fc,
+        0x250e9be6,
+        0x259306fc,
+        0x250e9be6,
+        0x68636952,
+        0x250e9be7,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn rich_header_is_decoded_and_checked() {
+    let blob = Blob::new(rich_image()).unwrap();
+    let rich = rich::parse_rich_header(&blob, 0xf8).unwrap().unwrap();
+    assert_eq!((rich.offset, rich.key), (0x80, 0x250e9be7));
+    let entries: Vec<_> = rich
+        .entries
+        .iter()
+        .map(|entry| (entry.product_id, entry.build, entry.count))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (0x98, 20115, 1),
+            (0xab, 40219, 33),
+            (0xaa, 40219, 118),
+            (0x9e, 40219, 9),
+            (0x93, 30729, 5),
+            (0x1, 0, 95),
+            (0xae, 40219, 1),
+            (0x9a, 40219, 1),
+            (0x9d, 40219, 1)
+        ]
+    );
+    assert!(rich.is_valid());
+}
+
+#[test]
+fn tampered_rich_header_fails_the_checksum() {
+    // One object less for the first compiler entry
+    let mut data = rich_image();
+    data[0x9c] ^= 1;
+    let blob = Blob::new(data).unwrap();
+    let rich = rich::parse_rich_header(&blob, 0xf8).unwrap().unwrap();
+    assert_eq!(rich.entries[1].count, 32);
+    assert!(!rich.is_valid());
+    assert!(rich.summary().contains("checksum MISMATCH"));
+}
+
+/// Managed image with the given metadata streams as raw name and data, the CLI header and the
+/// metadata root are placed in front of the streams
+fn dotnet_image(streams: &[(&[u8], Vec<u8>)]) -> PeBinary {
+    const ROOT: usize = 0x48;
+    let mut data = vec![0u8; ROOT];
+    data[..4].copy_from_slice(&72u32.to_le_bytes());
+    data[4..6].copy_from_slice(&2u16.to_le_bytes());
+    data[6..8].copy_from_slice(&5u16.to_le_bytes());
+    data[8..12].copy_from_slice(&(0x1000 + ROOT as u32).to_le_bytes());
+    data.extend_from_slice(b"BSJB\x01\0\x01\0\0\0\0\0\x0c\0\0\0v4.0.30319\0\0\0\0");
+    data.extend_from_slice(&(streams.len() as u16).to_le_bytes());
+    let headers_size: usize = streams
+        .iter()
+        .map(|(name, _)| 8 + (name.len() + 4) / 4 * 4)
+        .sum();
+    let mut stream_offset = data.len() - ROOT + headers_size;
+    for (name, stream) in streams {
+        data.extend_from_slice(&(stream_offset as u32).to_le_bytes());
+        data.extend_from_slice(&(stream.len() as u32).to_le_bytes());
+        data.extend_from_slice(name);
+        data.resize(data.len() + 4 - name.len() % 4, 0);
+        stream_offset += stream.len();
+    }
+    for (_, stream) in streams {
+        data.extend_from_slice(stream);
+    }
+    let size = (data.len() - ROOT) as u32;
+    data[12..16].copy_from_slice(&size.to_le_bytes());
+    build_pe(&[section(".text", 0x1000, data)], &[(14, 0x1000, 72)])
+}
+
+/// "#~" stream with the row counts of the present tables followed by the rows
+fn table_stream(heap_sizes: u8, rows: &[(usize, u32)], data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0, 0, 0, 0, 2, 0, heap_sizes, 1];
+    let valid = rows
+        .iter()
+        .fold(0u64, |valid, (table, _)| valid | 1 << table);
+    stream.extend_from_slice(&valid.to_le_bytes());
+    stream.extend_from_slice(&0u64.to_le_bytes());
+    for (_, count) in rows {
+        stream.extend_from_slice(&count.to_le_bytes());
+    }
+    stream.extend_from_slice(data);
+    stream
+}
+
+const DOTNET_STRINGS: &[u8] = b"\0System\0Object\0Program\0App\0mscorlib\0";
+
+#[test]
+fn dotnet_index_sizes_follow_heap_sizes_and_row_counts() {
+    let mut rows = Vec::new();
+    // TypeRef: resolution scope AssemblyRef 1, two byte coded index as all scopes are small
+    rows.extend_from_slice(&(1u16 << 2 | 2).to_le_bytes());
+    rows.extend_from_slice(&8u32.to_le_bytes());
+    rows.extend_from_slice(&1u32.to_le_bytes());
+    // TypeDef: extends TypeRef 1, four byte coded index as TypeSpec has 0x4000 rows
+    rows.extend_from_slice(&0x100001u32.to_le_bytes());
+    rows.extend_from_slice(&15u32.to_le_bytes());
+    rows.extend_from_slice(&23u32.to_le_bytes());
+    rows.extend_from_slice(&(1u32 << 2 | 1).to_le_bytes());
+    rows.extend_from_slice(&[1, 0, 1, 0]);
+    rows.resize(rows.len() + 2 * 0x4000, 0);
+    // AssemblyRef: version 4.0.0.0, no public key, name and culture, no hash
+    rows.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    rows.extend_from_slice(&27u32.to_le_bytes());
+    rows.extend_from_slice(&[0; 6]);
+    let mut pe = dotnet_image(&[
+        (
+            b"#~",
+            table_stream(
+                0x1,
+                &[(0x01, 1), (0x02, 1), (0x1b, 0x4000), (0x23, 1)],
+                &rows,
+            ),
+        ),
+        (b"#Strings", DOTNET_STRINGS.to_vec()),
+        (b"#Blob", vec![0; 4]),
+    ]);
+    pe.get_dotnet().unwrap();
+    let dotnet = pe.dotnet.as_ref().unwrap();
+    let type_ref = &dotnet.type_refs[0];
+    assert_eq!(
+        (
+            type_ref.scope.as_str(),
+            type_ref.namespace.as_str(),
+            type_ref.name.as_str()
+        ),
+        ("mscorlib", "System", "Object")
+    );
+    let type_def = &dotnet.type_defs[0];
+    assert_eq!(type_def.full_name(), "App.Program");
+    assert_eq!(type_def.extends, "System.Object");
+    assert_eq!(dotnet.assembly_refs[0].name, "mscorlib");
+    assert_eq!(dotnet.assembly_refs[0].details(), "version 4.0.0.0");
+}
+
+#[test]
+fn broken_dotnet_entries_are_marked() {
+    let mut rows = Vec::new();
+    // TypeRef: a string index beyond the heap, then a scope referencing a missing TypeRef
+    rows.extend_from_slice(&[0, 0, 0xff, 0x7f, 1, 0]);
+    rows.extend_from_slice(&[(5 << 2 | 3), 0, 8, 0, 1, 0]);
+    let mut user_strings = b"\0\x0bH\0e\0l\0l\0o\0\0".to_vec();
+    // Invalid length prefix
+    user_strings.extend_from_slice(&[0xff, 0, 0, 0]);
+    let mut pe = dotnet_image(&[
+        // Name that is no valid UTF-8, only its raw length counts for the padding
+        (b"#\xff\xff", vec![]),
+        (b"#~", table_stream(0, &[(0x01, 2)], &rows)),
+        (b"#Strings", DOTNET_STRINGS.to_vec()),
+        (b"#US", user_strings),
+    ]);
+    pe.get_dotnet().unwrap();
+    let dotnet = pe.dotnet.as_ref().unwrap();
+    assert_eq!(dotnet.streams.len(), 4);
+    assert_eq!(dotnet.streams[3].name, "#US");
+    assert_eq!(dotnet.user_strings, ["Hello"]);
+    let type_refs: Vec<_> = dotnet
+        .type_refs
+        .iter()
+        .map(|type_ref| (type_ref.scope.as_str(), type_ref.name.as_str()))
+        .collect();
+    assert_eq!(
+        type_refs,
+        [("", "invalid string"), ("invalid reference", "Object")]
+    );
+}
+
+#[test]
+fn unwind_codes_take_one_to_three_slots() {
+    let mut functions = Vec::new();
+    for (begin, end, unwind_data) in [(0x1000u32, 0x1040u32, 0x3000u32), (0x1040, 0x1080, 0x31fc)] {
+        for value in [begin, end, unwind_data] {
+            functions.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    // Version 1 with an exception handler, a 16 byte prolog and seven slots of codes
+    let mut unwind_info = vec![0x09, 0x10, 7, 0];
+    for slot in [
+        [0x10, 0x11],
+        [0x45, 0x23],
+        [0x01, 0x00],
+        [0x0c, 0x34],
+        [0x10, 0x00],
+        [0x08, 0x12],
+        [0x04, 0x50],
+    ] {
+        unwind_info.extend_from_slice(&slot);
+    }
+    // The handler follows the codes padded to an even number of slots
+    unwind_info.extend_from_slice(&[0; 2]);
+    unwind_info.extend_from_slice(&0x1100u32.to_le_bytes());
+    // Unwind information of the second function runs past the end of the file
+    unwind_info.resize(0x1fc, 0);
+    unwind_info.extend_from_slice(&[0x01, 0x04, 1, 0]);
+    let mut pe = build_pe(
+        &[
+            section(".text", 0x1000, vec![0xc3; 0x80]),
+            section(".pdata", 0x2000, functions),
+            section(".xdata", 0x3000, unwind_info),
+        ],
+        &[(3, 0x2000, 24)],
+    );
+    let table = pe.exceptions_table().unwrap();
+    assert_eq!(
+        table.rows[0].content[5..],
+        [
+            "EHANDLER",
+            "16",
+            "",
+            "0x10: alloc 0x12345; 0x0c: save rbx at rsp+0x80; 0x08: alloc 0x10; 0x04: push rbp",
+            "handler 0x00001100, data 0x00003018"
+        ]
+    );
+    assert_eq!(
+        table.rows[1].content[1..5],
+        ["0x00001040", "0x00001080", "64", "0x000031fc"]
+    );
+    assert!(table.rows[1].content[5..].iter().all(String::is_empty));
+}
+
+#[test]
+fn overlay_ends_at_the_certificate_table() {
+    // A debug directory larger than the file does not hide the overlay
+    let mut data = build_pe_data(
+        &[section(".text", 0x1000, vec![0xc3; 16])],
+        &[(6, 0x1000, 0x10000), (CERTIFICATE_DIRECTORY, 0x420, 0x10)],
+    );
+    data.extend_from_slice(b"PK\x03\x04 payload ...............");
+    data.extend_from_slice(&[0xaa; 0x10]);
+    let mut pe = PeBinary::new(Blob::new(data).unwrap()).unwrap();
+    let overlay = pe.overlay().unwrap().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (0x400, 0x20));
+    assert_eq!(overlay.file_type, "ZIP archive");
+}
+
+#[test]
+fn overlay_follows_a_certificate_table_behind_the_image() {
+    let mut data = build_pe_data(
+        &[section(".text", 0x1000, vec![0xc3; 16])],
+        &[(CERTIFICATE_DIRECTORY, 0x400, 0x10)],
+    );
+    data.extend_from_slice(&[0xaa; 0x10]);
+    data.extend_from_slice(&[0; 0x30]);
+    let mut pe = PeBinary::new(Blob::new(data).unwrap()).unwrap();
+    let overlay = pe.overlay().unwrap().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (0x410, 0x30));
+    assert_eq!(overlay.file_type, "zero padding");
+    assert!(build_pe(&[section(".text", 0x1000, vec![0xc3; 16])], &[])
+        .overlay()
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,61 @@
+use super::{PeBinary, Result};
+
+const TLS_DIRECTORY: usize = 9;
+
+/// The thread local storage directory, all addresses are virtual addresses
+pub struct TlsDirectory {
+    pub(super) start_address_of_raw_data: u64,
+    pub(super) end_address_of_raw_data: u64,
+    pub(super) address_of_index: u64,
+    pub(super) address_of_callbacks: u64,
+    pub(super) size_of_zero_fill: u32,
+    pub(super) characteristics: u32,
+    // Callbacks run before the entry point whenever a thread or the process starts
+    pub(super) callbacks: Vec<u64>,
+}
+
+/// Decode the TLS directory and its callback list, sections have to be loaded
+pub(super) fn parse_tls(binary: &PeBinary) -> Result<Option<TlsDirectory>> {
+    let Some(directory) = binary.data_directory(TLS_DIRECTORY) else {
+        return Ok(None);
+    };
+    let Some(offset) = binary.rva_to_offset(directory.virtual_address)? else {
+        return Ok(None);
+    };
+    let blob = &binary.blob;
+    let is_64 = binary.is_64();
+    let word_size = if is_64 { 8 } else { 4 };
+    let word = |offset: usize| -> Result<u64> {
+        if is_64 {
+            Ok(blob.get_u64(offset)?)
+        } else {
+            Ok(blob.get_u32(offset)? as u64)
+        }
+    };
+    let mut tls = TlsDirectory {
+        start_address_of_raw_data: word(offset)?,
+        end_address_of_raw_data: word(offset + word_size)?,
+        address_of_index: word(offset + 2 * word_size)?,
+        address_of_callbacks: word(offset + 3 * word_size)?,
+        size_of_zero_fill: blob.get_u32(offset + 4 * word_size)?,
+        characteristics: blob.get_u32(offset + 4 * word_size + 4)?,
+        callbacks: Vec::new(),
+    };
+
+    // The callback list is a zero terminated array of virtual addresses
+    let image_base = binary.image_base();
+    if tls.address_of_callbacks != 0 {
+        let rva = tls.address_of_callbacks.wrapping_sub(image_base) as u32;
+        if let Some(mut idx) = binary.rva_to_offset(rva)? {
+            loop {
+                let callback = word(idx)?;
+                if callback == 0 {
+                    break;
+                }
+                tls.callbacks.push(callback);
+                idx += word_size;
+            }
+        }
+    }
+    Ok(Some(tls))
+}
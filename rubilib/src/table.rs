@@ -7,8 +7,43 @@ use TableType::*;
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TableType {
     ElfSectionHeader,
+    ElfProgramHeaders,
+    ElfSegmentMapping,
     ElfSymbols,
     ElfDynamicSymbols,
+    ElfDynamic,
+    ElfRelocations,
+    ElfPlt,
+    ElfNotes,
+    ElfVersionNeeds,
+    ElfVersionDefinitions,
+    PeSectionHeaders,
+    PeImports,
+    PeExports,
+    PeResources,
+    PeResourceStrings,
+    PeManifest,
+    PeSignatures,
+    PeBaseRelocations,
+    PeTls,
+    PeLoadConfig,
+    PeDebug,
+    PeRichHeader,
+    PeDotNet,
+    PeExceptions,
+    CoffSectionHeaders,
+    CoffRelocations,
+    CoffSymbols,
+    CoffStrings,
+    MachOLoadCommands,
+    MachOSegments,
+    MachOSections,
+    MachOSymbols,
+    MachODylibs,
+    MachOFixups,
+    MachOArchitectures,
+    ArchiveMembers,
+    ArchiveSymbols,
     Hex,
 }
 
@@ -16,8 +51,43 @@ impl Display for TableType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ElfSectionHeader => write!(f, "ELF section headers"),
+            ElfProgramHeaders => write!(f, "ELF program headers"),
+            ElfSegmentMapping => write!(f, "ELF section to segment mapping"),
             ElfSymbols => write!(f, "ELF symbol table"),
             ElfDynamicSymbols => write!(f, "ELF dynamic symbols table"),
+            ElfDynamic => write!(f, "ELF dynamic section"),
+            ElfRelocations => write!(f, "ELF relocations"),
+            ElfPlt => write!(f, "ELF PLT entries"),
+            ElfNotes => write!(f, "ELF notes"),
+            ElfVersionNeeds => write!(f, "ELF version needs"),
+            ElfVersionDefinitions => write!(f, "ELF version definitions"),
+            PeSectionHeaders => write!(f, "PE section headers"),
+            PeImports => write!(f, "PE imports"),
+            PeExports => write!(f, "PE exports"),
+            PeResources => write!(f, "PE resources"),
+            PeResourceStrings => write!(f, "PE string table resources"),
+            PeManifest => write!(f, "PE manifest"),
+            PeSignatures => write!(f, "PE authenticode signatures"),
+            PeBaseRelocations => write!(f, "PE base relocations"),
+            PeTls => write!(f, "PE thread local storage"),
+            PeLoadConfig => write!(f, "PE load configuration"),
+            PeDebug => write!(f, "PE debug directory"),
+            PeRichHeader => write!(f, "PE rich header"),
+            PeDotNet => write!(f, "PE .NET metadata"),
+            PeExceptions => write!(f, "PE exception directory"),
+            CoffSectionHeaders => write!(f, "COFF section headers"),
+            CoffRelocations => write!(f, "COFF relocations"),
+            CoffSymbols => write!(f, "COFF symbol table"),
+            CoffStrings => write!(f, "COFF string table"),
+            MachOLoadCommands => write!(f, "Mach-O load commands"),
+            MachOSegments => write!(f, "Mach-O segments"),
+            MachOSections => write!(f, "Mach-O sections"),
+            MachOSymbols => write!(f, "Mach-O symbol table"),
+            MachODylibs => write!(f, "Mach-O dynamic libraries"),
+            MachOFixups => write!(f, "Mach-O chained fixups"),
+            MachOArchitectures => write!(f, "Mach-O universal binary architectures"),
+            ArchiveMembers => write!(f, "Archive members"),
+            ArchiveSymbols => write!(f, "Archive symbol index"),
             Hex => write!(f, "HEX table"),
         }
     }
@@ -33,7 +103,8 @@ pub struct Table {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RowAction {
     None,
-    View,
+    // Show `size` bytes at file `offset` as hex dump
+    View { offset: usize, size: usize },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,11 +1,34 @@
 use app::*;
-use axum::Router;
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
 use fileserv::file_and_error_handler;
 use leptos::prelude::*;
 use leptos_axum::{generate_route_list, LeptosRoutes};
 
 pub mod fileserv;
 
+/// Serve the overlay of the current binary as file download
+async fn download_overlay() -> impl IntoResponse {
+    match overlay_data() {
+        Some(data) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"overlay.bin\"",
+                ),
+            ],
+            data,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 #[tokio::main]
 async fn main() {
     simple_logger::init_with_level(log::Level::Debug).expect("couldn't initialize logging");
@@ -26,6 +49,7 @@ async fn main() {
             let leptos_options = leptos_options.clone();
             move || shell(leptos_options.clone())
         })
+        .route("/overlay", get(download_overlay))
         .fallback(file_and_error_handler)
         .with_state(leptos_options);
 